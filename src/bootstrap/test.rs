@@ -1272,6 +1272,10 @@ impl Step for Compiletest {
             cmd.env("RUSTC_PROFILER_SUPPORT", "1");
         }
 
+        if builder.config.rustc_parallel {
+            cmd.env("RUSTC_PARALLEL_COMPILER", "1");
+        }
+
         let tmp = builder.out.join("tmp");
         std::fs::create_dir_all(&tmp).unwrap();
         cmd.env("RUST_TEST_TMPDIR", tmp);
@@ -1,8 +1,11 @@
 use measureme::{event_id::SEPARATOR_BYTE, EventId, StringComponent, StringId};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::profiling::{SelfProfiler, TimingGuard};
+use rustc_middle::ty::query::LlvmFunctionTimes;
 use std::ffi::{c_void, CStr};
 use std::os::raw::c_char;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 fn llvm_args_to_string_id(profiler: &SelfProfiler, pass_name: &str, ir_name: &str) -> EventId {
     let pass_name = profiler.get_or_alloc_cached_string(pass_name);
@@ -19,25 +22,93 @@ fn llvm_args_to_string_id(profiler: &SelfProfiler, pass_name: &str, ir_name: &st
     EventId::from_label(profiler.alloc_string(components.as_slice()))
 }
 
+/// The time LLVM passes spend on each function of a module, for
+/// `-Z self-profile-summary`.
+pub struct LlvmFunctionTimer {
+    /// The names of the functions defined in the module.
+    functions: FxHashSet<String>,
+    /// The running passes, innermost last: the function each runs on or is
+    /// nested in, when it started, and how long the passes nested in it took.
+    stack: Vec<(Option<String>, Instant, Duration)>,
+    times: FxHashMap<String, Duration>,
+    sink: Arc<LlvmFunctionTimes>,
+}
+
+impl LlvmFunctionTimer {
+    pub fn new(functions: FxHashSet<String>, sink: Arc<LlvmFunctionTimes>) -> Self {
+        Self { functions, stack: Vec::new(), times: Default::default(), sink }
+    }
+
+    fn before_pass_callback(&mut self, ir_name: &str) {
+        // Passes on call graph SCCs name them as a list of their functions in
+        // parentheses. Those on a single function are attributed to it, and
+        // all other passes that are not on a function, e.g. loop passes, to
+        // the function they are nested in.
+        let ir_name = ir_name.trim_matches(&['(', ')'][..]);
+        let function = if self.functions.contains(ir_name) {
+            Some(ir_name.to_owned())
+        } else {
+            self.stack.last().and_then(|(function, ..)| function.clone())
+        };
+        self.stack.push((function, Instant::now(), Duration::default()));
+    }
+
+    fn after_pass_callback(&mut self) {
+        let (function, start, nested) = self.stack.pop().expect("no LLVM pass to finish");
+        let elapsed = start.elapsed();
+        if let Some((_, _, parent_nested)) = self.stack.last_mut() {
+            *parent_nested += elapsed;
+        }
+        if let Some(function) = function {
+            *self.times.entry(function).or_default() +=
+                elapsed.checked_sub(nested).unwrap_or_default();
+        }
+    }
+}
+
+impl Drop for LlvmFunctionTimer {
+    fn drop(&mut self) {
+        let mut sink = self.sink.lock().unwrap();
+        for (function, time) in self.times.drain() {
+            *sink.entry(function).or_default() += time;
+        }
+    }
+}
+
 pub struct LlvmSelfProfiler<'a> {
-    profiler: Arc<SelfProfiler>,
+    profiler: Option<Arc<SelfProfiler>>,
     stack: Vec<TimingGuard<'a>>,
-    llvm_pass_event_kind: StringId,
+    llvm_pass_event_kind: Option<StringId>,
+    function_timer: Option<LlvmFunctionTimer>,
 }
 
 impl<'a> LlvmSelfProfiler<'a> {
-    pub fn new(profiler: Arc<SelfProfiler>) -> Self {
-        let llvm_pass_event_kind = profiler.alloc_string("LLVM Pass");
-        Self { profiler, stack: Vec::default(), llvm_pass_event_kind }
+    /// Creates a profiler that records the passes as events of `profiler` and
+    /// their times per function in `function_timer`, if given.
+    pub fn new(
+        profiler: Option<Arc<SelfProfiler>>,
+        function_timer: Option<LlvmFunctionTimer>,
+    ) -> Self {
+        let llvm_pass_event_kind =
+            profiler.as_ref().map(|profiler| profiler.alloc_string("LLVM Pass"));
+        Self { profiler, stack: Vec::default(), llvm_pass_event_kind, function_timer }
     }
 
     fn before_pass_callback(&'a mut self, pass_name: &str, ir_name: &str) {
-        let event_id = llvm_args_to_string_id(&self.profiler, pass_name, ir_name);
+        if let Some(function_timer) = &mut self.function_timer {
+            function_timer.before_pass_callback(ir_name);
+        }
+        if let (Some(profiler), Some(event_kind)) = (&self.profiler, self.llvm_pass_event_kind) {
+            let event_id = llvm_args_to_string_id(profiler, pass_name, ir_name);
 
-        self.stack.push(TimingGuard::start(&self.profiler, self.llvm_pass_event_kind, event_id));
+            self.stack.push(TimingGuard::start(profiler, event_kind, event_id));
+        }
     }
     fn after_pass_callback(&mut self) {
         self.stack.pop();
+        if let Some(function_timer) = &mut self.function_timer {
+            function_timer.after_pass_callback();
+        }
     }
 }
 
@@ -2,7 +2,8 @@ use crate::attributes;
 use crate::back::bytecode;
 use crate::back::lto::ThinBuffer;
 use crate::back::profiling::{
-    selfprofile_after_pass_callback, selfprofile_before_pass_callback, LlvmSelfProfiler,
+    selfprofile_after_pass_callback, selfprofile_before_pass_callback, LlvmFunctionTimer,
+    LlvmSelfProfiler,
 };
 use crate::base;
 use crate::common;
@@ -16,7 +17,7 @@ use crate::ModuleLlvm;
 use log::debug;
 use rustc_codegen_ssa::back::write::{BitcodeSection, CodegenContext, EmitObj, ModuleConfig};
 use rustc_codegen_ssa::traits::*;
-use rustc_codegen_ssa::{CompiledModule, ModuleCodegen, RLIB_BYTECODE_EXTENSION};
+use rustc_codegen_ssa::{CompiledModule, ModuleCodegen, ModuleKind, RLIB_BYTECODE_EXTENSION};
 use rustc_data_structures::small_c_str::SmallCStr;
 use rustc_errors::{FatalError, Handler};
 use rustc_fs_util::{link_or_copy, path_to_c_string};
@@ -27,10 +28,11 @@ use rustc_session::config::{self, Lto, OutputType, Passes, Sanitizer, SwitchWith
 use rustc_session::Session;
 
 use libc::{c_char, c_int, c_uint, c_void, size_t};
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::ptr;
 use std::slice;
 use std::str;
 use std::sync::Arc;
@@ -72,11 +74,21 @@ pub fn write_output_file(
     pm: &llvm::PassManager<'ll>,
     m: &'ll llvm::Module,
     output: &Path,
+    dwo_output: Option<&Path>,
     file_type: llvm::FileType,
 ) -> Result<(), FatalError> {
     unsafe {
         let output_c = path_to_c_string(output);
-        let result = llvm::LLVMRustWriteOutputFile(target, pm, m, output_c.as_ptr(), file_type);
+        let dwo_output_c = dwo_output.map(path_to_c_string);
+        let dwo_output_ptr = dwo_output_c.as_ref().map_or(ptr::null(), |s| s.as_ptr());
+        let result = llvm::LLVMRustWriteOutputFile(
+            target,
+            pm,
+            m,
+            output_c.as_ptr(),
+            dwo_output_ptr,
+            file_type,
+        );
         result.into_result().map_err(|()| {
             let msg = format!("could not write output to {}", output.display());
             llvm_err(handler, &msg)
@@ -376,12 +388,22 @@ pub(crate) unsafe fn optimize_with_new_llvm_pass_manager(
         None
     };
 
-    let llvm_selfprofiler = if cgcx.prof.llvm_recording_enabled() {
-        let mut llvm_profiler = LlvmSelfProfiler::new(cgcx.prof.get_self_profiler().unwrap());
-        &mut llvm_profiler as *mut _ as *mut c_void
+    let profiler =
+        if cgcx.prof.llvm_recording_enabled() { cgcx.prof.get_self_profiler() } else { None };
+    let function_timer = cgcx.llvm_function_times.as_ref().map(|sink| {
+        let functions = base::iter_functions(module.module_llvm.llmod())
+            .map(|llfn| String::from_utf8_lossy(llvm::get_value_name(llfn)).into_owned())
+            .collect();
+        LlvmFunctionTimer::new(functions, sink.clone())
+    });
+    let mut llvm_profiler = if profiler.is_some() || function_timer.is_some() {
+        Some(LlvmSelfProfiler::new(profiler, function_timer))
     } else {
-        std::ptr::null_mut()
+        None
     };
+    let llvm_selfprofiler = llvm_profiler
+        .as_mut()
+        .map_or(std::ptr::null_mut(), |llvm_profiler| llvm_profiler as *mut _ as *mut c_void);
 
     // FIXME: NewPM doesn't provide a facility to pass custom InlineParams.
     // We would have to add upstream support for this first, before we can support
@@ -644,6 +666,15 @@ pub(crate) unsafe fn codegen(
         let bc_out = cgcx.output_filenames.temp_path(OutputType::Bitcode, module_name);
         let obj_out = cgcx.output_filenames.temp_path(OutputType::Object, module_name);
 
+        // Only regular modules carry debuginfo, and only real object code can
+        // have it split out into a separate `.dwo` file.
+        let dwo_out = match config.emit_obj {
+            EmitObj::ObjectCode(_) if module.kind == ModuleKind::Regular => {
+                cgcx.output_filenames.split_dwarf_path(cgcx.split_debuginfo, module_name)
+            }
+            _ => None,
+        };
+
         if config.bitcode_needed() {
             let _timer = cgcx
                 .prof
@@ -748,7 +779,15 @@ pub(crate) unsafe fn codegen(
                 llmod
             };
             with_codegen(tm, llmod, config.no_builtins, |cpm| {
-                write_output_file(diag_handler, tm, cpm, llmod, &path, llvm::FileType::AssemblyFile)
+                write_output_file(
+                    diag_handler,
+                    tm,
+                    cpm,
+                    llmod,
+                    &path,
+                    None,
+                    llvm::FileType::AssemblyFile,
+                )
             })?;
         }
 
@@ -764,6 +803,7 @@ pub(crate) unsafe fn codegen(
                         cpm,
                         llmod,
                         &obj_out,
+                        dwo_out.as_deref(),
                         llvm::FileType::ObjectFile,
                     )
                 })?;
@@ -791,6 +831,7 @@ pub(crate) unsafe fn codegen(
 
     Ok(module.into_compiled_module(
         config.emit_obj != EmitObj::None,
+        dwo_out.is_some(),
         config.emit_bc,
         config.emit_bc_compressed,
         &cgcx.output_filenames,
@@ -856,8 +897,6 @@ pub unsafe fn with_llvm_pmb(
     prepare_for_thin_lto: bool,
     f: &mut dyn FnMut(&llvm::PassManagerBuilder),
 ) {
-    use std::ptr;
-
     // Create the PassManagerBuilder for LLVM. We configure it with
     // reasonable defaults and prepare it to actually populate the pass
     // manager.
@@ -972,3 +1011,27 @@ fn create_msvc_imps(
         symbol_name.starts_with(b"__llvm_profile_")
     }
 }
+
+/// Reads the name and size of every sized symbol in the object file at `path`.
+/// Object formats which don't record symbol sizes yield no symbols at all.
+pub(crate) fn object_symbol_sizes(path: &Path) -> Result<Vec<(String, u64)>, String> {
+    unsafe {
+        let buf = path_to_c_string(path);
+        let mb = llvm::LLVMRustCreateMemoryBufferWithContentsOfFile(buf.as_ptr())
+            .ok_or_else(|| format!("error reading object file: '{}'", path.display()))?;
+        let of = llvm::ObjectFile::new(mb)
+            .ok_or_else(|| format!("provided path not an object file: '{}'", path.display()))?;
+
+        let mut symbols = Vec::new();
+        let si = llvm::mk_symbol_iter(of.llof);
+        while llvm::LLVMIsSymbolIteratorAtEnd(of.llof, si.llsi) == llvm::False {
+            let size = llvm::LLVMGetSymbolSize(si.llsi);
+            let name = llvm::LLVMGetSymbolName(si.llsi);
+            if size != 0 && !name.is_null() {
+                symbols.push((CStr::from_ptr(name).to_string_lossy().into_owned(), size));
+            }
+            llvm::LLVMMoveToNextSymbol(si.llsi);
+        }
+        Ok(symbols)
+    }
+}
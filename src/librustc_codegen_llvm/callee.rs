@@ -29,7 +29,11 @@ pub fn get_fn(cx: &CodegenCx<'ll, 'tcx>, instance: Instance<'tcx>) -> &'ll Value
 
     assert!(!instance.substs.needs_infer());
     assert!(!instance.substs.has_escaping_bound_vars());
-    assert!(!instance.substs.has_param_types_or_consts());
+
+    // Instances which only differ in unused generic parameters share their code, so make sure
+    // that every reference to one of them ends up at the same (polymorphized) function.
+    let instance = instance.polymorphize(tcx);
+    assert!(!instance.has_used_param_types_or_consts(tcx));
 
     if let Some(&llfn) = cx.instances.borrow().get(&instance) {
         return llfn;
@@ -36,6 +36,7 @@ use rustc_span::symbol::Symbol;
 use std::any::Any;
 use std::ffi::CStr;
 use std::fs;
+use std::path::Path;
 use std::sync::Arc;
 
 mod back {
@@ -117,6 +118,9 @@ impl ExtraBackendMethods for LlvmCodegenBackend {
     fn target_cpu<'b>(&self, sess: &'b Session) -> &'b str {
         llvm_util::target_cpu(sess)
     }
+    fn object_symbol_sizes(&self, path: &Path) -> Result<Vec<(String, u64)>, String> {
+        back::write::object_symbol_sizes(path)
+    }
 }
 
 impl WriteBackendMethods for LlvmCodegenBackend {
@@ -254,6 +258,19 @@ impl CodegenBackend for LlvmCodegenBackend {
         metadata: EncodedMetadata,
         need_metadata_module: bool,
     ) -> Box<dyn Any> {
+        // LLVM times are measured with pass instrumentation, which only the new
+        // pass manager supports. See `should_use_new_llvm_pass_manager`.
+        if let Some(item_profiler) = &tcx.item_profiler {
+            if llvm_util::get_major_version() < 9
+                || !tcx.sess.opts.debugging_opts.new_llvm_pass_manager
+            {
+                tcx.sess.warn(
+                    "`-Z self-profile-summary` requires `-Z new-llvm-pass-manager` to measure \
+                     the LLVM time of items",
+                );
+                item_profiler.disable_llvm_times();
+            }
+        }
         Box::new(rustc_codegen_ssa::base::codegen_crate(
             LlvmCodegenBackend(()),
             tcx,
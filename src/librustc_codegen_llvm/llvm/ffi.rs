@@ -601,6 +601,8 @@ extern "C" {
 }
 #[repr(C)]
 pub struct SectionIterator<'a>(InvariantOpaque<'a>);
+#[repr(C)]
+pub struct SymbolIterator<'a>(InvariantOpaque<'a>);
 extern "C" {
     pub type Pass;
 }
@@ -910,6 +912,8 @@ extern "C" {
         NameLen: size_t,
         FunctionTy: &'a Type,
     ) -> &'a Value;
+    pub fn LLVMGetFirstFunction(M: &Module) -> Option<&Value>;
+    pub fn LLVMGetNextFunction(Fn: &Value) -> Option<&Value>;
     pub fn LLVMSetFunctionCallConv(Fn: &Value, CC: c_uint);
     pub fn LLVMRustAddAlignmentAttr(Fn: &Value, index: c_uint, bytes: u32);
     pub fn LLVMRustAddDereferenceableAttr(Fn: &Value, index: c_uint, bytes: u64);
@@ -1575,6 +1579,20 @@ extern "C" {
     /// Returns the current section contents as a string buffer.
     pub fn LLVMGetSectionContents(SI: &SectionIterator<'_>) -> *const c_char;
 
+    /// Enumerates the symbols in an object file.
+    pub fn LLVMGetSymbols(ObjFile: &'a ObjectFile) -> &'a mut SymbolIterator<'a>;
+    /// Destroys a symbol iterator.
+    pub fn LLVMDisposeSymbolIterator(SI: &'a mut SymbolIterator<'a>);
+    /// Returns `true` if the symbol iterator is at the end of the symbol
+    /// list.
+    pub fn LLVMIsSymbolIteratorAtEnd(ObjFile: &'a ObjectFile, SI: &SymbolIterator<'a>) -> Bool;
+    /// Moves the symbol iterator to point to the next symbol.
+    pub fn LLVMMoveToNextSymbol(SI: &SymbolIterator<'_>);
+    /// Returns the name of the current symbol.
+    pub fn LLVMGetSymbolName(SI: &SymbolIterator<'_>) -> *const c_char;
+    /// Returns the size of the current symbol, if the object format records one.
+    pub fn LLVMGetSymbolSize(SI: &SymbolIterator<'_>) -> u64;
+
     /// Reads the given file and returns it as a memory buffer. Use
     /// LLVMDisposeMemoryBuffer() to get rid of it.
     pub fn LLVMRustCreateMemoryBufferWithContentsOfFile(
@@ -1985,6 +2003,7 @@ extern "C" {
         PM: &PassManager<'a>,
         M: &'a Module,
         Output: *const c_char,
+        DwoOutput: *const c_char,
         FileType: FileType,
     ) -> LLVMRustResult;
     pub fn LLVMRustOptimizeWithNewPassManager(
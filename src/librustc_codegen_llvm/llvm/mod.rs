@@ -189,6 +189,24 @@ pub fn mk_section_iter(llof: &ffi::ObjectFile) -> SectionIter<'_> {
     unsafe { SectionIter { llsi: LLVMGetSections(llof) } }
 }
 
+// Memory-managed interface to symbol iterators.
+
+pub struct SymbolIter<'a> {
+    pub llsi: &'a mut SymbolIterator<'a>,
+}
+
+impl Drop for SymbolIter<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            LLVMDisposeSymbolIterator(&mut *(self.llsi as *mut _));
+        }
+    }
+}
+
+pub fn mk_symbol_iter(llof: &ffi::ObjectFile) -> SymbolIter<'_> {
+    unsafe { SymbolIter { llsi: LLVMGetSymbols(llof) } }
+}
+
 /// Safe wrapper around `LLVMGetParam`, because segfaults are no fun.
 pub fn get_param(llfn: &Value, index: c_uint) -> &Value {
     unsafe {
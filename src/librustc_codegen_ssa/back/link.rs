@@ -4,7 +4,7 @@ use rustc_hir::def_id::CrateNum;
 use rustc_middle::middle::cstore::{EncodedMetadata, LibSource, NativeLibrary, NativeLibraryKind};
 use rustc_middle::middle::dependency_format::Linkage;
 use rustc_session::config::{
-    self, CFGuard, DebugInfo, OutputFilenames, OutputType, PrintRequest, Sanitizer,
+    self, CFGuard, DebugInfo, OutputFilenames, OutputType, PrintRequest, Sanitizer, SplitDebuginfo,
 };
 use rustc_session::output::{check_file_is_writeable, invalid_output_for_target, out_filename};
 use rustc_session::search_paths::PathKind;
@@ -130,6 +130,11 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(
                     remove(sess, obj);
                 }
             }
+            if !preserve_dwarf_objects(sess) {
+                for obj in codegen_results.modules.iter().filter_map(|m| m.dwarf_object.as_ref()) {
+                    remove(sess, obj);
+                }
+            }
             for obj in codegen_results.modules.iter().filter_map(|m| m.bytecode_compressed.as_ref())
             {
                 remove(sess, obj);
@@ -678,6 +683,47 @@ fn link_natively<'a, B: ArchiveBuilder<'a>>(
             sess.fatal(&format!("failed to run dsymutil: {}", e))
         }
     }
+
+    // With packed split debuginfo the `.dwo` files referenced by the skeleton
+    // units in the output get packaged into a single `.dwp` file next to it,
+    // which is where debuggers will look for it.
+    if sess.split_debuginfo() == SplitDebuginfo::Packed {
+        package_dwarf_objects(sess, out_filename);
+    }
+}
+
+fn package_dwarf_objects(sess: &Session, out_filename: &Path) {
+    let mut dwp_out_filename = out_filename.as_os_str().to_owned();
+    dwp_out_filename.push(".dwp");
+
+    let mut cmd = Command::new("llvm-dwp");
+    cmd.arg("-e").arg(out_filename).arg("-o").arg(&dwp_out_filename);
+
+    // Prefer an `llvm-dwp` bundled with the compiler's sysroot, if any.
+    let mut new_path = sess.host_filesearch(PathKind::All).get_tools_search_paths();
+    if let Some(path) = env::var_os("PATH") {
+        new_path.extend(env::split_paths(&path));
+    }
+    cmd.env("PATH", env::join_paths(new_path).unwrap());
+
+    info!("{:?}", &cmd);
+    match sess.time("run_dwp", || cmd.output()) {
+        Ok(prog) => {
+            if !prog.status.success() {
+                let mut output = prog.stderr.clone();
+                output.extend_from_slice(&prog.stdout);
+                sess.struct_err(&format!(
+                    "packaging split debuginfo with `llvm-dwp` failed: {}",
+                    prog.status
+                ))
+                .note(&format!("{:?}", &cmd))
+                .note(&String::from_utf8_lossy(&output))
+                .emit();
+                sess.abort_if_errors();
+            }
+        }
+        Err(e) => sess.fatal(&format!("failed to run `llvm-dwp`: {}", e)),
+    }
 }
 
 fn link_sanitizer_runtime(sess: &Session, crate_type: config::CrateType, linker: &mut dyn Linker) {
@@ -864,6 +910,23 @@ fn preserve_objects_for_their_debuginfo(sess: &Session) -> bool {
     false
 }
 
+/// Returns a boolean indicating whether the `.dwo` files holding split
+/// debuginfo need to be kept on the filesystem after linking.
+fn preserve_dwarf_objects(sess: &Session) -> bool {
+    match sess.split_debuginfo() {
+        SplitDebuginfo::Off => false,
+        // The skeleton units in the output refer to the `.dwo` files directly.
+        SplitDebuginfo::Unpacked => true,
+        // Once packaged into a `.dwp` the `.dwo` files are no longer needed,
+        // unless we're also producing an archive whose objects refer to them.
+        SplitDebuginfo::Packed => sess
+            .crate_types
+            .borrow()
+            .iter()
+            .any(|&x| x == config::CrateType::Rlib || x == config::CrateType::Staticlib),
+    }
+}
+
 pub fn archive_search_paths(sess: &Session) -> Vec<PathBuf> {
     sess.target_filesearch(PathKind::Native).search_path_dirs()
 }
@@ -1518,7 +1581,7 @@ fn linker_with_args<'a, B: ArchiveBuilder<'a>>(
 
     // OBJECT-FILES-NO, AUDIT-ORDER
     // Pass debuginfo flags down to the linker.
-    cmd.debuginfo();
+    cmd.debuginfo(sess.opts.debugging_opts.strip);
 
     // OBJECT-FILES-NO, AUDIT-ORDER
     // We want to prevent the compiler from accidentally leaking in any system libraries,
@@ -3,6 +3,7 @@ pub mod command;
 pub mod link;
 pub mod linker;
 pub mod lto;
+pub mod mono_report;
 pub mod rpath;
 pub mod symbol_export;
 pub mod write;
@@ -0,0 +1,206 @@
+//! The `--emit=mono-report` output.
+//!
+//! The report is a JSON document listing every monomorphized item of the
+//! crate together with the codegen units it was placed in, its MIR size, its
+//! inlining state and, once codegen has finished, the amount of object code
+//! it turned into. Items are attributed to the crate that defines them, so
+//! instantiations of upstream generics are accounted to the upstream crate
+//! rather than to the crate that happened to instantiate them.
+//!
+//! Object code sizes are read from the symbol tables of the emitted object
+//! files, which means they are only available for object formats that record
+//! symbol sizes (like ELF and wasm). Items that were inlined into all of their
+//! callers don't have a symbol of their own and report no size.
+
+use crate::traits::ExtraBackendMethods;
+use crate::CompiledModule;
+
+use rustc_attr::InlineAttr;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::mir::mono::{CodegenUnit, InstantiationMode, MonoItem};
+use rustc_middle::ty::TyCtxt;
+use rustc_serialize::json;
+use rustc_session::config::{OutputFilenames, OutputType};
+use rustc_session::Session;
+
+use std::collections::BTreeMap;
+use std::fs;
+
+#[derive(RustcEncodable)]
+pub struct MonoReport {
+    crate_name: String,
+    codegen_units: Vec<CodegenUnitEntry>,
+    items: Vec<ItemEntry>,
+    crates: Vec<CrateEntry>,
+}
+
+#[derive(RustcEncodable)]
+struct CodegenUnitEntry {
+    name: String,
+    size_estimate: usize,
+    /// The sum of all symbol sizes in the object file of this codegen unit.
+    object_size: Option<u64>,
+}
+
+#[derive(RustcEncodable)]
+struct ItemEntry {
+    item: String,
+    kind: &'static str,
+    symbol_name: String,
+    defining_crate: String,
+    generic: bool,
+    placements: Vec<Placement>,
+    /// The number of MIR statements, or 1 for statics, global assembly and
+    /// compiler-generated shims.
+    mir_statements: usize,
+    instantiation_mode: &'static str,
+    inline: &'static str,
+    /// The object code size summed over all codegen units the item ended up
+    /// in, or `None` if no sized symbol was found for it.
+    object_size: Option<u64>,
+}
+
+#[derive(RustcEncodable)]
+struct Placement {
+    codegen_unit: String,
+    linkage: String,
+}
+
+#[derive(RustcEncodable)]
+struct CrateEntry {
+    name: String,
+    items: usize,
+    mir_statements: usize,
+    object_size: u64,
+}
+
+impl MonoReport {
+    /// Collects everything about the mono items of `codegen_units` that is
+    /// known before codegen. Object code sizes are filled in by `emit`.
+    pub fn new<'tcx>(tcx: TyCtxt<'tcx>, codegen_units: &[CodegenUnit<'tcx>]) -> MonoReport {
+        let mut placements: FxHashMap<MonoItem<'tcx>, Vec<Placement>> = Default::default();
+        for cgu in codegen_units {
+            for (&mono_item, &(linkage, _)) in cgu.items() {
+                placements.entry(mono_item).or_default().push(Placement {
+                    codegen_unit: cgu.name().to_string(),
+                    linkage: format!("{:?}", linkage),
+                });
+            }
+        }
+
+        let mut items: Vec<_> = placements
+            .into_iter()
+            .map(|(mono_item, mut placements)| {
+                placements.sort_by(|a, b| a.codegen_unit.cmp(&b.codegen_unit));
+
+                let (kind, krate, inline) = match mono_item {
+                    MonoItem::Fn(instance) => {
+                        let def_id = instance.def_id();
+                        let inline = match tcx.codegen_fn_attrs(def_id).inline {
+                            InlineAttr::None => "none",
+                            InlineAttr::Hint => "hint",
+                            InlineAttr::Always => "always",
+                            InlineAttr::Never => "never",
+                        };
+                        ("fn", def_id.krate, inline)
+                    }
+                    MonoItem::Static(def_id) => ("static", def_id.krate, "none"),
+                    MonoItem::GlobalAsm(..) => ("global_asm", LOCAL_CRATE, "none"),
+                };
+                let instantiation_mode = match mono_item.instantiation_mode(tcx) {
+                    InstantiationMode::GloballyShared { .. } => "globally-shared",
+                    InstantiationMode::LocalCopy => "local-copy",
+                };
+
+                ItemEntry {
+                    item: mono_item.to_string(tcx, false),
+                    kind,
+                    symbol_name: mono_item.symbol_name(tcx).name.to_string(),
+                    defining_crate: tcx.crate_name(krate).to_string(),
+                    generic: mono_item.is_generic_fn(),
+                    placements,
+                    mir_statements: mono_item.size_estimate(tcx),
+                    instantiation_mode,
+                    inline,
+                    object_size: None,
+                }
+            })
+            .collect();
+        items.sort_by(|a, b| a.item.cmp(&b.item));
+
+        let mut codegen_units: Vec<_> = codegen_units
+            .iter()
+            .map(|cgu| CodegenUnitEntry {
+                name: cgu.name().to_string(),
+                size_estimate: cgu.size_estimate(),
+                object_size: None,
+            })
+            .collect();
+        codegen_units.sort_by(|a, b| a.name.cmp(&b.name));
+
+        MonoReport {
+            crate_name: tcx.crate_name(LOCAL_CRATE).to_string(),
+            codegen_units,
+            items,
+            crates: Vec::new(),
+        }
+    }
+
+    /// Fills in the object code sizes from the object files of `modules` and
+    /// writes the report to its output path.
+    pub fn emit<B: ExtraBackendMethods>(
+        mut self,
+        backend: &B,
+        sess: &Session,
+        modules: &[CompiledModule],
+        outputs: &OutputFilenames,
+    ) {
+        let mut symbol_sizes: FxHashMap<String, u64> = Default::default();
+        let mut module_sizes: FxHashMap<&str, u64> = Default::default();
+        for module in modules {
+            let path = match module.object {
+                Some(ref path) => path,
+                None => continue,
+            };
+            match backend.object_symbol_sizes(path) {
+                Ok(symbols) => {
+                    let mut module_size = 0;
+                    for (name, size) in symbols {
+                        *symbol_sizes.entry(name).or_default() += size;
+                        module_size += size;
+                    }
+                    module_sizes.insert(&module.name, module_size);
+                }
+                Err(e) => {
+                    sess.warn(&format!("could not measure code size for mono report: {}", e));
+                }
+            }
+        }
+
+        for cgu in &mut self.codegen_units {
+            cgu.object_size = module_sizes.get(&cgu.name[..]).cloned();
+        }
+
+        let mut crates: BTreeMap<String, CrateEntry> = BTreeMap::new();
+        for item in &mut self.items {
+            item.object_size = symbol_sizes.get(&item.symbol_name).cloned();
+
+            let krate = crates.entry(item.defining_crate.clone()).or_insert_with(|| CrateEntry {
+                name: item.defining_crate.clone(),
+                items: 0,
+                mir_statements: 0,
+                object_size: 0,
+            });
+            krate.items += 1;
+            krate.mir_statements += item.mir_statements;
+            krate.object_size += item.object_size.unwrap_or(0);
+        }
+        self.crates = crates.into_iter().map(|(_, krate)| krate).collect();
+
+        let path = outputs.path(OutputType::MonoReport);
+        if let Err(e) = fs::write(&path, json::as_pretty_json(&self).to_string()) {
+            sess.err(&format!("failed to write mono report to `{}`: {}", path.display(), e));
+        }
+    }
+}
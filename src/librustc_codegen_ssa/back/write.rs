@@ -1,6 +1,7 @@
 use super::link::{self, remove};
 use super::linker::LinkerInfo;
 use super::lto::{self, SerializedModule};
+use super::mono_report::MonoReport;
 use super::symbol_export::symbol_name_for_instance_in_crate;
 
 use crate::{
@@ -27,6 +28,7 @@ use rustc_incremental::{
 use rustc_middle::dep_graph::{WorkProduct, WorkProductFileKind, WorkProductId};
 use rustc_middle::middle::cstore::EncodedMetadata;
 use rustc_middle::middle::exported_symbols::SymbolExportLevel;
+use rustc_middle::ty::query::LlvmFunctionTimes;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::cgu_reuse_tracker::CguReuseTracker;
 use rustc_session::config::{
@@ -134,7 +136,11 @@ impl ModuleConfig {
 
         let should_emit_obj = sess.opts.output_types.contains_key(&OutputType::Exe)
             || match kind {
-                ModuleKind::Regular => sess.opts.output_types.contains_key(&OutputType::Object),
+                // The mono report measures code size from the object files.
+                ModuleKind::Regular => {
+                    sess.opts.output_types.contains_key(&OutputType::Object)
+                        || sess.opts.output_types.contains_key(&OutputType::MonoReport)
+                }
                 ModuleKind::Allocator => false,
                 ModuleKind::Metadata => sess.opts.output_types.contains_key(&OutputType::Metadata),
             };
@@ -303,6 +309,7 @@ pub struct CodegenContext<B: WriteBackendMethods> {
     pub target_pointer_width: String,
     pub target_arch: String,
     pub debuginfo: config::DebugInfo,
+    pub split_debuginfo: config::SplitDebuginfo,
 
     // Number of cgus excluding the allocator/metadata modules
     pub total_cgus: usize,
@@ -319,6 +326,9 @@ pub struct CodegenContext<B: WriteBackendMethods> {
     pub cgu_reuse_tracker: CguReuseTracker,
     // Channel back to the main control thread to send messages to
     pub coordinator_send: Sender<Box<dyn Any + Send>>,
+    // Where to record the time LLVM spends on each function, for
+    // `-Z self-profile-summary`
+    pub llvm_function_times: Option<Arc<LlvmFunctionTimes>>,
 }
 
 impl<B: WriteBackendMethods> CodegenContext<B> {
@@ -458,6 +468,7 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
         shared_emitter_main,
         future: coordinator_thread,
         output_filenames: tcx.output_filenames(LOCAL_CRATE),
+        mono_report: None,
     }
 }
 
@@ -479,6 +490,9 @@ fn copy_all_cgu_workproducts_to_incr_comp_cache_dir(
         if let Some(ref path) = module.object {
             files.push((WorkProductFileKind::Object, path.clone()));
         }
+        if let Some(ref path) = module.dwarf_object {
+            files.push((WorkProductFileKind::DwarfObject, path.clone()));
+        }
         if let Some(ref path) = module.bytecode {
             files.push((WorkProductFileKind::Bytecode, path.clone()));
         }
@@ -577,7 +591,11 @@ fn produce_final_output_artifacts(
                 user_wants_objects = true;
                 copy_if_one_unit(OutputType::Object, true);
             }
-            OutputType::Mir | OutputType::Metadata | OutputType::Exe | OutputType::DepInfo => {}
+            OutputType::Mir
+            | OutputType::Metadata
+            | OutputType::Exe
+            | OutputType::DepInfo
+            | OutputType::MonoReport => {}
         }
     }
 
@@ -820,6 +838,7 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
 ) -> Result<WorkItemResult<B>, FatalError> {
     let incr_comp_session_dir = cgcx.incr_comp_session_dir.as_ref().unwrap();
     let mut object = None;
+    let mut dwarf_object = None;
     let mut bytecode = None;
     let mut bytecode_compressed = None;
     for (kind, saved_file) in &module.source.saved_files {
@@ -829,6 +848,13 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
                 object = Some(path.clone());
                 path
             }
+            WorkProductFileKind::DwarfObject => {
+                let path = cgcx
+                    .output_filenames
+                    .temp_path_ext(config::DWARF_OBJECT_EXT, Some(&module.name));
+                dwarf_object = Some(path.clone());
+                path
+            }
             WorkProductFileKind::Bytecode => {
                 let path = cgcx.output_filenames.temp_path(OutputType::Bitcode, Some(&module.name));
                 bytecode = Some(path.clone());
@@ -869,6 +895,7 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
         name: module.name,
         kind: ModuleKind::Regular,
         object,
+        dwarf_object,
         bytecode,
         bytecode_compressed,
     }))
@@ -1030,6 +1057,8 @@ fn start_executing_work<B: ExtraBackendMethods>(
         target_pointer_width: tcx.sess.target.target.target_pointer_width.clone(),
         target_arch: tcx.sess.target.target.arch.clone(),
         debuginfo: tcx.sess.opts.debuginfo,
+        split_debuginfo: tcx.sess.split_debuginfo(),
+        llvm_function_times: tcx.item_profiler.as_ref().and_then(|p| p.llvm_function_times()),
     };
 
     // This is the "main loop" of parallel work happening for parallel codegen.
@@ -1107,7 +1136,12 @@ fn start_executing_work<B: ExtraBackendMethods>(
     // Doing LLVM Work on the Main Thread
     // ----------------------------------
     // Since the main thread owns the compiler processes implicit `Token`, it is
-    // wasteful to keep it blocked without doing any work. Therefore, what we do
+    // wasteful to keep it blocked without doing any work. (With the parallel
+    // compiler, the "main thread" is the rayon worker running the compilation.
+    // The process's actual main thread gives its implicit `Token` to the
+    // thread pool, and the worker acquires it before it starts, see
+    // `rustc_interface::util::spawn_thread_pool`. So the worker owns exactly
+    // one `Token` here as well.) Therefore, what we do
     // in this case is: We spawn off an additional LLVM worker thread that helps
     // reduce the queue. The work it is doing corresponds to the implicit
     // `Token`. The coordinator will mark the main thread as being busy with
@@ -1670,6 +1704,7 @@ pub struct OngoingCodegen<B: ExtraBackendMethods> {
     pub shared_emitter_main: SharedEmitterMain,
     pub future: thread::JoinHandle<Result<CompiledModules, ()>>,
     pub output_filenames: Arc<OutputFilenames>,
+    pub mono_report: Option<MonoReport>,
 }
 
 impl<B: ExtraBackendMethods> OngoingCodegen<B> {
@@ -1691,8 +1726,32 @@ impl<B: ExtraBackendMethods> OngoingCodegen<B> {
 
         sess.cgu_reuse_tracker.check_expected_reuse(sess.diagnostic());
 
+        if sess.opts.debugging_opts.cgu_reuse_summary {
+            let path = self.output_filenames.with_extension("cgu-reuse.json");
+            if let Err(e) = sess.cgu_reuse_tracker.write_summary(&path) {
+                sess.err(&format!(
+                    "failed to write codegen unit reuse summary to `{}`: {}",
+                    path.display(),
+                    e
+                ));
+            }
+        }
+
         sess.abort_if_errors();
 
+        // This has to happen before `produce_final_output_artifacts` removes
+        // the object files it measures.
+        if let Some(mono_report) = self.mono_report {
+            sess.time("emit_mono_report", || {
+                mono_report.emit(
+                    &self.backend,
+                    sess,
+                    &compiled_modules.modules,
+                    &self.output_filenames,
+                )
+            });
+        }
+
         let work_products =
             copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess, &compiled_modules);
         produce_final_output_artifacts(sess, &compiled_modules, &self.output_filenames);
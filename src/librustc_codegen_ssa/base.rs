@@ -13,6 +13,7 @@
 //!   but one `llvm::Type` corresponds to many `Ty`s; for instance, `tup(int, int,
 //!   int)` and `rec(x=int, y=int, z=int)` will have the same `llvm::Type`.
 
+use crate::back::mono_report::MonoReport;
 use crate::back::write::{
     start_async_codegen, submit_codegened_module_to_llvm, submit_post_lto_module_to_llvm,
     submit_pre_lto_module_to_llvm, OngoingCodegen,
@@ -43,7 +44,7 @@ use rustc_middle::ty::layout::{FAT_PTR_ADDR, FAT_PTR_EXTRA};
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, Instance, Ty, TyCtxt};
 use rustc_session::cgu_reuse_tracker::CguReuse;
-use rustc_session::config::{self, EntryFnType, Lto};
+use rustc_session::config::{self, EntryFnType, Lto, OutputType};
 use rustc_session::Session;
 use rustc_span::Span;
 use rustc_symbol_mangling::test as symbol_names_test;
@@ -543,7 +544,12 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
         }
     }
 
-    let ongoing_codegen = start_async_codegen(backend.clone(), tcx, metadata, codegen_units.len());
+    let mut ongoing_codegen =
+        start_async_codegen(backend.clone(), tcx, metadata, codegen_units.len());
+    if tcx.sess.opts.output_types.contains_key(&OutputType::MonoReport) {
+        ongoing_codegen.mono_report =
+            Some(tcx.sess.time("collect_mono_report", || MonoReport::new(tcx, codegen_units)));
+    }
     let ongoing_codegen = AbortCodegenOnDrop::<B>(Some(ongoing_codegen));
 
     // Codegen an allocator shim, if necessary.
@@ -27,7 +27,7 @@ use rustc_middle::dep_graph::WorkProduct;
 use rustc_middle::middle::cstore::{CrateSource, LibSource, NativeLibrary};
 use rustc_middle::middle::dependency_format::Dependencies;
 use rustc_middle::ty::query::Providers;
-use rustc_session::config::{OutputFilenames, OutputType, RUST_CGU_EXT};
+use rustc_session::config::{OutputFilenames, OutputType, DWARF_OBJECT_EXT, RUST_CGU_EXT};
 use rustc_span::symbol::Symbol;
 use std::path::{Path, PathBuf};
 
@@ -61,11 +61,14 @@ impl<M> ModuleCodegen<M> {
     pub fn into_compiled_module(
         self,
         emit_obj: bool,
+        emit_dwarf_obj: bool,
         emit_bc: bool,
         emit_bc_compressed: bool,
         outputs: &OutputFilenames,
     ) -> CompiledModule {
         let object = emit_obj.then(|| outputs.temp_path(OutputType::Object, Some(&self.name)));
+        let dwarf_object =
+            emit_dwarf_obj.then(|| outputs.temp_path_ext(DWARF_OBJECT_EXT, Some(&self.name)));
         let bytecode = emit_bc.then(|| outputs.temp_path(OutputType::Bitcode, Some(&self.name)));
         let bytecode_compressed = emit_bc_compressed.then(|| {
             outputs
@@ -77,6 +80,7 @@ impl<M> ModuleCodegen<M> {
             name: self.name.clone(),
             kind: self.kind,
             object,
+            dwarf_object,
             bytecode,
             bytecode_compressed,
         }
@@ -88,6 +92,8 @@ pub struct CompiledModule {
     pub name: String,
     pub kind: ModuleKind,
     pub object: Option<PathBuf>,
+    /// The `.dwo` file holding the split debuginfo of `object`, if any.
+    pub dwarf_object: Option<PathBuf>,
     pub bytecode: Option<PathBuf>,
     pub bytecode_compressed: Option<PathBuf>,
 }
@@ -4,6 +4,7 @@ use rustc_errors::ErrorReported;
 use rustc_middle::mir;
 use rustc_middle::mir::interpret::ErrorHandled;
 use rustc_middle::ty::layout::{FnAbiExt, HasTyCtxt, TyAndLayout};
+use rustc_middle::ty::query::ItemActivity;
 use rustc_middle::ty::{self, Instance, Ty, TypeFoldable};
 use rustc_target::abi::call::{FnAbi, PassMode};
 
@@ -137,6 +138,11 @@ pub fn codegen_mir<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>>(
 ) {
     assert!(!instance.substs.needs_infer());
 
+    let _timer = cx.tcx().item_profiler.as_ref().map(|profiler| {
+        profiler.record_symbol(instance.def_id(), &cx.tcx().symbol_name(instance).name.as_str());
+        profiler.start(ItemActivity::Codegen, Some(instance.def_id()))
+    });
+
     let llfn = cx.get_fn(instance);
 
     let mir = cx.tcx().instance_mir(instance.def);
@@ -19,6 +19,7 @@ use rustc_target::abi::LayoutOf;
 pub use rustc_data_structures::sync::MetadataRef;
 
 use std::any::Any;
+use std::path::Path;
 use std::sync::Arc;
 
 pub trait BackendTypes {
@@ -120,4 +121,7 @@ pub trait ExtraBackendMethods: CodegenBackend + WriteBackendMethods + Sized + Se
         find_features: bool,
     ) -> Arc<dyn Fn() -> Result<Self::TargetMachine, String> + Send + Sync>;
     fn target_cpu<'b>(&self, sess: &'b Session) -> &'b str;
+    /// Returns the name and size in bytes of every sized symbol defined in the
+    /// object file at `path`. Used to fill in code sizes for `--emit=mono-report`.
+    fn object_symbol_sizes(&self, path: &Path) -> Result<Vec<(String, u64)>, String>;
 }
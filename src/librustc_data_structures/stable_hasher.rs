@@ -463,6 +463,14 @@ impl<I: vec::Idx, CTX> HashStable<CTX> for bit_set::BitSet<I> {
     }
 }
 
+impl<T: bit_set::FiniteBitSetTy + HashStable<CTX>, CTX> HashStable<CTX>
+    for bit_set::FiniteBitSet<T>
+{
+    fn hash_stable(&self, ctx: &mut CTX, hasher: &mut StableHasher) {
+        self.0.hash_stable(ctx, hasher);
+    }
+}
+
 impl<R: vec::Idx, C: vec::Idx, CTX> HashStable<CTX> for bit_set::BitMatrix<R, C> {
     fn hash_stable(&self, ctx: &mut CTX, hasher: &mut StableHasher) {
         self.words().hash_stable(ctx, hasher);
@@ -22,13 +22,14 @@ use rustc_data_structures::sync::SeqCst;
 use rustc_errors::registry::{InvalidErrorCode, Registry};
 use rustc_errors::{ErrorReported, PResult};
 use rustc_feature::{find_gated_cfg, UnstableFeatures};
-use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_hir::def_id::{LocalDefId, LOCAL_CRATE};
 use rustc_interface::util::{collect_crate_types, get_builtin_codegen_backend};
 use rustc_interface::{interface, Queries};
 use rustc_lint::LintStore;
 use rustc_metadata::locator;
 use rustc_middle::middle::cstore::MetadataLoader;
 use rustc_middle::ty::TyCtxt;
+use rustc_mir::borrow_check::consumers::{self, BodyWithBorrowckFacts};
 use rustc_save_analysis as save;
 use rustc_save_analysis::DumpHandler;
 use rustc_serialize::json::{self, ToJson};
@@ -104,6 +105,38 @@ pub trait Callbacks {
     ) -> Compilation {
         Compilation::Continue
     }
+    /// Whether `after_borrowck` should get the body and borrow checker facts of
+    /// every body of the local crate (defaults to `false`). Collecting them
+    /// runs the compiler on a single thread and keeps borrow checking results
+    /// out of the incremental cache.
+    fn wants_borrowck_facts(&self) -> bool {
+        false
+    }
+    /// Called after type-checking, before borrow checking. The typed HIR is
+    /// available through `TyCtxt::typeck_tables_of`. Return value instructs the
+    /// compiler whether to continue the compilation afterwards (defaults to
+    /// `Compilation::Continue`)
+    fn after_typeck<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        _queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        Compilation::Continue
+    }
+    /// Called after borrow checking, which may have reported errors. If
+    /// `wants_borrowck_facts` returns `true`, `bodies` holds every body of the
+    /// local crate along with its borrow checker facts, otherwise it is empty.
+    /// The optimized MIR of each body is available through
+    /// `TyCtxt::optimized_mir`. Return value instructs the compiler whether to
+    /// continue the compilation afterwards (defaults to `Compilation::Continue`)
+    fn after_borrowck<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        _tcx: TyCtxt<'tcx>,
+        _bodies: &[(LocalDefId, BodyWithBorrowckFacts<'tcx>)],
+    ) -> Compilation {
+        Compilation::Continue
+    }
     /// Called after analysis. Return value instructs the compiler whether to
     /// continue the compilation afterwards (defaults to `Compilation::Continue`)
     fn after_analysis<'tcx>(
@@ -257,6 +290,13 @@ pub fn run_compiler(
 
     callbacks.config(&mut config);
 
+    if callbacks.wants_borrowck_facts() {
+        // The `mir_borrowck` provider collects the facts on the thread it runs
+        // on, so all queries have to run on the thread calling the callbacks.
+        config.opts.debugging_opts.threads = 1;
+        config.opts.collect_borrowck_facts = true;
+    }
+
     interface::run_compiler(config, |compiler| {
         let sess = compiler.session();
         let should_stop = RustcDefaultCalls::print_crate_info(
@@ -380,6 +420,26 @@ pub fn run_compiler(
                 mem::drop(queries.expansion()?.take());
             }
 
+            queries.global_ctxt()?.peek_mut().enter(|tcx| tcx.typeck_analysis(LOCAL_CRATE))?;
+
+            if callbacks.after_typeck(compiler, queries) == Compilation::Stop {
+                return early_exit();
+            }
+
+            let compilation = queries.global_ctxt()?.peek_mut().enter(|tcx| {
+                tcx.borrowck_analysis(LOCAL_CRATE)?;
+                let bodies = if tcx.sess.opts.collect_borrowck_facts {
+                    consumers::take_bodies_with_facts(tcx)
+                } else {
+                    Vec::new()
+                };
+                Ok(callbacks.after_borrowck(compiler, tcx, &bodies))
+            })?;
+
+            if compilation == Compilation::Stop {
+                return early_exit();
+            }
+
             queries.global_ctxt()?.peek_mut().enter(|tcx| tcx.analysis(LOCAL_CRATE))?;
 
             if callbacks.after_analysis(compiler, queries) == Compilation::Stop {
@@ -163,7 +163,7 @@ impl<'a> DiagnosticConverter<'a> {
             Level::Note => AnnotationType::Note,
             Level::Help => AnnotationType::Help,
             // FIXME(#59346): Not sure how to map these two levels
-            Level::Cancelled | Level::FailureNote => AnnotationType::Error,
+            Level::Cancelled | Level::FailureNote | Level::Allow => AnnotationType::Error,
         }
     }
 }
@@ -1,6 +1,7 @@
 use crate::snippet::Style;
 use crate::Applicability;
 use crate::CodeSuggestion;
+use crate::FutureIncompatLint;
 use crate::Level;
 use crate::Substitution;
 use crate::SubstitutionPart;
@@ -22,6 +23,14 @@ pub struct Diagnostic {
     /// as a sort key to sort a buffer of diagnostics.  By default, it is the primary span of
     /// `span` if there is one.  Otherwise, it is `DUMMY_SP`.
     pub sort_span: Span,
+
+    /// The macro invocations at which other occurrences of this diagnostic were
+    /// found, if they were grouped into it (see `-Z group-macro-diagnostics`).
+    pub grouped_occurrences: Vec<Span>,
+
+    /// The future-incompatible lint this diagnostic reports, to be collected
+    /// for `-Z emit-future-incompat-report` when it is emitted.
+    pub future_incompat: Option<FutureIncompatLint>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
@@ -100,6 +109,8 @@ impl Diagnostic {
             children: vec![],
             suggestions: vec![],
             sort_span: DUMMY_SP,
+            grouped_occurrences: vec![],
+            future_incompat: None,
         }
     }
 
@@ -107,7 +118,7 @@ impl Diagnostic {
         match self.level {
             Level::Bug | Level::Fatal | Level::Error | Level::FailureNote => true,
 
-            Level::Warning | Level::Note | Level::Help | Level::Cancelled => false,
+            Level::Warning | Level::Note | Level::Help | Level::Cancelled | Level::Allow => false,
         }
     }
 
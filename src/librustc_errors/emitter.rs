@@ -15,7 +15,8 @@ use rustc_span::{MultiSpan, SourceFile, Span};
 use crate::snippet::{Annotation, AnnotationType, Line, MultilineAnnotation, Style, StyledString};
 use crate::styled_buffer::StyledBuffer;
 use crate::{
-    pluralize, CodeSuggestion, Diagnostic, DiagnosticId, Level, SubDiagnostic, SuggestionStyle,
+    pluralize, CodeSuggestion, Diagnostic, DiagnosticId, FutureIncompatLint, Level, SubDiagnostic,
+    SuggestionStyle,
 };
 
 use log::*;
@@ -188,6 +189,11 @@ pub trait Emitter {
     /// other formats can, and will, simply ignore it.
     fn emit_artifact_notification(&mut self, _path: &Path, _artifact_type: &str) {}
 
+    /// Emit a report of the future-incompatible lints that fired during the
+    /// session. This is currently only supported for the JSON format, other
+    /// formats can, and will, simply ignore it.
+    fn emit_future_incompat_report(&mut self, _lints: &[FutureIncompatLint]) {}
+
     /// Checks if should show explanations about "rustc --explain"
     fn should_show_explain(&self) -> bool {
         true
@@ -14,7 +14,7 @@ use rustc_span::source_map::{FilePathMapping, SourceMap};
 use crate::emitter::{Emitter, HumanReadableErrorType};
 use crate::registry::Registry;
 use crate::{Applicability, DiagnosticId};
-use crate::{CodeSuggestion, SubDiagnostic};
+use crate::{CodeSuggestion, FutureIncompatLint, SubDiagnostic};
 
 use rustc_data_structures::sync::Lrc;
 use rustc_span::hygiene::ExpnData;
@@ -25,6 +25,7 @@ use std::sync::{Arc, Mutex};
 use std::vec;
 
 use rustc_serialize::json::{as_json, as_pretty_json};
+use rustc_serialize::{Encodable, Encoder};
 
 #[cfg(test)]
 mod tests;
@@ -124,6 +125,29 @@ impl Emitter for JsonEmitter {
         }
     }
 
+    fn emit_future_incompat_report(&mut self, lints: &[FutureIncompatLint]) {
+        let future_incompat_report = lints
+            .iter()
+            .map(|lint| FutureIncompatItem {
+                lint: lint.lint.clone(),
+                level: lint.level.clone(),
+                reference: lint.reference.clone(),
+                edition: lint.edition.clone(),
+                spans: DiagnosticSpan::from_multispan(&lint.span, self),
+            })
+            .collect();
+        let data = FutureIncompatReport { future_incompat_report };
+        let result = if self.pretty {
+            writeln!(&mut self.dst, "{}", as_pretty_json(&data))
+        } else {
+            writeln!(&mut self.dst, "{}", as_json(&data))
+        }
+        .and_then(|_| self.dst.flush());
+        if let Err(e) = result {
+            panic!("failed to print future incompatibility report: {:?}", e);
+        }
+    }
+
     fn source_map(&self) -> Option<&Lrc<SourceMap>> {
         Some(&self.sm)
     }
@@ -138,7 +162,6 @@ impl Emitter for JsonEmitter {
 
 // The following data types are provided just for serialisation.
 
-#[derive(RustcEncodable)]
 struct Diagnostic {
     /// The primary error message.
     message: String,
@@ -150,6 +173,31 @@ struct Diagnostic {
     children: Vec<Diagnostic>,
     /// The message as rustc would render it.
     rendered: Option<String>,
+    /// The macro invocations at which other occurrences of this diagnostic were
+    /// found, when they were grouped into it by `-Z group-macro-diagnostics`.
+    grouped_occurrences: Vec<DiagnosticSpan>,
+}
+
+// Implemented by hand so that `grouped_occurrences` is only present in the
+// output of diagnostics which other occurrences were grouped into.
+impl Encodable for Diagnostic {
+    fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
+        let len = if self.grouped_occurrences.is_empty() { 6 } else { 7 };
+        s.emit_struct("Diagnostic", len, |s| {
+            s.emit_struct_field("message", 0, |s| self.message.encode(s))?;
+            s.emit_struct_field("code", 1, |s| self.code.encode(s))?;
+            s.emit_struct_field("level", 2, |s| self.level.encode(s))?;
+            s.emit_struct_field("spans", 3, |s| self.spans.encode(s))?;
+            s.emit_struct_field("children", 4, |s| self.children.encode(s))?;
+            s.emit_struct_field("rendered", 5, |s| self.rendered.encode(s))?;
+            if !self.grouped_occurrences.is_empty() {
+                s.emit_struct_field("grouped_occurrences", 6, |s| {
+                    self.grouped_occurrences.encode(s)
+                })?;
+            }
+            Ok(())
+        })
+    }
 }
 
 #[derive(RustcEncodable)]
@@ -219,6 +267,24 @@ struct ArtifactNotification<'a> {
     emit: &'a str,
 }
 
+#[derive(RustcEncodable)]
+struct FutureIncompatReport {
+    future_incompat_report: Vec<FutureIncompatItem>,
+}
+
+#[derive(RustcEncodable)]
+struct FutureIncompatItem {
+    /// The name of the lint.
+    lint: String,
+    /// "allow", "warn", "deny" or "forbid".
+    level: String,
+    /// The tracking issue, or similar.
+    reference: String,
+    /// The edition in which the lint becomes an error, if any.
+    edition: Option<String>,
+    spans: Vec<DiagnosticSpan>,
+}
+
 impl Diagnostic {
     fn from_errors_diagnostic(diag: &crate::Diagnostic, je: &JsonEmitter) -> Diagnostic {
         let sugg = diag.suggestions.iter().map(|sugg| Diagnostic {
@@ -228,6 +294,7 @@ impl Diagnostic {
             spans: DiagnosticSpan::from_suggestion(sugg, je),
             children: vec![],
             rendered: None,
+            grouped_occurrences: vec![],
         });
 
         // generate regular command line output and store it in the json
@@ -265,6 +332,10 @@ impl Diagnostic {
                 .chain(sugg)
                 .collect(),
             rendered: Some(output),
+            grouped_occurrences: DiagnosticSpan::from_multispan(
+                &MultiSpan::from_spans(diag.grouped_occurrences.clone()),
+                je,
+            ),
         }
     }
 
@@ -280,6 +351,7 @@ impl Diagnostic {
                 .unwrap_or_else(|| DiagnosticSpan::from_multispan(&diag.span, je)),
             children: vec![],
             rendered: None,
+            grouped_occurrences: vec![],
         }
     }
 }
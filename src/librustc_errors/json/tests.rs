@@ -201,3 +201,48 @@ fn span_after_crlf() {
         },
     )
 }
+
+#[test]
+fn ordered_diagnostics() {
+    with_default_globals(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), "a b c".to_owned());
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let je = JsonEmitter::new(
+            Box::new(Shared { data: output.clone() }),
+            None,
+            sm,
+            true,
+            HumanReadableErrorType::Short(ColorConfig::Never),
+            false,
+        );
+
+        let span = |lo, hi| Span::with_root_ctxt(BytePos(lo), BytePos(hi));
+        let handler = Handler::with_emitter(true, None, Box::new(je));
+        {
+            let _ordered = handler.order_diagnostics();
+            handler.span_err(span(4, 5), "third");
+            handler.err("last");
+            handler.span_err(span(0, 1), "first");
+            {
+                // Nested guards do not emit anything early.
+                let _nested = handler.order_diagnostics();
+                handler.span_err(span(2, 3), "second");
+            }
+            assert!(output.lock().unwrap().is_empty());
+            assert_eq!(handler.err_count(), 4);
+        }
+
+        let bytes = output.lock().unwrap();
+        let messages: Vec<_> = str::from_utf8(&bytes)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let json = rustc_serialize::json::Json::from_str(line).unwrap();
+                json.find("message").unwrap().as_string().unwrap().to_owned()
+            })
+            .collect();
+        assert_eq!(messages, ["first", "second", "third", "last"]);
+    })
+}
@@ -17,6 +17,7 @@ use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync::{self, Lock, Lrc};
 use rustc_data_structures::AtomicRef;
+use rustc_span::hygiene::ExpnKind;
 use rustc_span::source_map::SourceMap;
 use rustc_span::{Loc, MultiSpan, Span};
 
@@ -34,6 +35,7 @@ pub mod emitter;
 pub mod json;
 mod lock;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
 pub use snippet::Style;
@@ -275,6 +277,25 @@ impl error::Error for ExplicitBug {}
 pub use diagnostic::{Diagnostic, DiagnosticId, DiagnosticStyledString, SubDiagnostic};
 pub use diagnostic_builder::DiagnosticBuilder;
 
+/// A future-incompatible lint that fired during the session, as collected for
+/// `-Z emit-future-incompat-report`. It is carried by the lint's diagnostic, so
+/// that it is collected again when the diagnostic is replayed from the
+/// incremental cache.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
+pub struct FutureIncompatLint {
+    /// The name of the lint, e.g. `array_into_iter`.
+    pub lint: String,
+    /// The level the lint fired at, after lint attributes and `--cap-lints`
+    /// were taken into account. This is `allow` for lints in capped crates.
+    pub level: String,
+    /// e.g., a URL for an issue/PR/RFC or error code.
+    pub reference: String,
+    /// If this is an edition fixing lint, the edition in which this lint
+    /// becomes obsolete.
+    pub edition: Option<String>,
+    pub span: MultiSpan,
+}
+
 /// A handler deals with errors and other compiler output.
 /// Certain errors (fatal, bug, unimpl) may cause immediate exit,
 /// others log errors for later reporting.
@@ -316,8 +337,91 @@ struct HandlerInner {
     /// When `.abort_if_errors()` is called, these are also emitted.
     stashed_diagnostics: FxIndexMap<(Span, StashKey), Diagnostic>,
 
+    /// Diagnostics originating in macro expansions, grouped by level, code (or
+    /// message template) and macro definition when `-Z group-macro-diagnostics`
+    /// is enabled. Each group holds its first diagnostic and the invocations at
+    /// which it occurred.
+    /// Like stashed diagnostics, these are emitted by `.abort_if_errors()`.
+    grouped_diagnostics: FxIndexMap<DiagnosticGroupKey, (Diagnostic, Vec<Span>)>,
+
+    /// Diagnostics held back while an `OrderedDiagnostics` guard is alive, to be
+    /// emitted sorted by span once it is dropped. `None` if there is no guard.
+    held_diagnostics: Option<Vec<Diagnostic>>,
+
     /// The warning count, used for a recap upon finishing
     deduplicated_warn_count: usize,
+
+    /// The future-incompatible lints carried by the diagnostics emitted so far,
+    /// for `-Z emit-future-incompat-report`.
+    future_incompat_lints: Vec<FutureIncompatLint>,
+}
+
+/// A guard returned by `Handler::order_diagnostics`. Dropping it emits the
+/// diagnostics that were held back, including when unwinding from a fatal
+/// error.
+#[must_use]
+pub struct OrderedDiagnostics<'a> {
+    handler: Option<&'a Handler>,
+}
+
+impl Drop for OrderedDiagnostics<'_> {
+    fn drop(&mut self) {
+        if let Some(handler) = self.handler {
+            handler.inner.borrow_mut().emit_held_diagnostics();
+        }
+    }
+}
+
+/// The number of other occurrences of a grouped diagnostic that are pointed
+/// at in its note; the remaining ones are only counted.
+const MAX_GROUPED_OCCURRENCES_SHOWN: usize = 8;
+
+/// What diagnostics from macro expansions must share to be grouped together.
+/// Their messages usually differ in the names they mention, so diagnostics
+/// with a code are grouped by it, and others by their message with the
+/// quoted parts left out.
+#[derive(PartialEq, Eq, Hash)]
+struct DiagnosticGroupKey {
+    level: Level,
+    code: Option<DiagnosticId>,
+    template: Option<String>,
+    /// The definition of the innermost macro the primary span comes from.
+    macro_def_site: Span,
+}
+
+impl DiagnosticGroupKey {
+    fn new(diagnostic: &Diagnostic) -> Option<Self> {
+        match diagnostic.level {
+            Error | Warning => {}
+            _ => return None,
+        }
+        let span = diagnostic.span.primary_span()?;
+        let macro_def_site = span.macro_backtrace().find_map(|expn_data| match expn_data.kind {
+            ExpnKind::Macro(..) => Some(expn_data.def_site),
+            _ => None,
+        })?;
+        let template = match diagnostic.code {
+            Some(_) => None,
+            None => Some(message_template(&diagnostic.message())),
+        };
+        Some(DiagnosticGroupKey {
+            level: diagnostic.level,
+            code: diagnostic.code.clone(),
+            template,
+            macro_def_site,
+        })
+    }
+}
+
+/// Replaces the parts of `message` quoted in backticks, which usually name the
+/// items a diagnostic is about, with `{}`.
+fn message_template(message: &str) -> String {
+    message
+        .split('`')
+        .enumerate()
+        .map(|(i, part)| if i % 2 == 0 { part } else { "{}" })
+        .collect::<Vec<_>>()
+        .join("`")
 }
 
 /// A key denoting where from a diagnostic was stashed.
@@ -350,6 +454,10 @@ pub struct HandlerFlags {
     pub macro_backtrace: bool,
     /// If true, identical diagnostics are reported only once.
     pub deduplicate_diagnostics: bool,
+    /// If true, diagnostics from different invocations of the same macro are
+    /// reported as one diagnostic listing the other invocations.
+    /// (rustc: see `-Z group-macro-diagnostics`)
+    pub group_macro_diagnostics: bool,
 }
 
 impl Drop for HandlerInner {
@@ -427,6 +535,9 @@ impl Handler {
                 emitted_diagnostic_codes: Default::default(),
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
+                grouped_diagnostics: Default::default(),
+                held_diagnostics: None,
+                future_incompat_lints: Vec::new(),
             }),
         }
     }
@@ -454,6 +565,23 @@ impl Handler {
         inner.emitted_diagnostic_codes = Default::default();
         inner.emitted_diagnostics = Default::default();
         inner.stashed_diagnostics = Default::default();
+        inner.grouped_diagnostics = Default::default();
+        inner.future_incompat_lints = Default::default();
+    }
+
+    /// Holds back the diagnostics emitted from now on until the returned guard
+    /// is dropped, and then emits them sorted by their primary span.
+    ///
+    /// This is used when the front end runs on multiple threads, which report
+    /// their diagnostics in whatever order they are scheduled in. Errors are
+    /// still counted as they are reported. Nested guards are no-ops.
+    pub fn order_diagnostics(&self) -> OrderedDiagnostics<'_> {
+        let mut inner = self.inner.borrow_mut();
+        let outermost = inner.held_diagnostics.is_none();
+        if outermost {
+            inner.held_diagnostics = Some(Vec::new());
+        }
+        OrderedDiagnostics { handler: if outermost { Some(self) } else { None } }
     }
 
     /// Stash a given diagnostic with the given `Span` and `StashKey` as the key for later stealing.
@@ -704,6 +832,14 @@ impl Handler {
         self.inner.borrow_mut().emit_artifact_notification(path, artifact_type)
     }
 
+    /// Emits the report of the future-incompatible lints carried by the
+    /// diagnostics emitted so far.
+    pub fn emit_future_incompat_report(&self) {
+        let mut inner = self.inner.borrow_mut();
+        let inner = &mut *inner;
+        inner.emitter.emit_future_incompat_report(&inner.future_incompat_lints)
+    }
+
     pub fn delay_as_bug(&self, diagnostic: Diagnostic) {
         self.inner.borrow_mut().delay_as_bug(diagnostic)
     }
@@ -722,6 +858,32 @@ impl HandlerInner {
     fn emit_stashed_diagnostics(&mut self) {
         let diags = self.stashed_diagnostics.drain(..).map(|x| x.1).collect::<Vec<_>>();
         diags.iter().for_each(|diag| self.emit_diagnostic(diag));
+        self.emit_grouped_diagnostics();
+    }
+
+    /// Emit one diagnostic for each group of diagnostics from macro expansions,
+    /// with a note pointing at the other invocations the group occurred in.
+    fn emit_grouped_diagnostics(&mut self) {
+        let groups = self.grouped_diagnostics.drain(..).map(|x| x.1).collect::<Vec<_>>();
+        for (mut diagnostic, mut occurrences) in groups {
+            // The first occurrence is the one of the diagnostic itself.
+            occurrences.remove(0);
+            if !occurrences.is_empty() {
+                let shown = occurrences.len().min(MAX_GROUPED_OCCURRENCES_SHOWN);
+                let mut msg = format!(
+                    "this {} also occurs in {} other invocation{} of this macro",
+                    diagnostic.level,
+                    occurrences.len(),
+                    if occurrences.len() == 1 { "" } else { "s" },
+                );
+                if shown < occurrences.len() {
+                    msg.push_str(&format!(" ({} not shown)", occurrences.len() - shown));
+                }
+                diagnostic.span_note(MultiSpan::from_spans(occurrences[..shown].to_vec()), &msg);
+                diagnostic.grouped_occurrences = occurrences;
+            }
+            self.emit_ungrouped_diagnostic(&diagnostic);
+        }
     }
 
     fn emit_diagnostic(&mut self, diagnostic: &Diagnostic) {
@@ -729,7 +891,21 @@ impl HandlerInner {
             return;
         }
 
-        if diagnostic.level == Warning && !self.flags.can_emit_warnings {
+        if let Some(lint) = &diagnostic.future_incompat {
+            if !self.future_incompat_lints.contains(lint) {
+                self.future_incompat_lints.push(lint.clone());
+            }
+        }
+
+        if diagnostic.level == Allow
+            || (diagnostic.level == Warning && !self.flags.can_emit_warnings)
+        {
+            // Diagnostics that are not shown are still tracked if they carry a
+            // future-incompatible lint, so that replaying them from the
+            // incremental cache collects the lint again.
+            if diagnostic.future_incompat.is_some() {
+                (*TRACK_DIAGNOSTICS)(diagnostic);
+            }
             return;
         }
 
@@ -739,6 +915,33 @@ impl HandlerInner {
             self.emitted_diagnostic_codes.insert(code.clone());
         }
 
+        // Grouping delays emission, so it is skipped when errors are turned into bugs.
+        if self.flags.group_macro_diagnostics && self.flags.treat_err_as_bug.is_none() {
+            if let Some(key) = DiagnosticGroupKey::new(diagnostic) {
+                let invocation = diagnostic.sort_span.source_callsite();
+                let (_, occurrences) = self
+                    .grouped_diagnostics
+                    .entry(key)
+                    .or_insert_with(|| (diagnostic.clone(), vec![]));
+                if !occurrences.contains(&invocation) {
+                    occurrences.push(invocation);
+                }
+                if diagnostic.is_error() {
+                    self.bump_err_count();
+                }
+                return;
+            }
+        }
+
+        self.emit_ungrouped_diagnostic(diagnostic);
+        if diagnostic.is_error() {
+            self.bump_err_count();
+        }
+    }
+
+    /// Hands `diagnostic` to the emitter, unless it is a duplicate that should
+    /// not be reported again. This does not count towards the error count.
+    fn emit_ungrouped_diagnostic(&mut self, diagnostic: &Diagnostic) {
         let already_emitted = |this: &mut Self| {
             use std::hash::Hash;
             let mut hasher = StableHasher::new();
@@ -750,15 +953,31 @@ impl HandlerInner {
         // Only emit the diagnostic if we've been asked to deduplicate and
         // haven't already emitted an equivalent diagnostic.
         if !(self.flags.deduplicate_diagnostics && already_emitted(self)) {
-            self.emitter.emit_diagnostic(diagnostic);
+            match &mut self.held_diagnostics {
+                Some(held) => held.push(diagnostic.clone()),
+                None => self.emitter.emit_diagnostic(diagnostic),
+            }
             if diagnostic.is_error() {
                 self.deduplicated_err_count += 1;
             } else if diagnostic.level == Warning {
                 self.deduplicated_warn_count += 1;
             }
         }
-        if diagnostic.is_error() {
-            self.bump_err_count();
+    }
+
+    /// Emits the diagnostics held back by an `OrderedDiagnostics` guard, ordered
+    /// by their primary span. Diagnostics without a span come last, and ties
+    /// are broken by message so that the order never depends on which thread
+    /// reported a diagnostic first.
+    fn emit_held_diagnostics(&mut self) {
+        if let Some(mut held) = self.held_diagnostics.take() {
+            held.sort_by_cached_key(|diagnostic| {
+                let span = diagnostic.span.primary_span().map(|span| (span.lo(), span.hi()));
+                (span.is_none(), span, diagnostic.message())
+            });
+            for diagnostic in &held {
+                self.emitter.emit_diagnostic(diagnostic);
+            }
         }
     }
 
@@ -942,6 +1161,9 @@ pub enum Level {
     Help,
     Cancelled,
     FailureNote,
+    /// An allowed lint, which is never shown. It is only emitted to collect
+    /// the future-incompatible lint it carries.
+    Allow,
 }
 
 impl fmt::Display for Level {
@@ -967,7 +1189,7 @@ impl Level {
                 spec.set_fg(Some(Color::Cyan)).set_intense(true);
             }
             FailureNote => {}
-            Cancelled => unreachable!(),
+            Allow | Cancelled => unreachable!(),
         }
         spec
     }
@@ -980,6 +1202,7 @@ impl Level {
             Note => "note",
             Help => "help",
             FailureNote => "failure-note",
+            Allow => panic!("Shouldn't call on allowed lint"),
             Cancelled => panic!("Shouldn't call on cancelled error"),
         }
     }
@@ -0,0 +1,468 @@
+//! A SARIF emitter for errors.
+//!
+//! SARIF (Static Analysis Results Interchange Format) 2.1.0 is the format
+//! code-scanning services ingest. Unlike the JSON emitter, which writes one
+//! object per diagnostic, a SARIF log is a single document, so this emitter
+//! collects all diagnostics of the session and writes the log when it is
+//! dropped. Warnings about the command line that are found before the session
+//! exists are kept in its options and reported through the session's handler
+//! instead of in logs of their own.
+//!
+//! Each diagnostic becomes a `result`:
+//!
+//! - its error code or lint name is the `ruleId`,
+//! - its primary spans are the `locations`,
+//! - its secondary span labels and its children (notes, helps, ...) are the
+//!   `relatedLocations`,
+//! - and its machine-applicable suggestions are the `fixes`.
+//!
+//! Columns are reported in Unicode code points, like in the JSON output.
+//!
+//! The SARIF schema uses camel-cased property names and forbids `null` for
+//! most properties, so the data types at the end of this file implement
+//! `ToJson` by hand instead of deriving `RustcEncodable`.
+
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::{Applicability, CodeSuggestion, Diagnostic, DiagnosticId, Level, SubDiagnostic};
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_serialize::json::{Json, Object, ToJson};
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+use rustc_span::{FileName, MultiSpan, Span};
+
+use std::io::{self, Write};
+
+#[cfg(test)]
+mod tests;
+
+pub struct SarifEmitter {
+    dst: Box<dyn Write + Send>,
+    registry: Option<Registry>,
+    sm: Lrc<SourceMap>,
+    rules: Vec<Rule>,
+    rule_indices: FxHashMap<String, usize>,
+    results: Vec<SarifResult>,
+}
+
+impl SarifEmitter {
+    pub fn stderr(registry: Option<Registry>, source_map: Lrc<SourceMap>) -> SarifEmitter {
+        SarifEmitter::new(Box::new(io::BufWriter::new(io::stderr())), registry, source_map)
+    }
+
+    pub fn basic() -> SarifEmitter {
+        let file_path_mapping = FilePathMapping::empty();
+        SarifEmitter::stderr(None, Lrc::new(SourceMap::new(file_path_mapping)))
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst,
+            registry,
+            sm: source_map,
+            rules: Vec::new(),
+            rule_indices: Default::default(),
+            results: Vec::new(),
+        }
+    }
+
+    /// Returns the index of the rule for `code` in the log's rule table,
+    /// adding it if this is the first result for the rule.
+    fn rule_index(&mut self, code: &DiagnosticId) -> (String, usize) {
+        let (id, is_error_code) = match code {
+            DiagnosticId::Error(s) => (s, true),
+            DiagnosticId::Lint(s) => (s, false),
+        };
+        if let Some(&index) = self.rule_indices.get(id) {
+            return (id.clone(), index);
+        }
+
+        let has_explanation = is_error_code
+            && self
+                .registry
+                .as_ref()
+                .map_or(false, |registry| matches!(registry.try_find_description(id), Ok(Some(_))));
+        let help_uri = if has_explanation {
+            Some(format!("https://doc.rust-lang.org/error-index.html#{}", id))
+        } else {
+            None
+        };
+
+        let index = self.rules.len();
+        self.rules.push(Rule { id: id.clone(), help_uri });
+        self.rule_indices.insert(id.clone(), index);
+        (id.clone(), index)
+    }
+
+    fn region(&self, span: Span) -> Option<PhysicalLocation> {
+        if span.is_dummy() {
+            return None;
+        }
+        let start = self.sm.lookup_char_pos(span.lo());
+        let end = self.sm.lookup_char_pos(span.hi());
+        let uri = file_uri(&start.file.name)?;
+        let byte_start = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        Some(PhysicalLocation {
+            uri,
+            region: Region {
+                start_line: start.line,
+                start_column: start.col.0 + 1,
+                end_line: end.line,
+                end_column: end.col.0 + 1,
+                byte_offset: byte_start,
+                byte_length: byte_end - byte_start,
+            },
+        })
+    }
+
+    fn related_locations(
+        &self,
+        span: &MultiSpan,
+        children: &[SubDiagnostic],
+        related: &mut Vec<Location>,
+    ) {
+        // Labelled secondary spans of the diagnostic itself.
+        for span_label in span.span_labels() {
+            if span_label.is_primary {
+                continue;
+            }
+            if let Some(label) = span_label.label {
+                related.push(Location {
+                    physical: self.region(span_label.span),
+                    message: Some(label),
+                });
+            }
+        }
+
+        for child in children {
+            let message = format!("{}: {}", child.level.to_str(), child.message());
+            let span = child.render_span.as_ref().unwrap_or(&child.span);
+            let physical = span.primary_span().and_then(|primary| self.region(primary));
+            related.push(Location { physical, message: Some(message) });
+        }
+    }
+
+    /// Each substitution of a machine-applicable suggestion is an alternative
+    /// fix of its own.
+    fn fixes(&self, suggestion: &CodeSuggestion, fixes: &mut Vec<Fix>) {
+        if suggestion.applicability != Applicability::MachineApplicable {
+            return;
+        }
+
+        for substitution in &suggestion.substitutions {
+            let mut changes: Vec<ArtifactChange> = Vec::new();
+            for part in &substitution.parts {
+                let location = match self.region(part.span) {
+                    Some(location) => location,
+                    // A fix that can't be applied completely is no fix at all.
+                    None => return,
+                };
+                let replacement = Replacement {
+                    deleted_region: location.region,
+                    inserted_text: part.snippet.clone(),
+                };
+                match changes.iter_mut().find(|change| change.uri == location.uri) {
+                    Some(change) => change.replacements.push(replacement),
+                    None => changes.push(ArtifactChange {
+                        uri: location.uri,
+                        replacements: vec![replacement],
+                    }),
+                }
+            }
+            fixes.push(Fix { description: suggestion.msg.clone(), changes });
+        }
+    }
+
+    fn write_log(&mut self) -> io::Result<()> {
+        let log = SarifLog {
+            rules: std::mem::take(&mut self.rules),
+            results: std::mem::take(&mut self.results),
+        };
+        writeln!(&mut self.dst, "{}", log.to_json().pretty())?;
+        self.dst.flush()
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: &Diagnostic) {
+        let level = match diag.level {
+            Level::Bug | Level::Fatal | Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Note | Level::Help => "note",
+            // These carry no information about the analyzed code, like the
+            // `rustc --explain` hints.
+            Level::Cancelled | Level::FailureNote | Level::Allow => return,
+        };
+
+        let rule = diag.code.as_ref().map(|code| self.rule_index(code));
+
+        let locations = diag
+            .span
+            .primary_spans()
+            .iter()
+            .filter_map(|&span| self.region(span))
+            .map(|physical| Location { physical: Some(physical), message: None })
+            .collect();
+
+        let mut related_locations = Vec::new();
+        self.related_locations(&diag.span, &diag.children, &mut related_locations);
+
+        let mut fixes = Vec::new();
+        for suggestion in &diag.suggestions {
+            self.fixes(suggestion, &mut fixes);
+        }
+
+        self.results.push(SarifResult {
+            rule,
+            level,
+            message: diag.message(),
+            locations,
+            related_locations,
+            fixes,
+        });
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        // Panicking here could abort the process while it is already unwinding,
+        // so a failure to write the log is only reported on stderr.
+        if let Err(e) = self.write_log() {
+            let _ = writeln!(io::stderr(), "error: failed to write the SARIF log: {}", e);
+        }
+    }
+}
+
+/// Converts a source file name into a URI, or `None` if the file doesn't
+/// exist on disk.
+fn file_uri(name: &FileName) -> Option<String> {
+    let path = match name {
+        FileName::Real(path) => path,
+        _ => return None,
+    };
+
+    let mut uri = String::new();
+    if path.is_absolute() {
+        uri.push_str("file://");
+    }
+    let path = path.to_string_lossy().replace('\\', "/");
+    if !path.starts_with('/') && !uri.is_empty() {
+        // Windows paths like `C:/foo` need a leading slash.
+        uri.push('/');
+    }
+    for &b in path.as_bytes() {
+        match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' => uri.push(b as char),
+            b'-' | b'.' | b'_' | b'~' | b'/' | b':' => uri.push(b as char),
+            _ => uri.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    Some(uri)
+}
+
+// The following data types are provided just for serialisation.
+
+struct SarifLog {
+    rules: Vec<Rule>,
+    results: Vec<SarifResult>,
+}
+
+struct Rule {
+    /// The error code or lint name.
+    id: String,
+    /// A link to the explanation of an error code, if it has one.
+    help_uri: Option<String>,
+}
+
+struct SarifResult {
+    /// The rule ID and its index in the rule table.
+    rule: Option<(String, usize)>,
+    /// "error", "warning" or "note".
+    level: &'static str,
+    message: String,
+    locations: Vec<Location>,
+    related_locations: Vec<Location>,
+    fixes: Vec<Fix>,
+}
+
+struct Location {
+    physical: Option<PhysicalLocation>,
+    message: Option<String>,
+}
+
+struct PhysicalLocation {
+    uri: String,
+    region: Region,
+}
+
+#[derive(Clone, Copy)]
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    end_line: usize,
+    /// 1-based, character offset.
+    start_column: usize,
+    end_column: usize,
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+struct Fix {
+    description: String,
+    changes: Vec<ArtifactChange>,
+}
+
+struct ArtifactChange {
+    uri: String,
+    replacements: Vec<Replacement>,
+}
+
+struct Replacement {
+    deleted_region: Region,
+    inserted_text: String,
+}
+
+fn object(fields: Vec<(&str, Json)>) -> Json {
+    let mut object = Object::new();
+    for (key, value) in fields {
+        object.insert(key.to_string(), value);
+    }
+    Json::Object(object)
+}
+
+fn message(text: &str) -> Json {
+    object(vec![("text", text.to_json())])
+}
+
+fn artifact_location(uri: &str) -> Json {
+    object(vec![("uri", uri.to_json())])
+}
+
+impl ToJson for SarifLog {
+    fn to_json(&self) -> Json {
+        let mut driver = vec![
+            ("name", "rustc".to_json()),
+            ("informationUri", "https://www.rust-lang.org/".to_json()),
+            ("rules", self.rules.to_json()),
+        ];
+        if let Some(version) = option_env!("CFG_RELEASE") {
+            driver.push(("semanticVersion", version.to_json()));
+        }
+        let run = object(vec![
+            ("tool", object(vec![("driver", object(driver))])),
+            ("columnKind", "unicodeCodePoints".to_json()),
+            ("results", self.results.to_json()),
+        ]);
+        object(vec![
+            ("$schema", "https://json.schemastore.org/sarif-2.1.0.json".to_json()),
+            ("version", "2.1.0".to_json()),
+            ("runs", Json::Array(vec![run])),
+        ])
+    }
+}
+
+impl ToJson for Rule {
+    fn to_json(&self) -> Json {
+        let mut fields = vec![("id", self.id.to_json())];
+        if let Some(ref help_uri) = self.help_uri {
+            fields.push(("helpUri", help_uri.to_json()));
+        }
+        object(fields)
+    }
+}
+
+impl ToJson for SarifResult {
+    fn to_json(&self) -> Json {
+        let mut fields = vec![("level", self.level.to_json()), ("message", message(&self.message))];
+        if let Some((ref id, index)) = self.rule {
+            fields.push(("ruleId", id.to_json()));
+            fields.push(("ruleIndex", index.to_json()));
+        }
+        if !self.locations.is_empty() {
+            fields.push(("locations", self.locations.to_json()));
+        }
+        if !self.related_locations.is_empty() {
+            fields.push(("relatedLocations", self.related_locations.to_json()));
+        }
+        if !self.fixes.is_empty() {
+            fields.push(("fixes", self.fixes.to_json()));
+        }
+        object(fields)
+    }
+}
+
+impl ToJson for Location {
+    fn to_json(&self) -> Json {
+        let mut fields = Vec::new();
+        if let Some(ref physical) = self.physical {
+            fields.push(("physicalLocation", physical.to_json()));
+        }
+        if let Some(ref text) = self.message {
+            fields.push(("message", message(text)));
+        }
+        object(fields)
+    }
+}
+
+impl ToJson for PhysicalLocation {
+    fn to_json(&self) -> Json {
+        object(vec![
+            ("artifactLocation", artifact_location(&self.uri)),
+            ("region", self.region.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Region {
+    fn to_json(&self) -> Json {
+        object(vec![
+            ("startLine", self.start_line.to_json()),
+            ("startColumn", self.start_column.to_json()),
+            ("endLine", self.end_line.to_json()),
+            ("endColumn", self.end_column.to_json()),
+            ("byteOffset", self.byte_offset.to_json()),
+            ("byteLength", self.byte_length.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Fix {
+    fn to_json(&self) -> Json {
+        object(vec![
+            ("description", message(&self.description)),
+            ("artifactChanges", self.changes.to_json()),
+        ])
+    }
+}
+
+impl ToJson for ArtifactChange {
+    fn to_json(&self) -> Json {
+        object(vec![
+            ("artifactLocation", artifact_location(&self.uri)),
+            ("replacements", self.replacements.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Replacement {
+    fn to_json(&self) -> Json {
+        object(vec![
+            ("deletedRegion", self.deleted_region.to_json()),
+            ("insertedContent", message(&self.inserted_text)),
+        ])
+    }
+}
@@ -0,0 +1,129 @@
+use super::*;
+
+use crate::Handler;
+use rustc_serialize::json;
+use rustc_span::BytePos;
+
+use std::path::Path;
+use std::str;
+use std::sync::{Arc, Mutex};
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+fn with_default_globals(f: impl FnOnce()) {
+    let globals = rustc_span::Globals::new(rustc_span::edition::DEFAULT_EDITION);
+    rustc_span::GLOBALS.set(&globals, || rustc_span::GLOBALS.set(&globals, f))
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+/// Runs `f` against a handler with a SARIF emitter for a source file `test.rs`
+/// containing `code`, and returns the log that was written.
+fn emit(code: &str, f: impl FnOnce(&Handler)) -> Json {
+    let output = Arc::new(Mutex::new(Vec::new()));
+
+    with_default_globals(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+
+        let emitter = SarifEmitter::new(Box::new(Shared { data: output.clone() }), None, sm);
+        let handler = Handler::with_emitter(true, None, Box::new(emitter));
+        f(&handler);
+    });
+
+    let bytes = output.lock().unwrap();
+    json::from_str(str::from_utf8(&bytes).unwrap()).unwrap()
+}
+
+fn get<'a>(json: &'a Json, path: &[&str]) -> &'a Json {
+    path.iter().fold(json, |json, key| match key.parse::<usize>() {
+        Ok(index) => &json.as_array().unwrap()[index],
+        Err(_) => json.find(key).unwrap_or_else(|| panic!("no `{}` in {}", key, json)),
+    })
+}
+
+#[test]
+fn empty_log() {
+    let log = emit("", |_| {});
+    assert_eq!(get(&log, &["version"]), &Json::String("2.1.0".to_string()));
+    assert_eq!(get(&log, &["runs", "0", "results"]), &Json::Array(vec![]));
+}
+
+#[test]
+fn result_with_rule_and_location() {
+    let log = emit("fn main() {\n    foo;\n}\n", |handler| {
+        handler
+            .struct_span_err_with_code(span(16, 19), "oops", DiagnosticId::Error("E0425".into()))
+            .emit();
+        handler.struct_span_warn(span(0, 2), "careful").emit();
+    });
+
+    let rules = get(&log, &["runs", "0", "tool", "driver", "rules"]).as_array().unwrap();
+    assert_eq!(rules.len(), 1);
+    assert_eq!(get(&rules[0], &["id"]).as_string(), Some("E0425"));
+
+    let result = get(&log, &["runs", "0", "results", "0"]);
+    assert_eq!(get(result, &["ruleId"]).as_string(), Some("E0425"));
+    assert_eq!(get(result, &["ruleIndex"]).as_u64(), Some(0));
+    assert_eq!(get(result, &["level"]).as_string(), Some("error"));
+    assert_eq!(get(result, &["message", "text"]).as_string(), Some("oops"));
+
+    let location = get(result, &["locations", "0", "physicalLocation"]);
+    assert_eq!(get(location, &["artifactLocation", "uri"]).as_string(), Some("test.rs"));
+    let region = get(location, &["region"]);
+    assert_eq!(get(region, &["startLine"]).as_u64(), Some(2));
+    assert_eq!(get(region, &["startColumn"]).as_u64(), Some(5));
+    assert_eq!(get(region, &["endColumn"]).as_u64(), Some(8));
+    assert_eq!(get(region, &["byteOffset"]).as_u64(), Some(16));
+    assert_eq!(get(region, &["byteLength"]).as_u64(), Some(3));
+
+    let warning = get(&log, &["runs", "0", "results", "1"]);
+    assert_eq!(get(warning, &["level"]).as_string(), Some("warning"));
+    assert!(warning.find("ruleId").is_none());
+}
+
+#[test]
+fn related_locations_and_fixes() {
+    let log = emit("let x = 1;\n", |handler| {
+        handler
+            .struct_span_err(span(4, 5), "bad name")
+            .span_label(span(8, 9), "initialized here")
+            .note("a note without a span")
+            .span_suggestion(span(4, 5), "rename it", "y".into(), Applicability::MachineApplicable)
+            .span_suggestion(span(4, 5), "or this", "z".into(), Applicability::MaybeIncorrect)
+            .emit();
+    });
+
+    let result = get(&log, &["runs", "0", "results", "0"]);
+    let related = get(result, &["relatedLocations"]).as_array().unwrap();
+    assert_eq!(related.len(), 2);
+    assert_eq!(get(&related[0], &["message", "text"]).as_string(), Some("initialized here"));
+    assert_eq!(get(&related[0], &["physicalLocation", "region", "byteOffset"]).as_u64(), Some(8));
+    assert_eq!(
+        get(&related[1], &["message", "text"]).as_string(),
+        Some("note: a note without a span")
+    );
+    assert!(related[1].find("physicalLocation").is_none());
+
+    // Only the machine-applicable suggestion is a fix.
+    let fixes = get(result, &["fixes"]).as_array().unwrap();
+    assert_eq!(fixes.len(), 1);
+    assert_eq!(get(&fixes[0], &["description", "text"]).as_string(), Some("rename it"));
+    let replacement = get(&fixes[0], &["artifactChanges", "0", "replacements", "0"]);
+    assert_eq!(get(replacement, &["insertedContent", "text"]).as_string(), Some("y"));
+    assert_eq!(get(replacement, &["deletedRegion", "byteOffset"]).as_u64(), Some(4));
+}
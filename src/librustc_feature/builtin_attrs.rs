@@ -566,6 +566,7 @@ pub const BUILTIN_ATTRIBUTES: &[BuiltinAttribute] = &[
     rustc_attr!(TEST, rustc_synthetic, Whitelisted, template!(Word)),
     rustc_attr!(TEST, rustc_symbol_name, Whitelisted, template!(Word)),
     rustc_attr!(TEST, rustc_def_path, Whitelisted, template!(Word)),
+    rustc_attr!(TEST, rustc_polymorphize_error, Whitelisted, template!(Word)),
     rustc_attr!(TEST, rustc_mir, Whitelisted, template!(List: "arg1, arg2, ...")),
     rustc_attr!(TEST, rustc_dump_program_clauses, Whitelisted, template!(Word)),
     rustc_attr!(TEST, rustc_dump_env_program_clauses, Whitelisted, template!(Word)),
@@ -9,6 +9,9 @@
 //!   current node).
 //! - `#[rustc_clean(cfg="rev2")]` same as above, except that the
 //!   fingerprints must be the SAME (along with all other fingerprints).
+//! - `#[rustc_clean(cfg="rev2", loaded_from_disk="mir_borrowck")]` additionally
+//!   asserts that the result of `DepNode::mir_borrowck(X)` was loaded from the
+//!   on-disk cache rather than recomputed.
 //!
 //! Errors are reported if we are in the suitable configuration but
 //! the required condition is not met.
@@ -33,6 +36,7 @@ use std::vec::Vec;
 const EXCEPT: Symbol = sym::except;
 const LABEL: Symbol = sym::label;
 const CFG: Symbol = sym::cfg;
+const LOADED_FROM_DISK: Symbol = sym::loaded_from_disk;
 
 // Base and Extra labels to build up the labels
 
@@ -136,15 +140,16 @@ type Labels = FxHashSet<String>;
 struct Assertion {
     clean: Labels,
     dirty: Labels,
+    loaded_from_disk: Labels,
 }
 
 impl Assertion {
     fn from_clean_labels(labels: Labels) -> Assertion {
-        Assertion { clean: labels, dirty: Labels::default() }
+        Assertion { clean: labels, dirty: Labels::default(), loaded_from_disk: Labels::default() }
     }
 
     fn from_dirty_labels(labels: Labels) -> Assertion {
-        Assertion { clean: Labels::default(), dirty: labels }
+        Assertion { clean: Labels::default(), dirty: labels, loaded_from_disk: Labels::default() }
     }
 }
 
@@ -193,7 +198,7 @@ impl DirtyCleanVisitor<'tcx> {
             // skip: not the correct `cfg=`
             return None;
         }
-        let assertion = if let Some(labels) = self.labels(attr) {
+        let mut assertion = if let Some(labels) = self.labels(attr) {
             if is_clean {
                 Assertion::from_clean_labels(labels)
             } else {
@@ -202,6 +207,7 @@ impl DirtyCleanVisitor<'tcx> {
         } else {
             self.assertion_auto(item_id, attr, is_clean)
         };
+        assertion.loaded_from_disk = self.loaded_from_disk(attr);
         Some(assertion)
     }
 
@@ -224,9 +230,9 @@ impl DirtyCleanVisitor<'tcx> {
             }
         }
         if is_clean {
-            Assertion { clean: auto, dirty: except }
+            Assertion { clean: auto, dirty: except, loaded_from_disk: Labels::default() }
         } else {
-            Assertion { clean: except, dirty: auto }
+            Assertion { clean: except, dirty: auto, loaded_from_disk: Labels::default() }
         }
     }
 
@@ -240,6 +246,17 @@ impl DirtyCleanVisitor<'tcx> {
         None
     }
 
+    /// `loaded_from_disk=` attribute value
+    fn loaded_from_disk(&self, attr: &Attribute) -> Labels {
+        for item in attr.meta_item_list().unwrap_or_else(Vec::new) {
+            if item.check_name(LOADED_FROM_DISK) {
+                let value = expect_associated_value(self.tcx, &item);
+                return self.resolve_labels(&item, &value.as_str());
+            }
+        }
+        Labels::default()
+    }
+
     /// `except=` attribute value
     fn except(&self, attr: &Attribute) -> Labels {
         for item in attr.meta_item_list().unwrap_or_else(Vec::new) {
@@ -432,6 +449,18 @@ impl DirtyCleanVisitor<'tcx> {
         }
     }
 
+    fn assert_loaded_from_disk(&self, item_span: Span, dep_node: DepNode) {
+        debug!("assert_loaded_from_disk({:?})", dep_node);
+
+        if !self.tcx.dep_graph.debug_was_loaded_from_disk(dep_node) {
+            let dep_node_str = self.dep_node_str(&dep_node);
+            self.tcx.sess.span_err(
+                item_span,
+                &format!("`{}` should have been loaded from disk but it was not", dep_node_str),
+            );
+        }
+    }
+
     fn check_item(&mut self, item_id: hir::HirId, item_span: Span) {
         let def_id = self.tcx.hir().local_def_id(item_id);
         for attr in self.tcx.get_attrs(def_id.to_def_id()).iter() {
@@ -446,6 +475,9 @@ impl DirtyCleanVisitor<'tcx> {
             for dep_node in self.dep_nodes(&assertion.dirty, def_id.to_def_id()) {
                 self.assert_dirty(item_span, dep_node);
             }
+            for dep_node in self.dep_nodes(&assertion.loaded_from_disk, def_id.to_def_id()) {
+                self.assert_loaded_from_disk(item_span, dep_node);
+            }
         }
     }
 }
@@ -50,6 +50,12 @@
 //! any more and will delete those. It will also delete any finalized session
 //! directories for a given crate except for the most recent one.
 //!
+//! The incremental compilation directory may also hold the data of crates
+//! that are not compiled anymore, e.g. because their version or compilation
+//! flags changed. With `-Z incremental-max-age` or `-Z incremental-max-size`,
+//! the compiler also deletes the data of other crates that have not been
+//! compiled for a while, or that is needed to stay within the size limit.
+//!
 //! ## Synchronization
 //!
 //! There is some synchronization needed in order for the compiler to be able to
@@ -384,6 +390,7 @@ pub fn finalize_session_directory(sess: &Session, svh: Svh) {
     }
 
     let _ = garbage_collect_session_directories(sess);
+    let _ = garbage_collect_incr_comp_directory(sess);
 }
 
 pub fn delete_all_session_dir_contents(sess: &Session) -> io::Result<()> {
@@ -659,7 +666,12 @@ pub fn garbage_collect_session_directories(sess: &Session) -> io::Result<()> {
         session_directory.display()
     );
 
-    let crate_directory = session_directory.parent().unwrap();
+    garbage_collect_crate_directory(sess, session_directory.parent().unwrap())
+}
+
+/// Deletes the session directories of the crate directory `crate_directory`
+/// that are not in use anymore, except for the most recent finalized one.
+fn garbage_collect_crate_directory(sess: &Session, crate_directory: &Path) -> io::Result<()> {
     debug!(
         "garbage_collect_session_directories() - crate directory: {}",
         crate_directory.display()
@@ -902,6 +914,227 @@ pub fn garbage_collect_session_directories(sess: &Session) -> io::Result<()> {
     Ok(())
 }
 
+/// Deletes the incremental compilation data of other crates according to
+/// `-Z incremental-max-age` and `-Z incremental-max-size`.
+///
+/// The crate directories in the incremental compilation directory are first
+/// compacted, so that each of them only holds its most recent session. The
+/// directories of crates that have not been compiled for longer than the
+/// maximum age are then deleted, followed by the least recently compiled ones
+/// until the incremental compilation directory is within the maximum size.
+/// The crate directory of the current session is never deleted.
+///
+/// Sizes are the sums of the sizes of all files, so files that are
+/// hard-linked into several session directories are counted more than once.
+pub fn garbage_collect_incr_comp_directory(sess: &Session) -> io::Result<()> {
+    let max_age = sess.opts.debugging_opts.incremental_max_age;
+    let max_size = sess.opts.debugging_opts.incremental_max_size;
+    if max_age.is_none() && max_size.is_none() {
+        return Ok(());
+    }
+
+    let max_age = max_age.map(|days| Duration::from_secs(days as u64 * 24 * 60 * 60));
+    let max_size = max_size.map(|mib| mib as u64 * 1024 * 1024);
+
+    let session_directory = sess.incr_comp_session_dir();
+    let current_crate_directory = session_directory.parent().unwrap();
+    let incr_comp_directory = current_crate_directory.parent().unwrap();
+
+    let mut total_size = dir_size(current_crate_directory);
+    let mut crate_directories = vec![];
+
+    for dir_entry in incr_comp_directory.read_dir()? {
+        let path = match dir_entry {
+            Ok(dir_entry) => dir_entry.path(),
+            // Ignore any errors
+            _ => continue,
+        };
+
+        if path == current_crate_directory || !path.is_dir() {
+            continue;
+        }
+
+        let _ = garbage_collect_crate_directory(sess, &path);
+
+        // Directories that do not contain any session directories are not
+        // ours to delete.
+        let last_used = match last_session_timestamp(&path) {
+            Some(last_used) => last_used,
+            None => continue,
+        };
+
+        let size = dir_size(&path);
+        total_size += size;
+        crate_directories.push((last_used, path, size));
+    }
+
+    // Least recently compiled first
+    crate_directories.sort_by_key(|&(last_used, ..)| last_used);
+
+    let now = SystemTime::now();
+    let mut crates_deleted = 0;
+    let mut bytes_reclaimed = 0;
+
+    for (last_used, path, size) in crate_directories {
+        let too_old = max_age.map_or(false, |max_age| {
+            now.duration_since(last_used).map_or(false, |age| age > max_age)
+        });
+        let too_large = max_size.map_or(false, |max_size| total_size > max_size);
+
+        // Like the session directories above, crates that were just
+        // compiled are left alone, as another compiler instance might still
+        // be about to lock their new session directory.
+        if !(too_old || too_large) || !is_old_enough_to_be_collected(last_used) {
+            continue;
+        }
+
+        if delete_crate_directory(sess, &path) {
+            total_size -= size;
+            bytes_reclaimed += size;
+            crates_deleted += 1;
+        }
+    }
+
+    if sess.opts.debugging_opts.incremental_info {
+        println!(
+            "[incremental] garbage collection: deleted the data of {} crates, \
+                  reclaiming {} KiB",
+            crates_deleted,
+            bytes_reclaimed / 1024
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns the time of the most recent session in `crate_directory`, if it
+/// contains any session directories.
+fn last_session_timestamp(crate_directory: &Path) -> Option<SystemTime> {
+    crate_directory
+        .read_dir()
+        .ok()?
+        .filter_map(|dir_entry| {
+            let entry_name = dir_entry.ok()?.file_name();
+            let entry_name = entry_name.to_string_lossy();
+            if is_session_directory(&entry_name) {
+                extract_timestamp_from_session_dir(&entry_name).ok()
+            } else {
+                None
+            }
+        })
+        .max()
+}
+
+/// Deletes a crate directory with all of its session directories, unless any
+/// of them is still in use. Returns whether the directory was deleted.
+fn delete_crate_directory(sess: &Session, crate_directory: &Path) -> bool {
+    debug!("delete_crate_directory() - deleting `{}`", crate_directory.display());
+
+    let dir_entries = match crate_directory.read_dir() {
+        Ok(dir_entries) => dir_entries,
+        Err(_) => return false,
+    };
+
+    // Take all the directory locks first, so that we never delete a session
+    // directory that is being read from or written to.
+    let mut locks = vec![];
+    for dir_entry in dir_entries {
+        let dir_entry = match dir_entry {
+            Ok(dir_entry) => dir_entry,
+            Err(_) => return false,
+        };
+
+        if is_session_directory_lock_file(&dir_entry.file_name().to_string_lossy()) {
+            match flock::Lock::new(
+                &dir_entry.path(),
+                false, // don't wait
+                false, // don't create the lock-file
+                true,  // get an exclusive lock
+            ) {
+                Ok(lock) => locks.push(lock),
+                Err(_) => {
+                    debug!("delete_crate_directory() - not deleting, still in use");
+                    return false;
+                }
+            }
+        }
+    }
+
+    let result = safe_remove_dir_all(crate_directory);
+
+    // Let's make it explicit that the file locks are released at this point,
+    // or rather, that we held on to them until here
+    mem::drop(locks);
+
+    match result {
+        Ok(()) => true,
+        Err(err) => {
+            sess.warn(&format!(
+                "Failed to garbage collect incremental compilation \
+                                directory `{}`: {}",
+                crate_directory.display(),
+                err
+            ));
+            false
+        }
+    }
+}
+
+/// Deletes the files in the current session directory that the compiler
+/// neither writes itself nor are among `work_product_files`, returning the
+/// number of bytes freed. Such files are left over from earlier sessions and
+/// would otherwise be copied into every new session directory.
+pub fn delete_unreferenced_files(sess: &Session, work_product_files: &FxHashSet<&str>) -> u64 {
+    let dir_entries = match sess.incr_comp_session_dir().read_dir() {
+        Ok(dir_entries) => dir_entries,
+        Err(_) => return 0,
+    };
+
+    let mut bytes_freed = 0;
+    for dir_entry in dir_entries.filter_map(Result::ok) {
+        let file_name = dir_entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        if file_name == DEP_GRAPH_FILENAME
+            || file_name == WORK_PRODUCTS_FILENAME
+            || file_name == QUERY_CACHE_FILENAME
+            || work_product_files.contains(&*file_name)
+        {
+            continue;
+        }
+
+        let size = dir_entry.metadata().map_or(0, |metadata| metadata.len());
+        match safe_remove_file(&dir_entry.path()) {
+            Ok(()) => bytes_freed += size,
+            Err(err) => {
+                sess.warn(&format!(
+                    "file-system error deleting unreferenced file `{}`: {}",
+                    dir_entry.path().display(),
+                    err
+                ));
+            }
+        }
+    }
+
+    bytes_freed
+}
+
+/// The total size of the files in `path`, ignoring any errors.
+fn dir_size(path: &Path) -> u64 {
+    let dir_entries = match path.read_dir() {
+        Ok(dir_entries) => dir_entries,
+        Err(_) => return 0,
+    };
+
+    dir_entries
+        .filter_map(Result::ok)
+        .map(|dir_entry| match dir_entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => dir_size(&dir_entry.path()),
+            Ok(_) => dir_entry.metadata().map_or(0, |metadata| metadata.len()),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
 fn all_except_most_recent(
     deletion_candidates: Vec<(SystemTime, PathBuf, Option<flock::Lock>)>,
 ) -> FxHashMap<PathBuf, Option<flock::Lock>> {
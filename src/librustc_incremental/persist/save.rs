@@ -1,4 +1,4 @@
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::join;
 use rustc_middle::dep_graph::{DepGraph, DepKind, WorkProduct, WorkProductId};
 use rustc_middle::ty::TyCtxt;
@@ -45,6 +45,29 @@ pub fn save_dep_graph(tcx: TyCtxt<'_>) {
         );
 
         dirty_clean::check_dirty_clean_annotations(tcx);
+
+        // Files that are not part of any work-product are left behind when
+        // deleting an old work-product fails. Get rid of them as well, so they
+        // are not copied into all future sessions. This is done here rather
+        // than when saving the work-product index, as that does not happen if
+        // we do not get to codegen, e.g. in check builds. New work-products
+        // are only copied into the session directory after this point, so the
+        // only files that may still be needed are those of the work-products
+        // the dep-graph was loaded with.
+        let work_product_files: FxHashSet<&str> = tcx
+            .dep_graph
+            .previous_work_products()
+            .values()
+            .flat_map(|wp| wp.saved_files.iter().map(|&(_, ref name)| &name[..]))
+            .collect();
+        let bytes_freed = delete_unreferenced_files(sess, &work_product_files);
+        if sess.opts.debugging_opts.incremental_info && bytes_freed > 0 {
+            println!(
+                "[incremental] session directory: deleted unreferenced files, \
+                      reclaiming {} KiB",
+                bytes_freed / 1024
+            );
+        }
     })
 }
 
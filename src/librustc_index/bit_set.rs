@@ -4,6 +4,7 @@ use std::fmt;
 use std::iter;
 use std::marker::PhantomData;
 use std::mem;
+use std::ops::{BitAnd, BitAndAssign, BitOrAssign, Not, Range, Shl};
 use std::slice;
 
 #[cfg(test)]
@@ -974,6 +975,127 @@ impl<R: Idx, C: Idx> SparseBitMatrix<R, C> {
     }
 }
 
+/// Integral type used to represent the bit set.
+pub trait FiniteBitSetTy:
+    BitAnd<Output = Self>
+    + BitAndAssign
+    + BitOrAssign
+    + Clone
+    + Copy
+    + Shl
+    + Not<Output = Self>
+    + PartialEq
+    + Sized
+{
+    /// Size of the domain representable by this type, e.g. 64 for `u64`.
+    const DOMAIN_SIZE: u32;
+
+    /// Value which represents the `FiniteBitSet` having every bit set.
+    const FILLED: Self;
+    /// Value which represents the `FiniteBitSet` having no bits set.
+    const EMPTY: Self;
+
+    /// Value for one as the integral type.
+    const ONE: Self;
+    /// Value for zero as the integral type.
+    const ZERO: Self;
+
+    /// Perform a checked left shift on the integral type.
+    fn checked_shl(self, rhs: u32) -> Option<Self>;
+    /// Perform a checked right shift on the integral type.
+    fn checked_shr(self, rhs: u32) -> Option<Self>;
+}
+
+macro_rules! impl_finite_bit_set_ty {
+    ($($ty:ty),*) => {$(
+        impl FiniteBitSetTy for $ty {
+            const DOMAIN_SIZE: u32 = <$ty>::MAX.count_ones();
+
+            const FILLED: Self = Self::MAX;
+            const EMPTY: Self = Self::MIN;
+
+            const ONE: Self = 1;
+            const ZERO: Self = 0;
+
+            fn checked_shl(self, rhs: u32) -> Option<Self> {
+                self.checked_shl(rhs)
+            }
+
+            fn checked_shr(self, rhs: u32) -> Option<Self> {
+                self.checked_shr(rhs)
+            }
+        }
+    )*};
+}
+
+impl_finite_bit_set_ty!(u32, u64, u128);
+
+/// A fixed-sized bitset type represented by an integer type. Setting or
+/// clearing indices outside of the range representable by `T` has no effect,
+/// and `contains` returns `None` for them.
+#[derive(Copy, Clone, Eq, PartialEq, RustcDecodable, RustcEncodable)]
+pub struct FiniteBitSet<T: FiniteBitSetTy>(pub T);
+
+impl<T: FiniteBitSetTy> FiniteBitSet<T> {
+    /// Creates a new, empty bitset.
+    pub fn new_empty() -> Self {
+        Self(T::EMPTY)
+    }
+
+    /// Sets the `index`th bit.
+    pub fn set(&mut self, index: u32) {
+        self.0 |= T::ONE.checked_shl(index).unwrap_or(T::ZERO);
+    }
+
+    /// Unsets the `index`th bit.
+    pub fn clear(&mut self, index: u32) {
+        self.0 &= !T::ONE.checked_shl(index).unwrap_or(T::ZERO);
+    }
+
+    /// Sets the `i`th to `j`th bits.
+    pub fn set_range(&mut self, range: Range<u32>) {
+        let bits = T::FILLED
+            .checked_shl(range.end - range.start)
+            .unwrap_or(T::ZERO)
+            .not()
+            .checked_shl(range.start)
+            .unwrap_or(T::ZERO);
+        self.0 |= bits;
+    }
+
+    /// Is the set empty?
+    pub fn is_empty(&self) -> bool {
+        self.0 == T::EMPTY
+    }
+
+    /// Returns `true` if `index` is representable by the bitset.
+    pub fn within_domain(&self, index: u32) -> bool {
+        index < T::DOMAIN_SIZE
+    }
+
+    /// Returns whether the `index`th bit is set, or `None` if `index` is out
+    /// of the domain of the bitset.
+    pub fn contains(&self, index: u32) -> Option<bool> {
+        if self.within_domain(index) {
+            Some((self.0.checked_shr(index).unwrap_or(T::ONE) & T::ONE) == T::ONE)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: FiniteBitSetTy> Default for FiniteBitSet<T> {
+    fn default() -> Self {
+        Self::new_empty()
+    }
+}
+
+impl<T: FiniteBitSetTy + fmt::Binary> fmt::Debug for FiniteBitSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FiniteBitSet({:#b})", self.0)
+    }
+}
+
 #[inline]
 fn num_words<T: Idx>(domain_size: T) -> usize {
     (domain_size.index() + WORD_BITS - 1) / WORD_BITS
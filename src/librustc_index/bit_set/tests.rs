@@ -281,6 +281,30 @@ fn sparse_matrix_iter() {
     assert!(iter.next().is_none());
 }
 
+#[test]
+fn finite_bit_set() {
+    let mut set: FiniteBitSet<u32> = FiniteBitSet::new_empty();
+    assert!(set.is_empty());
+
+    set.set_range(2..5);
+    assert_eq!(set.contains(1), Some(false));
+    assert_eq!(set.contains(2), Some(true));
+    assert_eq!(set.contains(4), Some(true));
+    assert_eq!(set.contains(5), Some(false));
+
+    set.clear(3);
+    assert_eq!(set.contains(3), Some(false));
+    set.set(31);
+    assert_eq!(set.contains(31), Some(true));
+
+    // Indices outside of the domain are never stored.
+    set.set(32);
+    assert_eq!(set.contains(32), None);
+
+    set.set_range(0..32);
+    assert_eq!(set.0, u32::MAX);
+}
+
 /// Merge dense hybrid set into empty sparse hybrid set.
 #[bench]
 fn union_hybrid_sparse_empty_to_dense(b: &mut Bencher) {
@@ -193,6 +193,7 @@ pub fn run_compiler_in_existing_thread_pool<R>(
 
     let r = {
         let _sess_abort_error = OnDrop(|| {
+            compiler.sess.emit_future_incompat_report();
             compiler.sess.diagnostic().print_error_count(registry);
         });
 
@@ -674,6 +674,8 @@ pub fn prepare_outputs(
 }
 
 pub fn default_provide(providers: &mut ty::query::Providers<'_>) {
+    providers.typeck_analysis = typeck_analysis;
+    providers.borrowck_analysis = borrowck_analysis;
     providers.analysis = analysis;
     proc_macro_decls::provide(providers);
     plugin::build::provide(providers);
@@ -712,6 +714,10 @@ impl<'tcx> QueryContext<'tcx> {
     pub fn print_stats(&mut self) {
         self.enter(ty::query::print_stats)
     }
+
+    pub fn item_profile_summary(&mut self) -> Option<ty::query::ItemProfileSummary> {
+        self.enter(|tcx| tcx.item_profiler.as_ref().and_then(|profiler| profiler.summary(tcx)))
+    }
 }
 
 pub fn create_global_ctxt<'tcx>(
@@ -743,6 +749,10 @@ pub fn create_global_ctxt<'tcx>(
         callback(sess, &mut local_providers, &mut extern_providers);
     }
 
+    if sess.opts.collect_borrowck_facts {
+        rustc_mir::borrow_check::consumers::provide_collecting_facts(&mut local_providers);
+    }
+
     let gcx = sess.time("setup_global_ctxt", || {
         global_ctxt.init_locking(|| {
             TyCtxt::create_global_ctxt(
@@ -770,11 +780,13 @@ pub fn create_global_ctxt<'tcx>(
     QueryContext(gcx)
 }
 
-/// Runs the resolution, type-checking, region checking and other
-/// miscellaneous analysis passes on the crate.
-fn analysis(tcx: TyCtxt<'_>, cnum: CrateNum) -> Result<()> {
+/// Runs type-checking and the miscellaneous passes preceding it on the crate.
+fn typeck_analysis(tcx: TyCtxt<'_>, cnum: CrateNum) -> Result<()> {
     assert_eq!(cnum, LOCAL_CRATE);
 
+    // Items are checked in parallel, report their errors in a fixed order.
+    let _ordered = tcx.sess.order_diagnostics_across_threads();
+
     rustc_passes::hir_id_validator::check_crate(tcx);
 
     let sess = tcx.sess;
@@ -805,7 +817,19 @@ fn analysis(tcx: TyCtxt<'_>, cnum: CrateNum) -> Result<()> {
     });
 
     // passes are timed inside typeck
-    typeck::check_crate(tcx)?;
+    typeck::check_crate(tcx)
+}
+
+/// Runs match checking, liveness checking and borrow checking on the crate,
+/// after type-checking it. This succeeds even if borrow checking reported
+/// errors, so that the remaining analysis passes get to report theirs.
+fn borrowck_analysis(tcx: TyCtxt<'_>, cnum: CrateNum) -> Result<()> {
+    assert_eq!(cnum, LOCAL_CRATE);
+
+    tcx.typeck_analysis(LOCAL_CRATE)?;
+    let _ordered = tcx.sess.order_diagnostics_across_threads();
+
+    let sess = tcx.sess;
 
     sess.time("misc_checking_2", || {
         parallel!(
@@ -837,6 +861,19 @@ fn analysis(tcx: TyCtxt<'_>, cnum: CrateNum) -> Result<()> {
         tcx.par_body_owners(|def_id| tcx.ensure().mir_borrowck(def_id.to_def_id()));
     });
 
+    Ok(())
+}
+
+/// Runs the resolution, type-checking, region checking and other
+/// miscellaneous analysis passes on the crate.
+fn analysis(tcx: TyCtxt<'_>, cnum: CrateNum) -> Result<()> {
+    assert_eq!(cnum, LOCAL_CRATE);
+
+    tcx.borrowck_analysis(LOCAL_CRATE)?;
+    let _ordered = tcx.sess.order_diagnostics_across_threads();
+
+    let sess = tcx.sess;
+
     sess.time("dumping_chalk_like_clauses", || {
         rustc_traits::lowering::dump_program_clauses(tcx);
     });
@@ -11,6 +11,7 @@ use rustc_incremental::DepGraphFuture;
 use rustc_lint::LintStore;
 use rustc_middle::arena::Arena;
 use rustc_middle::dep_graph::DepGraph;
+use rustc_middle::ty::query::ItemProfileSummary;
 use rustc_middle::ty::steal::Steal;
 use rustc_middle::ty::{GlobalCtxt, ResolverOutputs, TyCtxt};
 use rustc_session::config::{OutputFilenames, OutputType};
@@ -336,12 +337,22 @@ impl<'tcx> Queries<'tcx> {
         let sess = self.session().clone();
         let codegen_backend = self.codegen_backend().clone();
 
+        // The LLVM times are only known once codegen is joined, so the summary
+        // is printed by the linker rather than when the global context is
+        // dropped.
+        let item_profile = if sess.opts.debugging_opts.self_profile_summary.is_some() {
+            self.global_ctxt()?.peek_mut().item_profile_summary()
+        } else {
+            None
+        };
+
         Ok(Linker {
             sess,
             dep_graph: dep_graph.peek().clone(),
             prepare_outputs: prepare_outputs.take(),
             ongoing_codegen: ongoing_codegen.take(),
             codegen_backend,
+            item_profile,
         })
     }
 }
@@ -352,12 +363,19 @@ pub struct Linker {
     prepare_outputs: OutputFilenames,
     ongoing_codegen: Box<dyn Any>,
     codegen_backend: Lrc<Box<dyn CodegenBackend>>,
+    item_profile: Option<ItemProfileSummary>,
 }
 
 impl Linker {
     pub fn link(self) -> Result<()> {
         let codegen_results =
-            self.codegen_backend.join_codegen(self.ongoing_codegen, &self.sess, &self.dep_graph)?;
+            self.codegen_backend.join_codegen(self.ongoing_codegen, &self.sess, &self.dep_graph);
+        if let (Some(item_profile), Some(format)) =
+            (self.item_profile, self.sess.opts.debugging_opts.self_profile_summary)
+        {
+            item_profile.print(format);
+        }
+        let codegen_results = codegen_results?;
         let prof = self.sess.prof.clone();
         let dep_graph = self.dep_graph;
         prof.generic_activity("drop_dep_graph").run(move || drop(dep_graph));
@@ -390,6 +408,16 @@ impl Compiler {
             }
         }
 
+        // If compilation stopped before a `Linker` took the summary, print it
+        // here.
+        if let Some(format) = self.session().opts.debugging_opts.self_profile_summary {
+            if let Ok(gcx) = queries.global_ctxt() {
+                if let Some(item_profile) = gcx.peek_mut().item_profile_summary() {
+                    item_profile.print(format);
+                }
+            }
+        }
+
         _timer = Some(self.session().timer("free_global_ctxt"));
 
         ret
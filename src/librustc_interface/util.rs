@@ -8,7 +8,7 @@ use rustc_codegen_ssa::traits::CodegenBackend;
 use rustc_data_structures::fingerprint::Fingerprint;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 #[cfg(parallel_compiler)]
-use rustc_data_structures::jobserver;
+use rustc_data_structures::{jobserver, OnDrop};
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync::{Lock, Lrc};
 use rustc_errors::registry::Registry;
@@ -178,7 +178,20 @@ pub fn spawn_thread_pool<F: FnOnce() -> R + Send, R: Send>(
         config = config.stack_size(size);
     }
 
-    let with_pool = move |pool: &ThreadPool| pool.install(move || f());
+    let with_pool = move |pool: &ThreadPool| {
+        // This thread only waits while the pool runs `f`, so hand its jobserver
+        // token to the worker threads in the meantime. Otherwise they could not
+        // run at all when the jobserver has no other tokens to give out, e.g.
+        // when building with `-j1`. The worker running `f` acquires a token
+        // through the pool's `acquire_thread_handler` before it starts, and
+        // that token stands in for the implicit token the codegen coordinator
+        // assumes its thread owns. Workers that run out of work release their
+        // tokens when they go to sleep, so the pool holds no more tokens than
+        // it has threads doing work.
+        jobserver::release_thread();
+        let _reacquire = OnDrop(jobserver::acquire_thread);
+        pool.install(move || f())
+    };
 
     rustc_ast::with_globals(edition, || {
         rustc_ast::GLOBALS.with(|syntax_globals| {
@@ -99,6 +99,14 @@ impl<'tcx> DepContext for TyCtxt<'tcx> {
         self.sess.opts.debugging_opts.dep_tasks
     }
 
+    fn debug_query_dep_graph(&self) -> bool {
+        self.sess.opts.debugging_opts.query_dep_graph
+    }
+
+    fn incremental_explain(&self) -> Option<&str> {
+        self.sess.opts.debugging_opts.incremental_explain.as_deref()
+    }
+
     fn try_force_from_dep_node(&self, dep_node: &DepNode) -> bool {
         // FIXME: This match is just a workaround for incremental bugs and should
         // be removed. https://github.com/rust-lang/rust/issues/62649 is one such
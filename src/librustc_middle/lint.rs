@@ -3,7 +3,7 @@ use std::cmp;
 use crate::ich::StableHashingContext;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
-use rustc_errors::{DiagnosticBuilder, DiagnosticId};
+use rustc_errors::{Diagnostic, DiagnosticBuilder, DiagnosticId, FutureIncompatLint};
 use rustc_hir::HirId;
 use rustc_session::lint::{builtin, Level, Lint, LintId};
 use rustc_session::{DiagnosticMessageId, Session};
@@ -207,8 +207,31 @@ pub fn struct_lint_level<'s, 'd>(
         span: Option<MultiSpan>,
         decorate: Box<dyn for<'b> FnOnce(LintDiagnosticBuilder<'b>) + 'd>,
     ) {
+        // Future incompatible lints are collected whatever their level, so that
+        // the report also covers dependencies whose lints are capped. The lint
+        // is carried by a diagnostic, even if it is allowed, so that it is
+        // collected again when the diagnostic is replayed from the incremental
+        // cache.
+        let future_incompat_lint = match lint.future_incompatible {
+            Some(future_incompatible) if sess.opts.debugging_opts.emit_future_incompat_report => {
+                Some(FutureIncompatLint {
+                    lint: lint.name_lower(),
+                    level: level.as_str().to_string(),
+                    reference: future_incompatible.reference.to_string(),
+                    edition: future_incompatible.edition.map(|edition| edition.to_string()),
+                    span: span.clone().unwrap_or_else(MultiSpan::new),
+                })
+            }
+            _ => None,
+        };
+
         let mut err = match (level, span) {
             (Level::Allow, _) => {
+                if future_incompat_lint.is_some() {
+                    let mut diagnostic = Diagnostic::new(rustc_errors::Level::Allow, "");
+                    diagnostic.future_incompat = future_incompat_lint;
+                    sess.diagnostic().emit_diagnostic(&diagnostic);
+                }
                 return;
             }
             (Level::Warn, Some(span)) => sess.struct_span_warn(span, ""),
@@ -217,6 +240,8 @@ pub fn struct_lint_level<'s, 'd>(
             (Level::Deny | Level::Forbid, None) => sess.struct_err(""),
         };
 
+        err.future_incompat = future_incompat_lint;
+
         // Check for future incompatibility lints and issue a stronger warning.
         let lint_id = LintId::of(lint);
         let future_incompatible = lint.future_incompatible;
@@ -95,6 +95,19 @@ rustc_queries! {
             cache_on_disk_if { key.is_local() }
         }
 
+        /// Type-checks the crate. This is the first part of `analysis`.
+        query typeck_analysis(key: CrateNum) -> Result<(), ErrorReported> {
+            eval_always
+            desc { "type-checking this crate" }
+        }
+
+        /// Borrow-checks the crate, after `typeck_analysis`. This is the second
+        /// part of `analysis`.
+        query borrowck_analysis(key: CrateNum) -> Result<(), ErrorReported> {
+            eval_always
+            desc { "borrow-checking this crate" }
+        }
+
         query analysis(key: CrateNum) -> Result<(), ErrorReported> {
             eval_always
             desc { "running analysis passes on this crate" }
@@ -254,10 +267,14 @@ rustc_queries! {
         /// predicates (where-clauses) directly defined on it. This is
         /// equal to the `explicit_predicates_of` predicates plus the
         /// `inferred_outlives_of` predicates.
-        query predicates_defined_on(_: DefId) -> ty::GenericPredicates<'tcx> {}
+        query predicates_defined_on(key: DefId) -> ty::GenericPredicates<'tcx> {
+            cache_on_disk_if { key.is_local() }
+        }
 
         /// Returns the predicates written explicitly by the user.
-        query explicit_predicates_of(_: DefId) -> ty::GenericPredicates<'tcx> {}
+        query explicit_predicates_of(key: DefId) -> ty::GenericPredicates<'tcx> {
+            cache_on_disk_if { key.is_local() }
+        }
 
         /// Returns the inferred outlives predicates (e.g., for `struct
         /// Foo<'a, T> { x: &'a T }`, this would return `T: 'a`).
@@ -271,6 +288,7 @@ rustc_queries! {
         /// additional acyclicity requirements).
         query super_predicates_of(key: DefId) -> ty::GenericPredicates<'tcx> {
             desc { |tcx| "computing the supertraits of `{}`", tcx.def_path_str(key) }
+            cache_on_disk_if { key.is_local() }
         }
 
         /// To avoid cycles within the predicates of a single item we compute
@@ -309,6 +327,7 @@ rustc_queries! {
         /// `is_const_fn` function.
         query is_const_fn_raw(key: DefId) -> bool {
             desc { |tcx| "checking if item is const fn: `{}`", tcx.def_path_str(key) }
+            cache_on_disk_if { key.is_local() }
         }
 
         /// Returns `true` if this is a const `impl`. **Do not call this function manually.**
@@ -321,6 +340,7 @@ rustc_queries! {
 
         query asyncness(key: DefId) -> hir::IsAsync {
             desc { |tcx| "checking if the function is async: `{}`", tcx.def_path_str(key) }
+            cache_on_disk_if { key.is_local() }
         }
 
         /// Returns `true` if calls to the function may be promoted.
@@ -372,8 +392,12 @@ rustc_queries! {
             desc { |tcx| "collecting associated items of {}", tcx.def_path_str(key) }
         }
 
-        query impl_trait_ref(_: DefId) -> Option<ty::TraitRef<'tcx>> {}
-        query impl_polarity(_: DefId) -> ty::ImplPolarity {}
+        query impl_trait_ref(key: DefId) -> Option<ty::TraitRef<'tcx>> {
+            cache_on_disk_if { key.is_local() }
+        }
+        query impl_polarity(key: DefId) -> ty::ImplPolarity {
+            cache_on_disk_if { key.is_local() }
+        }
 
         query issue33140_self_ty(_: DefId) -> Option<ty::Ty<'tcx>> {}
     }
@@ -398,7 +422,9 @@ rustc_queries! {
         query unsafe_derive_on_repr_packed(_: DefId) -> () {}
 
         /// The signature of functions and closures.
-        query fn_sig(_: DefId) -> ty::PolyFnSig<'tcx> {}
+        query fn_sig(key: DefId) -> ty::PolyFnSig<'tcx> {
+            cache_on_disk_if { key.is_local() }
+        }
     }
 
     Other {
@@ -492,12 +518,16 @@ rustc_queries! {
     BorrowChecking {
         /// Borrow-checks the function body. If this is a closure, returns
         /// additional requirements that the closure's creator must verify.
+        ///
+        /// The result is cached for every local body, since whether it can be
+        /// loaded is decided without the result: a body defining an opaque
+        /// type has to be borrow checked again otherwise when `type_of` needs
+        /// its result. Results are not cached when a driver collects the
+        /// borrow checker facts, which would be skipped for loaded results.
         query mir_borrowck(key: DefId) -> &'tcx mir::BorrowCheckResult<'tcx> {
             desc { |tcx| "borrow-checking `{}`", tcx.def_path_str(key) }
             cache_on_disk_if(tcx, opt_result) {
-                key.is_local()
-                    && (tcx.is_closure(key)
-                        || opt_result.map_or(false, |r| !r.concrete_opaque_types.is_empty()))
+                key.is_local() && !tcx.sess.opts.collect_borrowck_facts
             }
         }
     }
@@ -1077,6 +1107,15 @@ rustc_queries! {
             desc { "collect_and_partition_mono_items" }
         }
         query is_codegened_item(_: DefId) -> bool {}
+        /// Determines which generic parameters of an item are unused by its MIR, so
+        /// that instances differing only in those parameters can share code (see
+        /// `-Z polymorphize`).
+        query unused_generic_params(key: DefId) -> FiniteBitSet<u32> {
+            desc { |tcx|
+                "determining which generic parameters are unused by `{}`",
+                tcx.def_path_str(key)
+            }
+        }
         query codegen_unit(_: Symbol) -> &'tcx CodegenUnit<'tcx> {
             desc { "codegen_unit" }
         }
@@ -917,6 +917,9 @@ pub struct GlobalCtxt<'tcx> {
 
     pub prof: SelfProfilerRef,
 
+    /// Collects the times of items for `-Z self-profile-summary`, if enabled.
+    pub item_profiler: Option<query::ItemProfiler>,
+
     /// Common types, pre-interned for your convenience.
     pub types: CommonTypes<'tcx>,
 
@@ -1146,6 +1149,11 @@ impl<'tcx> TyCtxt<'tcx> {
             interners,
             dep_graph,
             prof: s.prof.clone(),
+            item_profiler: s
+                .opts
+                .debugging_opts
+                .self_profile_summary
+                .map(|_| query::ItemProfiler::default()),
             types: common_types,
             lifetimes: common_lifetimes,
             consts: common_consts,
@@ -1,5 +1,6 @@
 use crate::middle::codegen_fn_attrs::CodegenFnAttrFlags;
 use crate::ty::print::{FmtPrinter, Printer};
+use crate::ty::subst::{GenericArgKind, InternalSubsts};
 use crate::ty::{self, SubstsRef, Ty, TyCtxt, TypeFoldable};
 use rustc_errors::ErrorReported;
 use rustc_hir::def::Namespace;
@@ -79,8 +80,9 @@ impl<'tcx> Instance<'tcx> {
     /// Returns the `Ty` corresponding to this `Instance`,
     /// with generic substitutions applied and lifetimes erased.
     ///
-    /// This method can only be called when the 'substs' for this Instance
-    /// are fully monomorphic (no `ty::Param`'s are present).
+    /// This method should only be called when the 'substs' for this Instance
+    /// are monomorphic, except for parameters that were replaced by
+    /// `Instance::polymorphize` because they are unused.
     /// This is usually the case (e.g. during codegen).
     /// However, during constant evaluation, we may want
     /// to try to resolve a `Instance` using generic parameters
@@ -92,7 +94,7 @@ impl<'tcx> Instance<'tcx> {
         // There shouldn't be any params - if there are, then
         // Instance.ty_env should have been used to provide the proper
         // ParamEnv
-        if self.substs.has_param_types_or_consts() {
+        if self.has_used_param_types_or_consts(tcx) {
             bug!("Instance.ty called for type {:?} with params in substs: {:?}", ty, self.substs);
         }
         tcx.subst_and_normalize_erasing_regions(self.substs, ty::ParamEnv::reveal_all(), &ty)
@@ -420,6 +422,72 @@ impl<'tcx> Instance<'tcx> {
             | InstanceDef::VtableShim(..) => Some(self.substs),
         }
     }
+
+    /// Like `self.substs.has_param_types_or_consts()`, but ignores the unused
+    /// parameters that `Instance::polymorphize` replaced with themselves. This
+    /// is the same unless `-Z polymorphize` is enabled.
+    pub fn has_used_param_types_or_consts(&self, tcx: TyCtxt<'tcx>) -> bool {
+        if !self.substs.has_param_types_or_consts() {
+            return false;
+        }
+
+        let unused = match self.def {
+            InstanceDef::Item(def_id) if tcx.sess.opts.debugging_opts.polymorphize => {
+                tcx.unused_generic_params(def_id)
+            }
+            _ => return true,
+        };
+
+        self.substs.iter().enumerate().any(|(index, arg)| {
+            let index = index as u32;
+            let is_identity = match arg.unpack() {
+                GenericArgKind::Type(ty) => matches!(ty.kind, ty::Param(p) if p.index == index),
+                GenericArgKind::Const(ct) => {
+                    matches!(ct.val, ty::ConstKind::Param(p) if p.index == index)
+                }
+                GenericArgKind::Lifetime(_) => false,
+            };
+            let polymorphized = is_identity && unused.contains(index).unwrap_or(false);
+            !polymorphized && arg.has_param_types_or_consts()
+        })
+    }
+
+    /// Replaces the generic parameters of this instance that are unused by its
+    /// MIR (see the `unused_generic_params` query) with the parameters
+    /// themselves, so that instances which only differ in unused parameters
+    /// are identical and share a single symbol. This is a no-op unless
+    /// `-Z polymorphize` is enabled.
+    pub fn polymorphize(self, tcx: TyCtxt<'tcx>) -> Self {
+        debug!("polymorphize({:?})", self);
+        if !tcx.sess.opts.debugging_opts.polymorphize {
+            return self;
+        }
+
+        if let InstanceDef::Item(def_id) = self.def {
+            let unused = tcx.unused_generic_params(def_id);
+            if unused.is_empty() {
+                return self;
+            }
+
+            debug!("polymorphize: unused={:?}", unused);
+            let polymorphized_substs =
+                InternalSubsts::for_item(tcx, def_id, |param, _| match param.kind {
+                    // If parameter is a const or type parameter..
+                    ty::GenericParamDefKind::Const | ty::GenericParamDefKind::Type { .. } if
+                        // ..and is within range and unused..
+                        unused.contains(param.index).unwrap_or(false) =>
+                            // ..then use the identity for this parameter.
+                            tcx.mk_param_from_def(param),
+                    // Otherwise, use the parameter as before.
+                    _ => self.substs[param.index as usize],
+                });
+
+            debug!("polymorphize: self={:?} polymorphized_substs={:?}", self, polymorphized_substs);
+            Self { def: self.def, substs: polymorphized_substs }
+        } else {
+            self
+        }
+    }
 }
 
 fn needs_fn_once_adapter_shim(
@@ -0,0 +1,329 @@
+//! The per-item timing summary printed by `-Z self-profile-summary`.
+//!
+//! While `-Z self-profile` records raw events for external tools, this keeps a
+//! small in-memory table of how much time was spent on each item in a few
+//! activities that are commonly responsible for slow builds: type checking,
+//! borrow checking, const evaluation, trait selection, MIR optimization,
+//! codegen and LLVM.
+//!
+//! Times are *self* times: the time an activity takes, minus the time of the
+//! activities nested in it. Nested activities are usually queries for other
+//! items, so this keeps e.g. the type checking of a closure from being counted
+//! for its parent function as well. Trait selection is keyed on goals rather
+//! than items, so it is attributed to the item whose activity it is nested in.
+//! Time spent in all other queries counts towards the enclosing activity.
+//!
+//! Codegen time is the time it takes to generate the LLVM IR of a function.
+//! LLVM time is the time LLVM passes spend on the functions generated for an
+//! item, which the codegen backend records by symbol name. Passes that work on
+//! whole modules or on several functions at once are not attributed to any
+//! item. Backends that can't measure this, such as LLVM with the legacy pass
+//! manager, disable it, and the summary leaves out the LLVM column. LLVM runs
+//! after the global context is gone, so the summary is resolved to item paths
+//! beforehand and only printed once codegen has finished.
+
+use crate::ty::TyCtxt;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lock;
+use rustc_hir::def_id::DefId;
+use rustc_serialize::json::as_pretty_json;
+use rustc_session::config::SelfProfileSummary;
+
+use std::cell::RefCell;
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The number of items printed by the text summary.
+const TEXT_SUMMARY_ITEMS: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ItemActivity {
+    TypeChecking,
+    BorrowChecking,
+    ConstEval,
+    TraitSelection,
+    MirOptimization,
+    Codegen,
+    Llvm,
+}
+
+impl ItemActivity {
+    const ALL: [ItemActivity; 7] = [
+        ItemActivity::TypeChecking,
+        ItemActivity::BorrowChecking,
+        ItemActivity::ConstEval,
+        ItemActivity::TraitSelection,
+        ItemActivity::MirOptimization,
+        ItemActivity::Codegen,
+        ItemActivity::Llvm,
+    ];
+
+    /// The activity the provider of the query `name` is counted as, if any.
+    pub fn of_query(name: &str) -> Option<ItemActivity> {
+        Some(match name {
+            "typeck_tables_of"
+            | "check_item_well_formed"
+            | "check_trait_item_well_formed"
+            | "check_impl_item_well_formed" => ItemActivity::TypeChecking,
+            "mir_borrowck" => ItemActivity::BorrowChecking,
+            "const_eval_raw" | "const_eval_validated" => ItemActivity::ConstEval,
+            "evaluate_obligation"
+            | "codegen_fulfill_obligation"
+            | "normalize_projection_ty"
+            | "type_op_prove_predicate" => ItemActivity::TraitSelection,
+            "optimized_mir" | "promoted_mir" => ItemActivity::MirOptimization,
+            _ => return None,
+        })
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            ItemActivity::TypeChecking => "typeck",
+            ItemActivity::BorrowChecking => "borrowck",
+            ItemActivity::ConstEval => "const-eval",
+            ItemActivity::TraitSelection => "traits",
+            ItemActivity::MirOptimization => "mir-opt",
+            ItemActivity::Codegen => "codegen",
+            ItemActivity::Llvm => "llvm",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+struct Frame {
+    activity: ItemActivity,
+    def_id: Option<DefId>,
+    start: Instant,
+    nested: Duration,
+}
+
+thread_local! {
+    /// The activities currently running on this thread, innermost last.
+    static STACK: RefCell<Vec<Frame>> = RefCell::new(Vec::new());
+}
+
+/// The times of the activities of an item, indexed by `ItemActivity::index`.
+type ItemTimes = [Duration; ItemActivity::ALL.len()];
+
+/// The time LLVM passes spent on each function, by symbol name. This is shared
+/// with the threads of the codegen backend.
+pub type LlvmFunctionTimes = Mutex<FxHashMap<String, Duration>>;
+
+/// Collects the self times of the activities of each item.
+pub struct ItemProfiler {
+    times: Lock<FxHashMap<DefId, ItemTimes>>,
+    /// The symbol names of the functions generated for each item.
+    symbols: Lock<FxHashMap<DefId, Vec<String>>>,
+    /// `None` if the codegen backend can't measure LLVM times.
+    llvm_function_times: Lock<Option<Arc<LlvmFunctionTimes>>>,
+    summarized: Lock<bool>,
+}
+
+impl Default for ItemProfiler {
+    fn default() -> Self {
+        ItemProfiler {
+            times: Default::default(),
+            symbols: Default::default(),
+            llvm_function_times: Lock::new(Some(Default::default())),
+            summarized: Default::default(),
+        }
+    }
+}
+
+impl ItemProfiler {
+    /// Starts timing `activity` for `def_id`. Timing continues until the
+    /// returned guard is dropped. Without a `def_id`, the time is attributed
+    /// to the item of the enclosing activity.
+    pub fn start(&self, activity: ItemActivity, def_id: Option<DefId>) -> ItemTimingGuard<'_> {
+        STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let def_id = def_id.or_else(|| stack.iter().rev().find_map(|frame| frame.def_id));
+            stack.push(Frame {
+                activity,
+                def_id,
+                start: Instant::now(),
+                nested: Duration::default(),
+            });
+        });
+        ItemTimingGuard { profiler: self }
+    }
+
+    fn finish(&self) {
+        let (frame, self_time) = STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let frame = stack.pop().expect("no item activity to finish");
+            let elapsed = frame.start.elapsed();
+            if let Some(parent) = stack.last_mut() {
+                parent.nested += elapsed;
+            }
+            let self_time = elapsed.checked_sub(frame.nested).unwrap_or_default();
+            (frame, self_time)
+        });
+        if let Some(def_id) = frame.def_id {
+            let mut times = self.times.lock();
+            times.entry(def_id).or_default()[frame.activity.index()] += self_time;
+        }
+    }
+
+    /// Records that the function `symbol` was generated for `def_id`, so
+    /// that the LLVM time of the function is attributed to it.
+    pub fn record_symbol(&self, def_id: DefId, symbol: &str) {
+        self.symbols.lock().entry(def_id).or_default().push(symbol.to_owned());
+    }
+
+    /// The table the codegen backend records LLVM times in, unless it can't
+    /// measure them.
+    pub fn llvm_function_times(&self) -> Option<Arc<LlvmFunctionTimes>> {
+        self.llvm_function_times.lock().clone()
+    }
+
+    /// Called by codegen backends that can't measure LLVM times, before codegen
+    /// starts.
+    pub fn disable_llvm_times(&self) {
+        *self.llvm_function_times.lock() = None;
+    }
+
+    /// Resolves the collected times to item paths, so that they can be printed
+    /// after the global context is gone. Returns `None` if the summary was
+    /// already taken.
+    pub fn summary(&self, tcx: TyCtxt<'_>) -> Option<ItemProfileSummary> {
+        if mem::replace(&mut *self.summarized.lock(), true) {
+            return None;
+        }
+
+        let mut symbols = mem::take(&mut *self.symbols.lock());
+        let items = mem::take(&mut *self.times.lock())
+            .into_iter()
+            .map(|(def_id, times)| ItemRow {
+                def_id,
+                path: tcx.def_path_str(def_id),
+                location: tcx.sess.source_map().span_to_string(tcx.def_span(def_id)),
+                symbols: symbols.remove(&def_id).unwrap_or_default(),
+                times,
+            })
+            .collect();
+        Some(ItemProfileSummary { items, llvm_function_times: self.llvm_function_times() })
+    }
+}
+
+struct ItemRow {
+    def_id: DefId,
+    path: String,
+    location: String,
+    symbols: Vec<String>,
+    times: ItemTimes,
+}
+
+/// The times of all items, resolved to their paths.
+pub struct ItemProfileSummary {
+    items: Vec<ItemRow>,
+    llvm_function_times: Option<Arc<LlvmFunctionTimes>>,
+}
+
+impl ItemProfileSummary {
+    /// Prints the summary in the given format to stdout. This should only be
+    /// called once the codegen backend has finished, so that the LLVM times are
+    /// complete.
+    pub fn print(mut self, format: SelfProfileSummary) {
+        if let Some(llvm_function_times) = &self.llvm_function_times {
+            let llvm_function_times = llvm_function_times.lock().unwrap();
+            for item in &mut self.items {
+                item.times[ItemActivity::Llvm.index()] =
+                    item.symbols.iter().filter_map(|symbol| llvm_function_times.get(symbol)).sum();
+            }
+        }
+        let measure_llvm = self.llvm_function_times.is_some();
+        let activities: Vec<_> = ItemActivity::ALL
+            .iter()
+            .copied()
+            .filter(|&activity| measure_llvm || activity != ItemActivity::Llvm)
+            .collect();
+
+        let mut items: Vec<_> =
+            self.items.iter().map(|item| (item, item.times.iter().sum::<Duration>())).collect();
+        items.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.def_id.cmp(&b.0.def_id)));
+
+        match format {
+            SelfProfileSummary::Text => {
+                println!(
+                    "Self-profile summary: {} of {} items, times in milliseconds",
+                    items.len().min(TEXT_SUMMARY_ITEMS),
+                    items.len()
+                );
+                print!("{:>10}", "total");
+                for activity in &activities {
+                    print!(" {:>10}", activity.as_str());
+                }
+                println!("  item");
+                for &(item, total) in items.iter().take(TEXT_SUMMARY_ITEMS) {
+                    print!("{:>10.3}", millis(total));
+                    for activity in &activities {
+                        print!(" {:>10.3}", millis(item.times[activity.index()]));
+                    }
+                    println!("  {} ({})", item.path, item.location);
+                }
+            }
+            SelfProfileSummary::Json => {
+                let items = items
+                    .iter()
+                    .map(|&(item, total)| {
+                        let time = |activity: ItemActivity| millis(item.times[activity.index()]);
+                        ItemSummary {
+                            item: item.path.clone(),
+                            location: item.location.clone(),
+                            total_ms: millis(total),
+                            typeck_ms: time(ItemActivity::TypeChecking),
+                            borrowck_ms: time(ItemActivity::BorrowChecking),
+                            const_eval_ms: time(ItemActivity::ConstEval),
+                            traits_ms: time(ItemActivity::TraitSelection),
+                            mir_opt_ms: time(ItemActivity::MirOptimization),
+                            codegen_ms: time(ItemActivity::Codegen),
+                            llvm_ms: Some(time(ItemActivity::Llvm)).filter(|_| measure_llvm),
+                        }
+                    })
+                    .collect();
+                println!("{}", as_pretty_json(&Summary { items }));
+            }
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[must_use]
+pub struct ItemTimingGuard<'a> {
+    profiler: &'a ItemProfiler,
+}
+
+impl Drop for ItemTimingGuard<'_> {
+    fn drop(&mut self) {
+        self.profiler.finish();
+    }
+}
+
+#[derive(RustcEncodable)]
+struct Summary {
+    /// All items with recorded times, the slowest first.
+    items: Vec<ItemSummary>,
+}
+
+#[derive(RustcEncodable)]
+struct ItemSummary {
+    item: String,
+    location: String,
+    total_ms: f64,
+    typeck_ms: f64,
+    borrowck_ms: f64,
+    const_eval_ms: f64,
+    traits_ms: f64,
+    mir_opt_ms: f64,
+    codegen_ms: f64,
+    /// `null` if LLVM times couldn't be measured.
+    llvm_ms: Option<f64>,
+}
@@ -23,6 +23,12 @@ pub trait Key {
     /// In the event that a cycle occurs, if no explicit span has been
     /// given for a query with key `self`, what span should we use?
     fn default_span(&self, tcx: TyCtxt<'_>) -> Span;
+
+    /// The item this key refers to, if any. This is used to attribute the
+    /// time spent in a query to an item for `-Z self-profile-summary`.
+    fn key_as_def_id(&self) -> Option<DefId> {
+        None
+    }
 }
 
 impl<'tcx> Key for ty::InstanceDef<'tcx> {
@@ -35,6 +41,10 @@ impl<'tcx> Key for ty::InstanceDef<'tcx> {
     fn default_span(&self, tcx: TyCtxt<'_>) -> Span {
         tcx.def_span(self.def_id())
     }
+
+    fn key_as_def_id(&self) -> Option<DefId> {
+        Some(self.def_id())
+    }
 }
 
 impl<'tcx> Key for ty::Instance<'tcx> {
@@ -47,6 +57,10 @@ impl<'tcx> Key for ty::Instance<'tcx> {
     fn default_span(&self, tcx: TyCtxt<'_>) -> Span {
         tcx.def_span(self.def_id())
     }
+
+    fn key_as_def_id(&self) -> Option<DefId> {
+        Some(self.def_id())
+    }
 }
 
 impl<'tcx> Key for mir::interpret::GlobalId<'tcx> {
@@ -59,6 +73,10 @@ impl<'tcx> Key for mir::interpret::GlobalId<'tcx> {
     fn default_span(&self, tcx: TyCtxt<'_>) -> Span {
         self.instance.default_span(tcx)
     }
+
+    fn key_as_def_id(&self) -> Option<DefId> {
+        self.instance.key_as_def_id()
+    }
 }
 
 impl<'tcx> Key for mir::interpret::LitToConstInput<'tcx> {
@@ -93,6 +111,9 @@ impl Key for LocalDefId {
     fn default_span(&self, tcx: TyCtxt<'_>) -> Span {
         self.to_def_id().default_span(tcx)
     }
+    fn key_as_def_id(&self) -> Option<DefId> {
+        Some(self.to_def_id())
+    }
 }
 
 impl Key for DefId {
@@ -104,6 +125,9 @@ impl Key for DefId {
     fn default_span(&self, tcx: TyCtxt<'_>) -> Span {
         tcx.def_span(*self)
     }
+    fn key_as_def_id(&self) -> Option<DefId> {
+        Some(*self)
+    }
 }
 
 impl Key for (DefId, DefId) {
@@ -115,6 +139,9 @@ impl Key for (DefId, DefId) {
     fn default_span(&self, tcx: TyCtxt<'_>) -> Span {
         self.1.default_span(tcx)
     }
+    fn key_as_def_id(&self) -> Option<DefId> {
+        Some(self.0)
+    }
 }
 
 impl Key for (CrateNum, DefId) {
@@ -126,6 +153,9 @@ impl Key for (CrateNum, DefId) {
     fn default_span(&self, tcx: TyCtxt<'_>) -> Span {
         self.1.default_span(tcx)
     }
+    fn key_as_def_id(&self) -> Option<DefId> {
+        Some(self.1)
+    }
 }
 
 impl Key for (DefId, SimplifiedType) {
@@ -137,6 +167,9 @@ impl Key for (DefId, SimplifiedType) {
     fn default_span(&self, tcx: TyCtxt<'_>) -> Span {
         self.0.default_span(tcx)
     }
+    fn key_as_def_id(&self) -> Option<DefId> {
+        Some(self.0)
+    }
 }
 
 impl<'tcx> Key for SubstsRef<'tcx> {
@@ -159,6 +192,9 @@ impl<'tcx> Key for (DefId, SubstsRef<'tcx>) {
     fn default_span(&self, tcx: TyCtxt<'_>) -> Span {
         self.0.default_span(tcx)
     }
+    fn key_as_def_id(&self) -> Option<DefId> {
+        Some(self.0)
+    }
 }
 
 impl<'tcx> Key for (ty::ParamEnv<'tcx>, ty::PolyTraitRef<'tcx>) {
@@ -247,6 +283,9 @@ impl<'tcx, T: Key> Key for ty::ParamEnvAnd<'tcx, T> {
     fn default_span(&self, tcx: TyCtxt<'_>) -> Span {
         self.value.default_span(tcx)
     }
+    fn key_as_def_id(&self) -> Option<DefId> {
+        self.value.key_as_def_id()
+    }
 }
 
 impl<'tcx> Key for traits::Environment<'tcx> {
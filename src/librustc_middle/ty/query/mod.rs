@@ -45,6 +45,7 @@ use rustc_hir::def::DefKind;
 use rustc_hir::def_id::{CrateNum, DefId, DefIdMap, DefIdSet, LocalDefId};
 use rustc_hir::lang_items::{LangItem, LanguageItems};
 use rustc_hir::{Crate, HirIdSet, ItemLocalId, TraitCandidate};
+use rustc_index::bit_set::FiniteBitSet;
 use rustc_index::vec::IndexVec;
 use rustc_session::config::{EntryFnType, OptLevel, OutputFilenames, SymbolManglingVersion};
 use rustc_session::CrateDisambiguator;
@@ -67,6 +68,11 @@ use rustc_query_system::query::*;
 mod stats;
 pub use self::stats::print_stats;
 
+mod item_profiling;
+pub use self::item_profiling::{
+    ItemActivity, ItemProfileSummary, ItemProfiler, ItemTimingGuard, LlvmFunctionTimes,
+};
+
 #[cfg(parallel_compiler)]
 mod job;
 #[cfg(parallel_compiler)]
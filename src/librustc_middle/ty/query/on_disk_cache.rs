@@ -1007,9 +1007,11 @@ where
     let state = Q::query_state(tcx);
     assert!(state.all_inactive());
 
+    let mut cached_results = 0;
     state.iter_results(|results| {
         for (key, value, dep_node) in results {
             if Q::cache_on_disk(tcx, key.clone(), Some(&value)) {
+                cached_results += 1;
                 let dep_node = SerializedDepNodeIndex::new(dep_node.index());
 
                 // Record position of the cache entry.
@@ -1021,5 +1023,11 @@ where
             }
         }
         Ok(())
-    })
+    })?;
+
+    if tcx.sess.opts.debugging_opts.incremental_info && cached_results > 0 {
+        println!("[incremental] cached {} results of `{}`", cached_results, Q::NAME);
+    }
+
+    Ok(())
 }
@@ -7,14 +7,16 @@ use crate::ty::query::Query;
 use crate::ty::tls::{self, ImplicitCtxt};
 use crate::ty::{self, TyCtxt};
 use rustc_query_system::query::QueryContext;
-use rustc_query_system::query::{CycleError, QueryJobId, QueryJobInfo};
+use rustc_query_system::query::{CycleError, QueryInfo, QueryJobId, QueryJobInfo};
 
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::Lock;
 use rustc_data_structures::thin_vec::ThinVec;
 use rustc_errors::{struct_span_err, Diagnostic, DiagnosticBuilder, Handler, Level};
+use rustc_hir as hir;
+use rustc_hir::Node;
 use rustc_span::def_id::DefId;
-use rustc_span::Span;
+use rustc_span::{Span, DUMMY_SP};
 
 impl QueryContext for TyCtxt<'tcx> {
     type Query = Query<'tcx>;
@@ -83,7 +85,20 @@ impl<'tcx> TyCtxt<'tcx> {
         assert!(!stack.is_empty());
 
         let fix_span = |span: Span, query: &Query<'tcx>| {
-            self.sess.source_map().guess_head_span(query.default_span(self, span))
+            // Point at the code the user wrote rather than into a macro expansion.
+            let span = query.default_span(self, span).source_callsite();
+            self.sess.source_map().guess_head_span(span)
+        };
+
+        // Edges of the cycle which are in other crates can't be changed by the
+        // user, so they are only described.
+        let edge_span = |span: Span, query: &Query<'tcx>| {
+            let span = fix_span(span, query);
+            if self.sess.source_map().is_imported(span) {
+                DUMMY_SP
+            } else {
+                span
+            }
         };
 
         // Disable naming impls with types in this path, since that
@@ -100,10 +115,16 @@ impl<'tcx> TyCtxt<'tcx> {
                 stack[0].query.describe(self)
             );
 
+            // Different queries can have the same description, e.g. the
+            // steps of building MIR. Only mention each of them once per span.
+            let mut notes = FxHashSet::default();
             for i in 1..stack.len() {
                 let query = &stack[i].query;
-                let span = fix_span(stack[(i + 1) % stack.len()].span, query);
-                err.span_note(span, &format!("...which requires {}...", query.describe(self)));
+                let span = edge_span(stack[(i + 1) % stack.len()].span, query);
+                let description = query.describe(self);
+                if notes.insert((span, description.clone())) {
+                    err.span_note(span, &format!("...which requires {}...", description));
+                }
             }
 
             err.note(&format!(
@@ -118,10 +139,75 @@ impl<'tcx> TyCtxt<'tcx> {
                 );
             }
 
+            self.explain_auto_trait_leakage_cycle(&mut err, &stack);
+
             err
         })
     }
 
+    /// Explains cycles that go through checking whether an opaque type
+    /// implements an auto trait, which requires type-checking the function
+    /// defining the opaque type to find its hidden type.
+    fn explain_auto_trait_leakage_cycle(
+        self,
+        err: &mut DiagnosticBuilder<'_>,
+        stack: &[QueryInfo<Query<'tcx>>],
+    ) {
+        let leak = stack.iter().find_map(|frame| match frame.query {
+            Query::evaluate_obligation(goal) => match goal.value.value {
+                ty::Predicate::Trait(predicate, _) => {
+                    let predicate = predicate.skip_binder();
+                    match predicate.self_ty().kind {
+                        ty::Opaque(opaque_def_id, _) if self.trait_is_auto(predicate.def_id()) => {
+                            Some((predicate.self_ty(), opaque_def_id, predicate.def_id()))
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+            _ => None,
+        });
+
+        if let Some((opaque_ty, opaque_def_id, trait_def_id)) = leak {
+            let trait_path = self.def_path_str(trait_def_id);
+            // The hidden type of `-> impl Trait` is defined by the function
+            // returning it, while the one of `type Alias = impl Trait;` is
+            // defined by the uses of the alias in its scope.
+            let origin = match self.hir().get_if_local(opaque_def_id) {
+                Some(Node::Item(hir::Item {
+                    kind: hir::ItemKind::OpaqueTy(hir::OpaqueTy { origin, .. }),
+                    ..
+                })) => Some(*origin),
+                _ => None,
+            };
+            let defined_by = match origin {
+                Some(hir::OpaqueTyOrigin::FnReturn) | Some(hir::OpaqueTyOrigin::AsyncFn) => {
+                    format!("`{}`", self.def_path_str(self.parent(opaque_def_id).unwrap()))
+                }
+                _ => "the items that define it".to_string(),
+            };
+            err.note(&format!(
+                "whether the opaque type `{}` implements the auto trait `{}` depends on its \
+                 hidden type, which is only known after type-checking {}",
+                opaque_ty, trait_path, defined_by,
+            ));
+            err.span_help(
+                self.def_span(opaque_def_id),
+                &format!(
+                    "consider adding `+ {}` to the bounds of the opaque type, so that it is \
+                     known without looking at the hidden type",
+                    trait_path
+                ),
+            );
+            err.help(&format!(
+                "alternatively, do not require `{}` for the opaque type in the function that \
+                 defines it, e.g. by moving that code into a separate function",
+                trait_path
+            ));
+        }
+    }
+
     pub fn try_print_query_stack(handler: &Handler) {
         eprintln!("query stack during panic:");
 
@@ -359,6 +445,10 @@ macro_rules! define_queries_inner {
                     // would be missing appropriate entries in `providers`.
                     .unwrap_or(&tcx.queries.fallback_extern_providers)
                     .$name;
+                let _timer = tcx.item_profiler.as_ref().and_then(|profiler| {
+                    ItemActivity::of_query(stringify!($name))
+                        .map(|activity| profiler.start(activity, key.key_as_def_id()))
+                });
                 provider(tcx, key)
             }
 
@@ -0,0 +1,121 @@
+//! This file provides API for compiler consumers that want to inspect the
+//! results of borrow checking, such as static analyzers built on
+//! `rustc_driver`.
+
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_index::vec::IndexVec;
+use rustc_infer::infer::TyCtxtInferExt;
+use rustc_middle::mir::{Body, BorrowCheckResult, Promoted};
+use rustc_middle::ty::query::Providers;
+use rustc_middle::ty::TyCtxt;
+
+use std::cell::RefCell;
+use std::mem;
+
+pub use super::facts::{AllFacts as PoloniusInput, RustcFacts};
+pub use super::location::{LocationIndex, LocationTable, RichLocation};
+pub use crate::dataflow::move_paths::MovePathIndex;
+pub use crate::dataflow::BorrowIndex;
+
+/// A MIR body together with the facts computed for it by the borrow checker.
+pub struct BodyWithBorrowckFacts<'tcx> {
+    /// The body that was borrow checked. Its regions have been replaced with
+    /// the region variables the facts refer to.
+    pub body: Body<'tcx>,
+    /// The promoted constants of `body`, with their regions replaced as well.
+    pub promoted: IndexVec<Promoted, Body<'tcx>>,
+    /// The facts that the borrow checker computed, in the form Polonius takes
+    /// as input. These are the facts `-Z nll-facts` writes to disk.
+    pub input_facts: PoloniusInput,
+    /// Maps the points used in `input_facts` to locations in `body`.
+    pub location_table: LocationTable,
+}
+
+thread_local! {
+    /// The bodies collected by `mir_borrowck_collecting_facts`. Their lifetime
+    /// is erased, see `take_bodies_with_facts`.
+    static COLLECTED_BODIES: RefCell<Vec<(LocalDefId, BodyWithBorrowckFacts<'static>)>> =
+        RefCell::new(Vec::new());
+}
+
+/// Overrides the `mir_borrowck` query so that the body and facts of every body
+/// of the local crate are collected, to be taken by `take_bodies_with_facts`.
+/// This is done when `Options::collect_borrowck_facts` is set, and needs the
+/// queries to run on a single thread, the one calling `take_bodies_with_facts`.
+pub fn provide_collecting_facts(providers: &mut Providers<'_>) {
+    COLLECTED_BODIES.with(|bodies| bodies.borrow_mut().clear());
+    providers.mir_borrowck = mir_borrowck_collecting_facts;
+}
+
+fn mir_borrowck_collecting_facts<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+) -> &'tcx BorrowCheckResult<'tcx> {
+    mir_borrowck_with_facts(tcx, def_id, |body_with_facts| {
+        // SAFETY: the body only outlives `tcx` in `COLLECTED_BODIES`, where
+        // nothing looks at it until `take_bodies_with_facts` hands it out
+        // again with the lifetime of the `tcx` that borrow checked it.
+        let body_with_facts = unsafe {
+            mem::transmute::<BodyWithBorrowckFacts<'tcx>, BodyWithBorrowckFacts<'static>>(
+                body_with_facts,
+            )
+        };
+        COLLECTED_BODIES
+            .with(|bodies| bodies.borrow_mut().push((def_id.expect_local(), body_with_facts)));
+    })
+}
+
+/// Returns the bodies of the local crate along with their borrow checker facts,
+/// when `Options::collect_borrowck_facts` is set. Bodies that have not been
+/// borrow checked yet are borrow checked first, so that the result covers every
+/// body even if `borrowck_analysis` was marked green in the incremental cache.
+pub fn take_bodies_with_facts<'tcx>(
+    tcx: TyCtxt<'tcx>,
+) -> Vec<(LocalDefId, BodyWithBorrowckFacts<'tcx>)> {
+    assert!(tcx.sess.opts.collect_borrowck_facts);
+
+    // `borrowck_analysis` only ensures that each body is borrow checked, which
+    // doesn't run the provider for bodies that are green. Their results are
+    // not cached on disk in this mode, so requesting them runs the provider.
+    for def_id in tcx.body_owners() {
+        tcx.mir_borrowck(def_id.to_def_id());
+    }
+
+    let bodies = COLLECTED_BODIES.with(|bodies| mem::take(&mut *bodies.borrow_mut()));
+    // SAFETY: `provide_collecting_facts` cleared `COLLECTED_BODIES` when the
+    // providers of `tcx` were set up, so all of these bodies were collected
+    // by borrow checking with `tcx`.
+    unsafe {
+        mem::transmute::<
+            Vec<(LocalDefId, BodyWithBorrowckFacts<'static>)>,
+            Vec<(LocalDefId, BodyWithBorrowckFacts<'tcx>)>,
+        >(bodies)
+    }
+}
+
+/// Borrow checks the body of `def_id` like the `mir_borrowck` query does, and
+/// additionally hands the body along with the facts computed for it to
+/// `consumer`.
+///
+/// This is the implementation of the `mir_borrowck` query that
+/// `provide_collecting_facts` installs, so that each body is still borrow
+/// checked only once, and before `optimized_mir` steals its MIR. The facts are
+/// passed to `consumer` instead of being returned because the query result is
+/// cached.
+pub fn mir_borrowck_with_facts<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    consumer: impl FnOnce(BodyWithBorrowckFacts<'tcx>),
+) -> &'tcx BorrowCheckResult<'tcx> {
+    let (input_body, promoted) = tcx.mir_validated(def_id);
+    debug!("run query mir_borrowck with facts: {}", tcx.def_path_str(def_id));
+
+    let (result, body_with_facts) = tcx.infer_ctxt().enter(|infcx| {
+        let input_body: &Body<'_> = &input_body.borrow();
+        let promoted: &IndexVec<_, _> = &promoted.borrow();
+        super::do_mir_borrowck(&infcx, input_body, promoted, def_id.expect_local(), true)
+    });
+    consumer(*body_with_facts.unwrap());
+
+    tcx.arena.alloc(result)
+}
@@ -41,6 +41,7 @@ use crate::dataflow::{Analysis, BorrowckFlowState as Flows, BorrowckResults};
 use crate::dataflow::{MaybeInitializedPlaces, MaybeUninitializedPlaces};
 use crate::transform::MirSource;
 
+use self::consumers::BodyWithBorrowckFacts;
 use self::diagnostics::{AccessKind, RegionName};
 use self::location::LocationTable;
 use self::prefixes::PrefixSet;
@@ -51,6 +52,7 @@ use self::path_utils::*;
 mod borrow_set;
 mod constraint_generation;
 mod constraints;
+pub mod consumers;
 mod diagnostics;
 mod facts;
 mod invalidation;
@@ -99,7 +101,7 @@ fn mir_borrowck(tcx: TyCtxt<'_>, def_id: DefId) -> &BorrowCheckResult<'_> {
     let opt_closure_req = tcx.infer_ctxt().enter(|infcx| {
         let input_body: &Body<'_> = &input_body.borrow();
         let promoted: &IndexVec<_, _> = &promoted.borrow();
-        do_mir_borrowck(&infcx, input_body, promoted, def_id.expect_local())
+        do_mir_borrowck(&infcx, input_body, promoted, def_id.expect_local(), false).0
     });
     debug!("mir_borrowck done");
 
@@ -111,7 +113,8 @@ fn do_mir_borrowck<'a, 'tcx>(
     input_body: &Body<'tcx>,
     input_promoted: &IndexVec<Promoted, Body<'tcx>>,
     def_id: LocalDefId,
-) -> BorrowCheckResult<'tcx> {
+    return_body_with_facts: bool,
+) -> (BorrowCheckResult<'tcx>, Option<Box<BodyWithBorrowckFacts<'tcx>>>) {
     debug!("do_mir_borrowck(def_id = {:?})", def_id);
 
     let tcx = infcx.tcx;
@@ -171,13 +174,19 @@ fn do_mir_borrowck<'a, 'tcx>(
     // requires first making our own copy of the MIR. This copy will
     // be modified (in place) to contain non-lexical lifetimes. It
     // will have a lifetime tied to the inference context.
-    let mut body = input_body.clone();
+    let mut body_owned = input_body.clone();
     let mut promoted = input_promoted.clone();
-    let free_regions =
-        nll::replace_regions_in_mir(infcx, def_id.to_def_id(), param_env, &mut body, &mut promoted);
-    let body = &body; // no further changes
+    let free_regions = nll::replace_regions_in_mir(
+        infcx,
+        def_id.to_def_id(),
+        param_env,
+        &mut body_owned,
+        &mut promoted,
+    );
+    let body = &body_owned; // no further changes
 
-    let location_table = &LocationTable::new(&body);
+    let location_table_owned = LocationTable::new(body);
+    let location_table = &location_table_owned;
 
     let mut errors_buffer = Vec::new();
     let (move_data, move_errors): (MoveData<'tcx>, Option<Vec<(Place<'tcx>, MoveError<'tcx>)>>) =
@@ -201,6 +210,7 @@ fn do_mir_borrowck<'a, 'tcx>(
     let nll::NllOutput {
         regioncx,
         opaque_type_values,
+        polonius_input,
         polonius_output,
         opt_closure_req,
         nll_errors,
@@ -215,6 +225,7 @@ fn do_mir_borrowck<'a, 'tcx>(
         &mut flow_inits,
         &mdpe.move_data,
         &borrow_set,
+        return_body_with_facts,
     );
 
     // Dump MIR results into a file, if that is enabled. This let us
@@ -417,7 +428,18 @@ fn do_mir_borrowck<'a, 'tcx>(
 
     debug!("do_mir_borrowck: result = {:#?}", result);
 
-    result
+    let body_with_facts = if return_body_with_facts {
+        Some(Box::new(BodyWithBorrowckFacts {
+            body: body_owned,
+            promoted,
+            input_facts: polonius_input.expect("polonius facts were not gathered"),
+            location_table: location_table_owned,
+        }))
+    } else {
+        None
+    };
+
+    (result, body_with_facts)
 }
 
 crate struct MirBorrowckCtxt<'cx, 'tcx> {
@@ -8,7 +8,8 @@ pub use self::framework::{
     Engine, GenKill, GenKillAnalysis, Results, ResultsCursor, ResultsRefCursor, ResultsVisitor,
 };
 pub use self::impls::{
-    borrows::Borrows, DefinitelyInitializedPlaces, EverInitializedPlaces, MaybeBorrowedLocals,
+    borrows::{BorrowIndex, Borrows},
+    DefinitelyInitializedPlaces, EverInitializedPlaces, MaybeBorrowedLocals,
     MaybeInitializedPlaces, MaybeMutBorrowedLocals, MaybeRequiresStorage, MaybeStorageLive,
     MaybeUninitializedPlaces,
 };
@@ -33,7 +33,7 @@ extern crate log;
 #[macro_use]
 extern crate rustc_middle;
 
-mod borrow_check;
+pub mod borrow_check;
 pub mod const_eval;
 pub mod dataflow;
 pub mod interpret;
@@ -50,6 +50,7 @@ pub fn provide(providers: &mut Providers<'_>) {
     shim::provide(providers);
     transform::provide(providers);
     monomorphize::partitioning::provide(providers);
+    monomorphize::polymorphize::provide(providers);
     providers.const_eval_validated = const_eval::const_eval_validated_provider;
     providers.const_eval_raw = const_eval::const_eval_raw_provider;
     providers.const_caller_location = const_eval::const_caller_location;
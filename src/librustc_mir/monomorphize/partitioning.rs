@@ -91,11 +91,33 @@
 //! Note though that as a side-effect of creating a codegen units per
 //! source-level module, functions from the same module will be available for
 //! inlining, even when they are not marked `#[inline]`.
+//!
+//! Stable Partitioning
+//! -------------------
+//! Merging the smallest codegen units until the requested number is reached
+//! (see `merge_codegen_units`) means that growing or shrinking a single module
+//! can change which modules end up sharing a codegen unit, invalidating the
+//! cached object files of codegen units that didn't otherwise change. With
+//! `-Z cgu-partitioning-strategy=stable-hash`, items are instead distributed
+//! over a fixed number of codegen units by a stable hash: non-generic items by
+//! the `DefPathHash` of their home module, so a module's items still stay
+//! together, and generic instantiations by the `DefPathHash` of their item
+//! path. The codegen unit an item is placed in then only depends on where it
+//! is defined, so edits elsewhere in the crate never move it.
+//!
+//! As with per-module partitioning, incremental builds keep generic
+//! instantiations in "volatile" codegen units of their own, which are numbered
+//! separately from the ones for non-generic items. Adding a new instantiation
+//! therefore never invalidates a codegen unit containing only stable items.
+//! Codegen units aren't merged in that case, so up to twice the requested
+//! number of codegen units can be produced.
 
 use std::cmp;
 use std::collections::hash_map::Entry;
+use std::hash::Hasher;
 
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync;
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::{CrateNum, DefId, DefIdSet, CRATE_DEF_INDEX, LOCAL_CRATE};
@@ -106,6 +128,7 @@ use rustc_middle::mir::mono::{InstantiationMode, MonoItem};
 use rustc_middle::ty::print::characteristic_def_id_of_type;
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, DefIdTree, InstanceDef, TyCtxt};
+use rustc_session::config::CguPartitioningStrategy;
 use rustc_span::symbol::{Symbol, SymbolStr};
 
 use crate::monomorphize::collector::InliningMap;
@@ -132,15 +155,20 @@ where
     // functions and statics defined in the local crate.
     let mut initial_partitioning = {
         let _prof_timer = tcx.prof.generic_activity("cgu_partitioning_place_roots");
-        place_root_mono_items(tcx, mono_items)
+        place_root_mono_items(tcx, mono_items, max_cgu_count)
     };
 
     initial_partitioning.codegen_units.iter_mut().for_each(|cgu| cgu.estimate_size(tcx));
 
     debug_dump(tcx, "INITIAL PARTITIONING:", initial_partitioning.codegen_units.iter());
 
-    // Merge until we have at most `max_cgu_count` codegen units.
-    {
+    // Merge until we have at most `max_cgu_count` codegen units. Merging
+    // would put volatile and stable items of the `stable-hash` strategy back
+    // into the same codegen unit, so it is skipped in incremental builds.
+    let keep_volatile_cgus = tcx.sess.opts.incremental.is_some()
+        && tcx.sess.opts.debugging_opts.cgu_partitioning_strategy
+            == CguPartitioningStrategy::StableHash;
+    if !keep_volatile_cgus {
         let _prof_timer = tcx.prof.generic_activity("cgu_partitioning_merge_cgus");
         merge_codegen_units(tcx, &mut initial_partitioning, max_cgu_count);
         debug_dump(tcx, "POST MERGING:", initial_partitioning.codegen_units.iter());
@@ -199,7 +227,11 @@ struct PostInliningPartitioning<'tcx> {
     internalization_candidates: FxHashSet<MonoItem<'tcx>>,
 }
 
-fn place_root_mono_items<'tcx, I>(tcx: TyCtxt<'tcx>, mono_items: I) -> PreInliningPartitioning<'tcx>
+fn place_root_mono_items<'tcx, I>(
+    tcx: TyCtxt<'tcx>,
+    mono_items: I,
+    max_cgu_count: usize,
+) -> PreInliningPartitioning<'tcx>
 where
     I: Iterator<Item = MonoItem<'tcx>>,
 {
@@ -216,6 +248,8 @@ where
 
     let cgu_name_builder = &mut CodegenUnitNameBuilder::new(tcx);
     let cgu_name_cache = &mut FxHashMap::default();
+    let stable_cgu_name_cache = &mut FxHashMap::default();
+    let strategy = tcx.sess.opts.debugging_opts.cgu_partitioning_strategy;
 
     for mono_item in mono_items {
         match mono_item.instantiation_mode(tcx) {
@@ -226,15 +260,25 @@ where
         let characteristic_def_id = characteristic_def_id_of_mono_item(tcx, mono_item);
         let is_volatile = is_incremental_build && mono_item.is_generic_fn();
 
-        let codegen_unit_name = match characteristic_def_id {
-            Some(def_id) => compute_codegen_unit_name(
+        let codegen_unit_name = match (characteristic_def_id, strategy) {
+            (Some(def_id), CguPartitioningStrategy::Default) => compute_codegen_unit_name(
                 tcx,
                 cgu_name_builder,
                 def_id,
                 is_volatile,
                 cgu_name_cache,
             ),
-            None => fallback_cgu_name(cgu_name_builder),
+            (Some(def_id), CguPartitioningStrategy::StableHash) => {
+                compute_stable_codegen_unit_name(
+                    tcx,
+                    cgu_name_builder,
+                    def_id,
+                    is_volatile,
+                    max_cgu_count,
+                    stable_cgu_name_cache,
+                )
+            }
+            (None, _) => fallback_cgu_name(cgu_name_builder),
         };
 
         let codegen_unit = codegen_units
@@ -768,7 +812,49 @@ fn compute_codegen_unit_name(
     volatile: bool,
     cache: &mut CguNameCache,
 ) -> Symbol {
-    // Find the innermost module that is not nested within a function.
+    let cgu_def_id = home_module(tcx, def_id);
+
+    *cache.entry((cgu_def_id, volatile)).or_insert_with(|| {
+        let def_path = tcx.def_path(cgu_def_id);
+
+        let components = def_path.data.iter().map(|part| part.data.as_symbol());
+
+        let volatile_suffix = volatile.then_some("volatile");
+
+        name_builder.build_cgu_name(def_path.krate, components, volatile_suffix)
+    })
+}
+
+/// Computes the name of the codegen unit `def_id` is placed in by the
+/// `stable-hash` partitioning strategy: one of `cgu_count` numbered codegen
+/// units, chosen by the stable hash of the home module of `def_id` or, for
+/// `volatile` (generic) items, of `def_id` itself. Volatile items get their
+/// own set of codegen units, so that they never share one with stable items.
+fn compute_stable_codegen_unit_name(
+    tcx: TyCtxt<'_>,
+    name_builder: &mut CodegenUnitNameBuilder<'_>,
+    def_id: DefId,
+    volatile: bool,
+    cgu_count: usize,
+    cache: &mut FxHashMap<(u64, bool), Symbol>,
+) -> Symbol {
+    let hash_def_id = if volatile { def_id } else { home_module(tcx, def_id) };
+
+    let mut hasher = StableHasher::new();
+    hasher.write_u64(tcx.def_path_hash(hash_def_id).0.to_smaller_hash());
+    hasher.write_u8(volatile as u8);
+    let hash: u64 = hasher.finish();
+
+    let index = hash % cgu_count as u64;
+    *cache.entry((index, volatile)).or_insert_with(|| {
+        let suffix = if volatile { format!("{}-volatile", index) } else { index.to_string() };
+        name_builder.build_cgu_name(LOCAL_CRATE, &["stable"], Some(suffix))
+    })
+}
+
+/// Finds the innermost module containing `def_id` that is not nested within a
+/// function.
+fn home_module(tcx: TyCtxt<'_>, def_id: DefId) -> DefId {
     let mut current_def_id = def_id;
     let mut cgu_def_id = None;
     // Walk backwards from the item we want to find the module for.
@@ -793,17 +879,7 @@ fn compute_codegen_unit_name(
         current_def_id = tcx.parent(current_def_id).unwrap();
     }
 
-    let cgu_def_id = cgu_def_id.unwrap();
-
-    *cache.entry((cgu_def_id, volatile)).or_insert_with(|| {
-        let def_path = tcx.def_path(cgu_def_id);
-
-        let components = def_path.data.iter().map(|part| part.data.as_symbol());
-
-        let volatile_suffix = volatile.then_some("volatile");
-
-        name_builder.build_cgu_name(def_path.krate, components, volatile_suffix)
-    })
+    cgu_def_id.unwrap()
 }
 
 fn numbered_codegen_unit_name(
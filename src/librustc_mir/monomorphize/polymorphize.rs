@@ -0,0 +1,390 @@
+//! Polymorphization Analysis
+//! =========================
+//!
+//! This module implements an analysis of functions, methods and closures to determine which
+//! generic parameters are unused (and eventually, in what ways generic parameters are used - only
+//! for their size, offset of a field, etc.).
+//!
+//! Instances which only differ in generic parameters that are unused can share a single copy of
+//! their code: `Instance::polymorphize` replaces unused parameters with the parameters
+//! themselves, so both the monomorphization collector and codegen end up referring to the same
+//! instance (and symbol).
+
+use rustc_hir::def_id::DefId;
+use rustc_index::bit_set::FiniteBitSet;
+use rustc_middle::mir::{
+    self,
+    visit::{TyContext, Visitor},
+    CastKind, Local, LocalDecl, Location, Rvalue,
+};
+use rustc_middle::ty::{
+    self,
+    adjustment::PointerCast,
+    fold::{TypeFoldable, TypeVisitor},
+    query::Providers,
+    subst::{GenericArg, GenericArgKind, SubstsRef},
+    Const, Ty, TyCtxt,
+};
+use rustc_span::symbol::sym;
+use std::convert::TryInto;
+
+/// Provide implementations of queries relating to polymorphization analysis.
+pub fn provide(providers: &mut Providers<'_>) {
+    providers.unused_generic_params = unused_generic_params;
+}
+
+/// Determine which generic parameters are used by the function/method/closure represented by
+/// `def_id`. Returns a bitset where bits representing unused parameters are set (`is_empty`
+/// indicates all parameters are used).
+fn unused_generic_params(tcx: TyCtxt<'_>, def_id: DefId) -> FiniteBitSet<u32> {
+    debug!("unused_generic_params({:?})", def_id);
+
+    if !tcx.sess.opts.debugging_opts.polymorphize {
+        // If polymorphization disabled, then all parameters are used.
+        return FiniteBitSet::new_empty();
+    }
+
+    let generics = tcx.generics_of(def_id);
+    debug!("unused_generic_params: generics={:?}", generics);
+
+    // Exit early when there are no parameters to be unused.
+    if generics.count() == 0 {
+        return FiniteBitSet::new_empty();
+    }
+
+    // Exit early when there is no MIR available.
+    if !tcx.is_mir_available(def_id) {
+        debug!("unused_generic_params: (no mir available) def_id={:?}", def_id);
+        return FiniteBitSet::new_empty();
+    }
+
+    // Generator bodies refer to their own type after the generator transform, so treat all of
+    // their parameters as used.
+    if tcx.type_of(def_id).is_generator() {
+        return FiniteBitSet::new_empty();
+    }
+
+    // Create a bitset with N rightmost ones for each parameter.
+    let generics_count: u32 =
+        generics.count().try_into().expect("more generic parameters than can fit into a `u32`");
+    let mut unused_parameters = FiniteBitSet::<u32>::new_empty();
+    unused_parameters.set_range(0..generics_count);
+    debug!("unused_generic_params: (start) unused_parameters={:?}", unused_parameters);
+    mark_used_by_default_parameters(tcx, def_id, generics, &mut unused_parameters);
+    debug!("unused_generic_params: (after default) unused_parameters={:?}", unused_parameters);
+
+    // Visit MIR and accumulate used generic parameters.
+    let body = tcx.optimized_mir(def_id);
+    let mut vis = UsedGenericParametersVisitor {
+        tcx,
+        def_id,
+        body,
+        unused_parameters: &mut unused_parameters,
+        in_substs: false,
+    };
+    vis.visit_body(body);
+    debug!("unused_generic_params: (after visitor) unused_parameters={:?}", unused_parameters);
+
+    mark_used_by_predicates(tcx, def_id, &mut unused_parameters);
+    debug!("unused_generic_params: (end) unused_parameters={:?}", unused_parameters);
+
+    // Emit errors for debugging and testing if enabled.
+    if !unused_parameters.is_empty() {
+        emit_unused_generic_params_error(tcx, def_id, generics, &unused_parameters);
+    }
+
+    unused_parameters
+}
+
+/// Some parameters are considered used-by-default, such as non-generic parameters and the dummy
+/// generic parameters from closures, this function marks them as used.
+fn mark_used_by_default_parameters<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    generics: &'tcx ty::Generics,
+    unused_parameters: &mut FiniteBitSet<u32>,
+) {
+    if !tcx.is_trait(def_id) && tcx.is_closure(def_id) {
+        for param in &generics.params {
+            debug!("mark_used_by_default_parameters: (closure) param={:?}", param);
+            unused_parameters.clear(param.index);
+        }
+    } else {
+        for param in &generics.params {
+            debug!("mark_used_by_default_parameters: (other) param={:?}", param);
+            if let ty::GenericParamDefKind::Lifetime = param.kind {
+                unused_parameters.clear(param.index);
+            }
+        }
+    }
+
+    if let Some(parent) = generics.parent {
+        mark_used_by_default_parameters(tcx, parent, tcx.generics_of(parent), unused_parameters);
+    }
+}
+
+/// Search the predicates on used generic parameters for any unused generic parameters, and mark
+/// those as used.
+fn mark_used_by_predicates<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    unused_parameters: &mut FiniteBitSet<u32>,
+) {
+    let def_id = tcx.closure_base_def_id(def_id);
+
+    let is_self_ty_used = |unused_parameters: &mut FiniteBitSet<u32>, self_ty: Ty<'tcx>| {
+        debug!("mark_used_by_predicates: self_ty={:?}", self_ty);
+        if let ty::Param(param) = self_ty.kind {
+            !unused_parameters.contains(param.index).unwrap_or(false)
+        } else {
+            false
+        }
+    };
+
+    let mark_ty = |unused_parameters: &mut FiniteBitSet<u32>, ty: Ty<'tcx>| {
+        for arg in ty.walk() {
+            mark_param_used(unused_parameters, arg);
+        }
+    };
+
+    let predicates = tcx.explicit_predicates_of(def_id);
+    debug!("mark_used_by_predicates: predicates_of={:?}", predicates);
+
+    // Marking a parameter as used can make the self type of another predicate used, so keep
+    // going until nothing changes.
+    loop {
+        let prev = *unused_parameters;
+        for (predicate, _) in predicates.predicates {
+            match predicate {
+                ty::Predicate::Trait(predicate, ..) => {
+                    let trait_ref = predicate.skip_binder().trait_ref;
+                    if is_self_ty_used(unused_parameters, trait_ref.self_ty()) {
+                        for ty in trait_ref.substs.types() {
+                            debug!("mark_used_by_predicates: (trait) ty={:?}", ty);
+                            mark_ty(unused_parameters, ty);
+                        }
+                    }
+                }
+                ty::Predicate::Projection(predicate) => {
+                    let self_ty = predicate.skip_binder().projection_ty.self_ty();
+                    if is_self_ty_used(unused_parameters, self_ty) {
+                        let ty = predicate.skip_binder().ty;
+                        debug!("mark_used_by_predicates: (projection) ty={:?}", ty);
+                        mark_ty(unused_parameters, ty);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if prev == *unused_parameters {
+            break;
+        }
+    }
+}
+
+/// Marks `arg` as used if it is a type or const parameter.
+fn mark_param_used(unused_parameters: &mut FiniteBitSet<u32>, arg: GenericArg<'_>) {
+    match arg.unpack() {
+        GenericArgKind::Type(ty) => {
+            if let ty::Param(param) = ty.kind {
+                unused_parameters.clear(param.index);
+            }
+        }
+        GenericArgKind::Const(ct) => {
+            if let ty::ConstKind::Param(param) = ct.val {
+                unused_parameters.clear(param.index);
+            }
+        }
+        GenericArgKind::Lifetime(_) => {}
+    }
+}
+
+/// Emit errors for the function annotated by `#[rustc_polymorphize_error]`, labelling each generic
+/// parameter which was unused.
+fn emit_unused_generic_params_error<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    generics: &'tcx ty::Generics,
+    unused_parameters: &FiniteBitSet<u32>,
+) {
+    debug!("emit_unused_generic_params_error: def_id={:?}", def_id);
+    let base_def_id = tcx.closure_base_def_id(def_id);
+    if !tcx.has_attr(base_def_id, sym::rustc_polymorphize_error) {
+        return;
+    }
+
+    debug!("emit_unused_generic_params_error: unused_parameters={:?}", unused_parameters);
+    let fn_span = match tcx.opt_item_name(def_id) {
+        Some(ident) => ident.span,
+        _ => tcx.def_span(def_id),
+    };
+
+    let mut err = tcx.sess.struct_span_err(fn_span, "item has unused generic parameters");
+
+    let mut next_generics = Some(generics);
+    while let Some(generics) = next_generics {
+        for param in &generics.params {
+            if unused_parameters.contains(param.index).unwrap_or(false) {
+                debug!("emit_unused_generic_params_error: param={:?}", param);
+                let def_span = tcx.def_span(param.def_id);
+                err.span_label(def_span, &format!("generic parameter `{}` is unused", param.name));
+            }
+        }
+
+        next_generics = generics.parent.map(|did| tcx.generics_of(did));
+    }
+
+    err.emit();
+}
+
+/// Visitor used to aggregate generic parameter uses.
+struct UsedGenericParametersVisitor<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    body: &'a mir::Body<'tcx>,
+    unused_parameters: &'a mut FiniteBitSet<u32>,
+    /// Whether the type being visited is a generic argument of another type (rather than the type
+    /// of a local or one of its fields, elements or pointees), in which case it can be observed
+    /// through trait selection.
+    in_substs: bool,
+}
+
+impl<'a, 'tcx> UsedGenericParametersVisitor<'a, 'tcx> {
+    /// Invoked for closures and generators defined in the item being visited: only the substs
+    /// corresponding to parameters which the child body uses make the parameters in them used.
+    fn visit_child_body(&mut self, def_id: DefId, substs: SubstsRef<'tcx>) {
+        debug!("visit_child_body: def_id={:?}", def_id);
+        let unused = self.tcx.unused_generic_params(def_id);
+        for (i, arg) in substs.iter().enumerate() {
+            let i = i.try_into().unwrap();
+            if !unused.contains(i).unwrap_or(false) {
+                arg.visit_with(self);
+            }
+        }
+        debug!("visit_child_body: unused_parameters={:?}", self.unused_parameters);
+    }
+
+    /// Visit the components of `ty` with `in_substs` set to `in_substs`.
+    fn visit_components(&mut self, ty: Ty<'tcx>, in_substs: bool) -> bool {
+        let prev = std::mem::replace(&mut self.in_substs, in_substs);
+        let result = ty.super_visit_with(self);
+        self.in_substs = prev;
+        result
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for UsedGenericParametersVisitor<'a, 'tcx> {
+    fn visit_local_decl(&mut self, local: Local, local_decl: &LocalDecl<'tcx>) {
+        debug!("visit_local_decl: local_decl={:?}", local_decl);
+        if local == Local::from_usize(1) && self.tcx.is_closure(self.def_id) {
+            // Skip visiting the closure that is currently being processed. The first argument to
+            // the closure is (a reference to) the closure itself, which would otherwise make every
+            // parameter inherited from the parent item used by default.
+            //
+            // Dropping the closure requires drop glue for the closure type, which can only be
+            // built from a fully monomorphic type, so don't skip it in that case.
+            let closure_ty = self.tcx.type_of(self.def_id);
+            if !closure_ty.needs_drop(self.tcx, self.tcx.param_env(self.def_id)) {
+                return;
+            }
+        }
+
+        self.super_local_decl(local, local_decl);
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        // Unsizing to a trait object requires a vtable, which can only be built from a fully
+        // monomorphic type, so every parameter of the source type is used - including the ones
+        // inherited by closures.
+        if let Rvalue::Cast(CastKind::Pointer(PointerCast::Unsize), ref operand, _) = *rvalue {
+            for arg in operand.ty(self.body, self.tcx).walk() {
+                mark_param_used(self.unused_parameters, arg);
+            }
+        }
+
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_const(&mut self, c: &&'tcx Const<'tcx>, _: Location) {
+        c.visit_with(self);
+    }
+
+    fn visit_ty(&mut self, ty: Ty<'tcx>, _: TyContext) {
+        ty.visit_with(self);
+    }
+}
+
+impl<'a, 'tcx> TypeVisitor<'tcx> for UsedGenericParametersVisitor<'a, 'tcx> {
+    fn visit_const(&mut self, c: &'tcx Const<'tcx>) -> bool {
+        debug!("visit_const: c={:?}", c);
+        if !c.has_param_types_or_consts() {
+            return false;
+        }
+
+        match c.val {
+            ty::ConstKind::Param(param) => {
+                debug!("visit_const: param={:?}", param);
+                self.unused_parameters.clear(param.index);
+                false
+            }
+            // Promoteds are evaluated with the identity substitutions of the item they were
+            // promoted from, so visit their body rather than considering all of those parameters
+            // used.
+            ty::ConstKind::Unevaluated(def_id, _, Some(p)) if def_id == self.def_id => {
+                let promoted = self.tcx.promoted_mir(def_id);
+                let mut vis = UsedGenericParametersVisitor {
+                    tcx: self.tcx,
+                    def_id: self.def_id,
+                    body: &promoted[p],
+                    unused_parameters: self.unused_parameters,
+                    in_substs: false,
+                };
+                vis.visit_body(&promoted[p]);
+                false
+            }
+            _ => c.super_visit_with(self),
+        }
+    }
+
+    fn visit_ty(&mut self, ty: Ty<'tcx>) -> bool {
+        debug!("visit_ty: ty={:?}", ty);
+        if !ty.has_param_types_or_consts() {
+            return false;
+        }
+
+        match ty.kind {
+            ty::Param(param) => {
+                debug!("visit_ty: param={:?}", param);
+                self.unused_parameters.clear(param.index);
+                false
+            }
+            ty::Closure(def_id, substs) | ty::Generator(def_id, substs, ..) => {
+                // Skip the closure or generator currently being processed, its substs are the
+                // identity substs of the item being visited.
+                if def_id == self.def_id {
+                    return false;
+                }
+
+                // Dropping the closure or generator requires drop glue for its type, which can
+                // only be built from a fully monomorphic type.
+                if ty.needs_drop(self.tcx, self.tcx.param_env(self.def_id)) {
+                    return self.visit_components(ty, true);
+                }
+
+                // Only the parameters used by the child body are used by it.
+                self.visit_child_body(def_id, substs);
+                false
+            }
+            // `PhantomData<T>` has the same layout for every `T`, so `T` isn't used by it unless
+            // it is passed on as a generic argument.
+            ty::Adt(def, _) if def.is_phantom_data() && !self.in_substs => false,
+            // The layout of these types only depends on the layout of their components.
+            ty::Tuple(..) | ty::Ref(..) | ty::RawPtr(..) | ty::Array(..) | ty::Slice(..) => {
+                let in_substs = self.in_substs;
+                self.visit_components(ty, in_substs)
+            }
+            _ => self.visit_components(ty, true),
+        }
+    }
+}
@@ -90,6 +90,15 @@ struct DepGraphData<K: DepKind> {
     previous_work_products: FxHashMap<WorkProductId, WorkProduct>,
 
     dep_node_debug: Lock<FxHashMap<DepNode<K>, String>>,
+
+    /// For `-Z incremental-explain`, maps each node that could not be marked
+    /// green to the dependency that kept it from being marked green.
+    red_dependencies: Lock<FxHashMap<SerializedDepNodeIndex, SerializedDepNodeIndex>>,
+
+    /// For `-Z query-dep-graph`, the nodes whose results were loaded from the
+    /// on-disk cache, so `#[rustc_clean(loaded_from_disk = "..")]` can check
+    /// that a result was not recomputed.
+    debug_loaded_from_disk: Lock<FxHashSet<DepNode<K>>>,
 }
 
 pub fn hash_result<HashCtxt, R>(hcx: &mut HashCtxt, result: &R) -> Option<Fingerprint>
@@ -118,6 +127,8 @@ impl<K: DepKind> DepGraph<K> {
                 emitting_diagnostics_cond_var: Condvar::new(),
                 previous: prev_graph,
                 colors: DepNodeColorMap::new(prev_graph_node_count),
+                red_dependencies: Default::default(),
+                debug_loaded_from_disk: Default::default(),
             })),
             virtual_dep_node_index: Lrc::new(AtomicU32::new(0)),
         }
@@ -243,6 +254,12 @@ impl<K: DepKind> DepGraph<K> {
             //  - we can get an idea of the runtime cost.
             let mut hcx = cx.create_stable_hashing_context();
 
+            if let Some(filter) = cx.incremental_explain() {
+                if format!("{:?}", key).contains(filter) {
+                    self.explain_execution(data, &key);
+                }
+            }
+
             let result = if no_tcx {
                 task(cx, arg)
             } else {
@@ -437,6 +454,14 @@ impl<K: DepKind> DepGraph<K> {
         self.data.as_ref()?.dep_node_debug.borrow().get(&dep_node).cloned()
     }
 
+    pub fn mark_debug_loaded_from_disk(&self, dep_node: DepNode<K>) {
+        self.data.as_ref().unwrap().debug_loaded_from_disk.lock().insert(dep_node);
+    }
+
+    pub fn debug_was_loaded_from_disk(&self, dep_node: DepNode<K>) -> bool {
+        self.data.as_ref().unwrap().debug_loaded_from_disk.lock().contains(&dep_node)
+    }
+
     pub fn edge_deduplication_data(&self) -> Option<(u64, u64)> {
         if cfg!(debug_assertions) {
             let current_dep_graph = &self.data.as_ref().unwrap().current;
@@ -594,6 +619,7 @@ impl<K: DepKind> DepGraph<K> {
                         dep_node,
                         data.previous.index_to_node(dep_dep_node_index)
                     );
+                    self.record_red_dependency(tcx, data, prev_dep_node_index, dep_dep_node_index);
                     return None;
                 }
                 None => {
@@ -649,6 +675,12 @@ impl<K: DepKind> DepGraph<K> {
                                         dependency {:?} was red after forcing",
                                     dep_node, dep_dep_node
                                 );
+                                self.record_red_dependency(
+                                    tcx,
+                                    data,
+                                    prev_dep_node_index,
+                                    dep_dep_node_index,
+                                );
                                 return None;
                             }
                             None => {
@@ -684,6 +716,12 @@ impl<K: DepKind> DepGraph<K> {
                                 could not be forced",
                             dep_node, dep_dep_node
                         );
+                        self.record_red_dependency(
+                            tcx,
+                            data,
+                            prev_dep_node_index,
+                            dep_dep_node_index,
+                        );
                         return None;
                     }
                 }
@@ -732,6 +770,85 @@ impl<K: DepKind> DepGraph<K> {
         Some(dep_node_index)
     }
 
+    fn record_red_dependency<Ctxt: DepContext<DepKind = K>>(
+        &self,
+        tcx: Ctxt,
+        data: &DepGraphData<K>,
+        prev_dep_node_index: SerializedDepNodeIndex,
+        red_dep_node_index: SerializedDepNodeIndex,
+    ) {
+        if tcx.incremental_explain().is_some() {
+            data.red_dependencies.lock().insert(prev_dep_node_index, red_dep_node_index);
+        }
+    }
+
+    /// Prints why `dep_node` is being executed for `-Z incremental-explain`.
+    /// This follows the dependencies that kept each node from being marked
+    /// green, starting at `dep_node`, until it reaches one that changed by
+    /// itself, which is usually an input like the HIR of an item or the
+    /// metadata of an upstream crate.
+    #[cold]
+    #[inline(never)]
+    fn explain_execution(&self, data: &DepGraphData<K>, dep_node: &DepNode<K>) {
+        let prev_index = match data.previous.node_to_index_opt(dep_node) {
+            Some(prev_index) => prev_index,
+            None => {
+                println!("[incremental] executing `{:?}`, which is new in this session", dep_node);
+                return;
+            }
+        };
+
+        if dep_node.kind.is_eval_always() {
+            println!(
+                "[incremental] re-executing `{:?}`, which is executed in every session",
+                dep_node
+            );
+            return;
+        }
+
+        // Collect the chain first, as printing dep nodes can execute queries.
+        let mut chain = vec![];
+        {
+            let red_dependencies = data.red_dependencies.lock();
+            let mut index = prev_index;
+            while let Some(&dep_index) = red_dependencies.get(&index) {
+                if chain.contains(&dep_index) {
+                    break;
+                }
+                chain.push(dep_index);
+                index = dep_index;
+            }
+        }
+
+        let last = match chain.last() {
+            Some(&last) => last,
+            None => {
+                println!(
+                    "[incremental] re-executing `{:?}`, but no changed dependency was recorded",
+                    dep_node
+                );
+                return;
+            }
+        };
+
+        println!("[incremental] re-executing `{:?}`, because", dep_node);
+        for &index in &chain[..chain.len() - 1] {
+            println!(
+                "[incremental]     `{:?}` changed, because",
+                data.previous.index_to_node(index)
+            );
+        }
+
+        let last_node = data.previous.index_to_node(last);
+        match data.colors.get(last) {
+            Some(DepNodeColor::Red) if last_node.kind.is_eval_always() => {
+                println!("[incremental]     `{:?}` changed, which is an input", last_node)
+            }
+            Some(DepNodeColor::Red) => println!("[incremental]     `{:?}` changed", last_node),
+            _ => println!("[incremental]     `{:?}` could not be checked for changes", last_node),
+        }
+    }
+
     /// Atomically emits some loaded diagnostics.
     /// This may be called concurrently on multiple threads for the same dep node.
     #[cold]
@@ -869,6 +986,7 @@ pub enum WorkProductFileKind {
     Object,
     Bytecode,
     BytecodeCompressed,
+    DwarfObject,
 }
 
 #[derive(Clone)]
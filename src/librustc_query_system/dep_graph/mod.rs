@@ -29,6 +29,12 @@ pub trait DepContext: Copy {
 
     fn debug_dep_tasks(&self) -> bool;
 
+    /// Whether `-Z query-dep-graph` is given.
+    fn debug_query_dep_graph(&self) -> bool;
+
+    /// The filter of `-Z incremental-explain`, if given.
+    fn incremental_explain(&self) -> Option<&str>;
+
     /// Try to force a dep node to execute and see if it's green.
     fn try_force_from_dep_node(&self, dep_node: &DepNode<Self::DepKind>) -> bool;
 
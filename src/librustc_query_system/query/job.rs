@@ -296,8 +296,9 @@ type Waiter<K> = (QueryJobId<K>, usize);
 /// and a span indicating the reason the query waited on `query_ref`.
 /// If `visit` returns Some, this function returns.
 /// For visits of non-resumable waiters it returns the return value of `visit`.
-/// For visits of resumable waiters it returns Some(Some(Waiter)) which has the
-/// required information to resume the waiter.
+/// For visits of resumable waiters it returns the return value of `visit` if that
+/// is already Some(Some(Waiter)), and Some(Some(Waiter)) for the visited waiter
+/// otherwise, which has the required information to resume the waiter.
 /// If all `visit` calls returns None, this function also returns None.
 #[cfg(parallel_compiler)]
 fn visit_waiters<CTX: QueryContext, F>(
@@ -319,9 +320,12 @@ where
     if let Some(latch) = query.latch(query_map) {
         for (i, waiter) in latch.info.lock().waiters.iter().enumerate() {
             if let Some(waiter_query) = waiter.query {
-                if visit(waiter.span, waiter_query).is_some() {
+                match visit(waiter.span, waiter_query) {
+                    // A waiter deeper in the search can be resumed, keep that one
+                    Some(Some(waiter)) => return Some(Some(waiter)),
                     // Return a value which indicates that this waiter can be resumed
-                    return Some(Some((query, i)));
+                    Some(None) => return Some(Some((query, i))),
+                    None => {}
                 }
             }
         }
@@ -369,7 +373,15 @@ fn cycle_check<CTX: QueryContext>(
         stack.pop();
     }
 
-    r
+    // A waiter of `query` may only be resumed if `query` is part of the cycle,
+    // otherwise resuming it would report a cycle error to a query which is not
+    // in a cycle, while the cycle itself stays deadlocked.
+    match r {
+        Some(Some((waitee, _))) if waitee == query && !stack.iter().any(|q| q.1 == query) => {
+            Some(None)
+        }
+        r => r,
+    }
 }
 
 /// Finds out if there's a path to the compiler root (aka. code which isn't in a query)
@@ -405,23 +417,33 @@ where
     F: Fn(&T) -> (Span, QueryJobId<CTX::DepKind>),
 {
     // Deterministically pick an entry point
-    // FIXME: Sort this instead
     let mut hcx = tcx.create_stable_hashing_context();
     queries
         .iter()
         .min_by_key(|v| {
             let (span, query) = f(v);
-            let mut stable_hasher = StableHasher::new();
-            query.query(query_map).hash_stable(&mut hcx, &mut stable_hasher);
             // Prefer entry points which have valid spans for nicer error messages
             // We add an integer to the tuple ensuring that entry points
             // with valid spans are picked first
             let span_cmp = if span == DUMMY_SP { 1 } else { 0 };
-            (span_cmp, stable_hasher.finish::<u64>())
+            (span_cmp, stable_query_hash(query_map, &mut hcx, query))
         })
         .unwrap()
 }
 
+/// A hash of the query of `job` that does not depend on the scheduling of the
+/// threads, unlike the job ids which are assigned in the order jobs start.
+#[cfg(parallel_compiler)]
+fn stable_query_hash<CTX: QueryContext>(
+    query_map: &QueryMap<CTX>,
+    hcx: &mut CTX::StableHashingContext,
+    job: QueryJobId<CTX::DepKind>,
+) -> u64 {
+    let mut stable_hasher = StableHasher::new();
+    job.query(query_map).hash_stable(hcx, &mut stable_hasher);
+    stable_hasher.finish()
+}
+
 /// Looks for query cycles starting from the last query in `jobs`.
 /// If a cycle is found, all queries in the cycle is removed from `jobs` and
 /// the function return true.
@@ -507,9 +529,9 @@ fn remove_cycle<CTX: QueryContext>(
                 .collect(),
         };
 
-        // We unwrap `waiter` here since there must always be one
-        // edge which is resumeable / waited using a query latch
-        let (waitee_query, waiter_idx) = waiter.unwrap();
+        // There must always be one edge in the cycle which is resumeable /
+        // waited using a query latch
+        let (waitee_query, waiter_idx) = waiter.expect("query cycle without a resumable waiter");
 
         // Extract the waiter we want to resume
         let waiter = waitee_query.latch(query_map).unwrap().extract_waiter(waiter_idx);
@@ -542,6 +564,12 @@ pub fn deadlock<CTX: QueryContext>(tcx: CTX, registry: &rayon_core::Registry) {
     let query_map = tcx.try_collect_active_jobs().unwrap();
     let mut jobs: Vec<QueryJobId<CTX::DepKind>> = query_map.keys().cloned().collect();
 
+    // Look for cycles in the same order no matter how the threads were
+    // scheduled, so that the same cycles are broken by resuming the same
+    // waiters and the same cycle errors are reported.
+    let mut hcx = tcx.create_stable_hashing_context();
+    jobs.sort_by_cached_key(|&job| stable_query_hash(&query_map, &mut hcx, job));
+
     let mut found_cycle = false;
 
     while jobs.len() > 0 {
@@ -500,6 +500,9 @@ where
     };
 
     let result = if let Some(result) = result {
+        if unlikely!(tcx.debug_query_dep_graph()) {
+            tcx.dep_graph().mark_debug_loaded_from_disk(*dep_node);
+        }
         result
     } else {
         // We could not load a result from the on-disk cache, so
@@ -1,10 +1,14 @@
 //! Some facilities for tracking how codegen-units are reused during incremental
-//! compilation. This is used for incremental compilation tests and debug
-//! output.
+//! compilation. This is used for incremental compilation tests, debug output
+//! and the `-Z cgu-reuse-summary` report.
 
 use log::debug;
 use rustc_data_structures::fx::FxHashMap;
+use rustc_serialize::json;
 use rustc_span::Span;
+use std::fs;
+use std::io;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
@@ -14,6 +18,16 @@ pub enum CguReuse {
     PostLto,
 }
 
+impl CguReuse {
+    fn as_str(self) -> &'static str {
+        match self {
+            CguReuse::No => "no",
+            CguReuse::PreLto => "pre-lto",
+            CguReuse::PostLto => "post-lto",
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum ComparisonKind {
     Exact,
@@ -25,6 +39,24 @@ struct TrackerData {
     expected_reuse: FxHashMap<String, (String, SendSpan, CguReuse, ComparisonKind)>,
 }
 
+/// The JSON document written by `-Z cgu-reuse-summary`.
+#[derive(RustcEncodable)]
+struct CguReuseSummary {
+    codegen_units: usize,
+    reused_pre_lto: usize,
+    reused_post_lto: usize,
+    not_reused: usize,
+    /// The percentage of codegen units that were reused in some form.
+    reuse_percentage: f64,
+    reuse: Vec<CguReuseEntry>,
+}
+
+#[derive(RustcEncodable)]
+struct CguReuseEntry {
+    name: String,
+    reuse: &'static str,
+}
+
 // Span does not implement `Send`, so we can't just store it in the shared
 // `TrackerData` object. Instead of splitting up `TrackerData` into shared and
 // non-shared parts (which would be complicated), we just mark the `Span` here
@@ -84,6 +116,42 @@ impl CguReuseTracker {
         }
     }
 
+    /// Writes a JSON summary of the reuse recorded for every codegen unit to
+    /// `path`. Does nothing if tracking is disabled.
+    pub fn write_summary(&self, path: &Path) -> io::Result<()> {
+        if let Some(ref data) = self.data {
+            let data = data.lock().unwrap();
+
+            let mut reuse: Vec<_> = data
+                .actual_reuse
+                .iter()
+                .map(|(name, &kind)| CguReuseEntry { name: name.clone(), reuse: kind.as_str() })
+                .collect();
+            reuse.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let count = |kind: CguReuse| data.actual_reuse.values().filter(|&&k| k == kind).count();
+            let not_reused = count(CguReuse::No);
+            let reuse_percentage = if reuse.is_empty() {
+                0.0
+            } else {
+                100.0 * (reuse.len() - not_reused) as f64 / reuse.len() as f64
+            };
+
+            let summary = CguReuseSummary {
+                codegen_units: reuse.len(),
+                reused_pre_lto: count(CguReuse::PreLto),
+                reused_post_lto: count(CguReuse::PostLto),
+                not_reused,
+                reuse_percentage,
+                reuse,
+            };
+
+            fs::write(path, json::as_pretty_json(&summary).to_string())?;
+        }
+
+        Ok(())
+    }
+
     pub fn check_expected_reuse(&self, diag: &rustc_errors::Handler) {
         if let Some(ref data) = self.data {
             let data = data.lock().unwrap();
@@ -175,6 +175,67 @@ pub enum DebugInfo {
     Full,
 }
 
+/// The different settings that the `-C split-debuginfo` flag can have.
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+pub enum SplitDebuginfo {
+    /// Debuginfo is kept in the object files and copied into the final
+    /// artifact by the linker.
+    Off,
+
+    /// Debuginfo is emitted into one `.dwo` file per codegen unit, the object
+    /// files only contain skeleton units and, after linking, all `.dwo` files
+    /// are packaged into a single `.dwp` file next to the output.
+    Packed,
+
+    /// Debuginfo is emitted into one `.dwo` file per codegen unit which is left
+    /// in place and referenced from the skeleton units of the final artifact.
+    Unpacked,
+}
+
+/// The different settings that the `-Z strip` flag can have.
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+pub enum Strip {
+    /// Do not strip anything.
+    None,
+
+    /// Strip debuginfo.
+    Debuginfo,
+
+    /// Strip debuginfo and the symbol table.
+    Symbols,
+}
+
+/// The different settings that the `-Z cgu-partitioning-strategy` flag can have.
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+pub enum CguPartitioningStrategy {
+    /// Place items by source-level module and merge the smallest codegen
+    /// units until the requested number is reached.
+    Default,
+
+    /// Place items into a fixed number of codegen units by a stable hash of
+    /// their home module and item path, and never merge codegen units.
+    StableHash,
+}
+
+/// The output formats of the `-Z self-profile-summary` flag.
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+pub enum SelfProfileSummary {
+    /// A table of the items that took the longest, for humans.
+    Text,
+
+    /// A JSON document with the times of all items, for tools.
+    Json,
+}
+
+impl SplitDebuginfo {
+    pub fn is_split(self) -> bool {
+        match self {
+            SplitDebuginfo::Off => false,
+            SplitDebuginfo::Packed | SplitDebuginfo::Unpacked => true,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord, RustcEncodable, RustcDecodable)]
 pub enum OutputType {
     Bitcode,
@@ -185,6 +246,7 @@ pub enum OutputType {
     Object,
     Exe,
     DepInfo,
+    MonoReport,
 }
 
 impl_stable_hash_via_hash!(OutputType);
@@ -192,7 +254,10 @@ impl_stable_hash_via_hash!(OutputType);
 impl OutputType {
     fn is_compatible_with_codegen_units_and_single_output_file(&self) -> bool {
         match *self {
-            OutputType::Exe | OutputType::DepInfo | OutputType::Metadata => true,
+            OutputType::Exe
+            | OutputType::DepInfo
+            | OutputType::Metadata
+            | OutputType::MonoReport => true,
             OutputType::Bitcode
             | OutputType::Assembly
             | OutputType::LlvmAssembly
@@ -211,6 +276,7 @@ impl OutputType {
             OutputType::Metadata => "metadata",
             OutputType::Exe => "link",
             OutputType::DepInfo => "dep-info",
+            OutputType::MonoReport => "mono-report",
         }
     }
 
@@ -224,13 +290,14 @@ impl OutputType {
             "metadata" => OutputType::Metadata,
             "link" => OutputType::Exe,
             "dep-info" => OutputType::DepInfo,
+            "mono-report" => OutputType::MonoReport,
             _ => return None,
         })
     }
 
     fn shorthands_display() -> String {
         format!(
-            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
+            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
             OutputType::Bitcode.shorthand(),
             OutputType::Assembly.shorthand(),
             OutputType::LlvmAssembly.shorthand(),
@@ -239,6 +306,7 @@ impl OutputType {
             OutputType::Metadata.shorthand(),
             OutputType::Exe.shorthand(),
             OutputType::DepInfo.shorthand(),
+            OutputType::MonoReport.shorthand(),
         )
     }
 
@@ -252,6 +320,7 @@ impl OutputType {
             OutputType::Metadata => "rmeta",
             OutputType::DepInfo => "d",
             OutputType::Exe => "",
+            OutputType::MonoReport => "mono.json",
         }
     }
 }
@@ -269,6 +338,9 @@ pub enum ErrorOutputType {
         /// human output.
         json_rendered: HumanReadableErrorType,
     },
+    /// A single SARIF 2.1.0 log for the whole session, consumed by code-scanning
+    /// services.
+    Sarif,
 }
 
 impl Default for ErrorOutputType {
@@ -318,7 +390,8 @@ impl OutputTypes {
             | OutputType::LlvmAssembly
             | OutputType::Mir
             | OutputType::Object
-            | OutputType::Exe => true,
+            | OutputType::Exe
+            | OutputType::MonoReport => true,
             OutputType::Metadata | OutputType::DepInfo => false,
         })
     }
@@ -469,6 +542,7 @@ impl_stable_hash_via_hash!(OutputFilenames);
 
 pub const RLINK_EXT: &str = "rlink";
 pub const RUST_CGU_EXT: &str = "rcgu";
+pub const DWARF_OBJECT_EXT: &str = "dwo";
 
 impl OutputFilenames {
     pub fn new(
@@ -529,6 +603,20 @@ impl OutputFilenames {
         path.set_extension(extension);
         path
     }
+
+    /// Gets the path of the `.dwo` file that the split debuginfo of the given
+    /// codegen unit is written to, or `None` if debuginfo isn't being split.
+    pub fn split_dwarf_path(
+        &self,
+        split_debuginfo: SplitDebuginfo,
+        codegen_unit_name: Option<&str>,
+    ) -> Option<PathBuf> {
+        if split_debuginfo.is_split() {
+            Some(self.temp_path_ext(DWARF_OBJECT_EXT, codegen_unit_name))
+        } else {
+            None
+        }
+    }
 }
 
 pub fn host_triple() -> &'static str {
@@ -576,6 +664,8 @@ impl Default for Options {
             edition: DEFAULT_EDITION,
             json_artifact_notifications: false,
             pretty: None,
+            early_warnings: Vec::new(),
+            collect_borrowck_facts: false,
         }
     }
 }
@@ -625,6 +715,7 @@ impl DebuggingOptions {
             report_delayed_bugs: self.report_delayed_bugs,
             macro_backtrace: self.macro_backtrace,
             deduplicate_diagnostics: self.deduplicate_diagnostics,
+            group_macro_diagnostics: self.group_macro_diagnostics,
         }
     }
 }
@@ -1119,13 +1210,14 @@ pub fn parse_error_format(
             }
             Some("json") => ErrorOutputType::Json { pretty: false, json_rendered },
             Some("pretty-json") => ErrorOutputType::Json { pretty: true, json_rendered },
+            Some("sarif") => ErrorOutputType::Sarif,
             Some("short") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
 
             Some(arg) => early_error(
                 ErrorOutputType::HumanReadable(HumanReadableErrorType::Default(color)),
                 &format!(
-                    "argument for `--error-format` must be `human`, `json` or \
-                     `short` (instead was `{}`)",
+                    "argument for `--error-format` must be `human`, `json`, \
+                     `sarif` or `short` (instead was `{}`)",
                     arg
                 ),
             ),
@@ -1202,6 +1294,12 @@ fn check_debug_option_stability(
                 "`--error-format=human-annotate-rs` is unstable",
             );
         }
+        if let ErrorOutputType::Sarif = error_format {
+            early_error(
+                ErrorOutputType::Json { pretty: false, json_rendered },
+                "`--error-format=sarif` is unstable",
+            );
+        }
     }
 }
 
@@ -1226,6 +1324,12 @@ fn parse_output_types(
                         ),
                     )
                 });
+                if output_type == OutputType::MonoReport && !debugging_opts.unstable_options {
+                    early_error(
+                        error_format,
+                        "`--emit=mono-report` is unstable and requires `-Z unstable-options`",
+                    );
+                }
                 let path = parts.next().map(PathBuf::from);
                 output_types.insert(output_type, path);
             }
@@ -1237,10 +1341,26 @@ fn parse_output_types(
     OutputTypes(output_types)
 }
 
+/// Reports a warning about the command line while building the `Options`. A
+/// SARIF log is written as a whole by the session's emitter, so for SARIF
+/// output the warning is kept in `early_warnings` and reported by
+/// `build_session` instead.
+fn early_warn_or_buffer(
+    error_format: ErrorOutputType,
+    early_warnings: &mut Vec<String>,
+    msg: &str,
+) {
+    match error_format {
+        ErrorOutputType::Sarif => early_warnings.push(msg.to_owned()),
+        _ => early_warn(error_format, msg),
+    }
+}
+
 fn should_override_cgus_and_disable_thinlto(
     output_types: &OutputTypes,
     matches: &getopts::Matches,
     error_format: ErrorOutputType,
+    early_warnings: &mut Vec<String>,
     mut codegen_units: Option<usize>,
 ) -> (bool, Option<usize>) {
     let mut disable_thinlto = false;
@@ -1258,8 +1378,9 @@ fn should_override_cgus_and_disable_thinlto(
             Some(n) if n > 1 => {
                 if matches.opt_present("o") {
                     for ot in &incompatible {
-                        early_warn(
+                        early_warn_or_buffer(
                             error_format,
+                            early_warnings,
                             &format!(
                                 "`--emit={}` with `-o` incompatible with \
                                  `-C codegen-units=N` for N > 1",
@@ -1267,7 +1388,11 @@ fn should_override_cgus_and_disable_thinlto(
                             ),
                         );
                     }
-                    early_warn(error_format, "resetting to default -C codegen-units=1");
+                    early_warn_or_buffer(
+                        error_format,
+                        early_warnings,
+                        "resetting to default -C codegen-units=1",
+                    );
                     codegen_units = Some(1);
                     disable_thinlto = true;
                 }
@@ -1649,16 +1774,27 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let output_types = parse_output_types(&debugging_opts, matches, error_format);
 
+    let mut early_warnings = Vec::new();
     let mut cg = build_codegen_options(matches, error_format);
     let (disable_thinlto, mut codegen_units) = should_override_cgus_and_disable_thinlto(
         &output_types,
         matches,
         error_format,
+        &mut early_warnings,
         cg.codegen_units,
     );
 
     check_thread_count(&debugging_opts, error_format);
 
+    if debugging_opts.emit_future_incompat_report
+        && !matches!(error_format, ErrorOutputType::Json { .. })
+    {
+        early_error(
+            error_format,
+            "`-Z emit-future-incompat-report` requires `--error-format=json`",
+        );
+    }
+
     let incremental = cg.incremental.as_ref().map(PathBuf::from);
 
     if debugging_opts.profile && incremental.is_some() {
@@ -1706,7 +1842,12 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     // to use them interchangeably. See the note above (regarding `-O` and `-C opt-level`)
     // for more details.
     let debug_assertions = cg.debug_assertions.unwrap_or(opt_level == OptLevel::No);
-    let debuginfo = select_debuginfo(matches, &cg, error_format);
+    // There's no point in generating debuginfo only to have the linker strip it
+    // again, so skip generating it entirely in that case.
+    let debuginfo = match debugging_opts.strip {
+        Strip::None => select_debuginfo(matches, &cg, error_format),
+        Strip::Debuginfo | Strip::Symbols => DebugInfo::None,
+    };
 
     let mut search_paths = vec![];
     for s in &matches.opt_strs("L") {
@@ -1720,7 +1861,11 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     let borrowck_mode = parse_borrowck_mode(&debugging_opts, error_format);
 
     if !cg.remark.is_empty() && debuginfo == DebugInfo::None {
-        early_warn(error_format, "-C remark requires \"-C debuginfo=n\" to show source locations");
+        early_warn_or_buffer(
+            error_format,
+            &mut early_warnings,
+            "-C remark requires \"-C debuginfo=n\" to show source locations",
+        );
     }
 
     let externs = parse_externs(matches, &debugging_opts, error_format);
@@ -1762,6 +1907,8 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         edition,
         json_artifact_notifications,
         pretty,
+        early_warnings,
+        collect_borrowck_facts: false,
     }
 }
 
@@ -1997,9 +2144,9 @@ impl PpMode {
 /// how the hash should be calculated when adding a new command-line argument.
 crate mod dep_tracking {
     use super::{
-        CFGuard, CrateType, DebugInfo, ErrorOutputType, LinkerPluginLto, LtoCli, OptLevel,
-        OutputTypes, Passes, Sanitizer, SourceFileHashAlgorithm, SwitchWithOptPath,
-        SymbolManglingVersion,
+        CFGuard, CguPartitioningStrategy, CrateType, DebugInfo, ErrorOutputType, LinkerPluginLto,
+        LtoCli, OptLevel, OutputTypes, Passes, Sanitizer, SourceFileHashAlgorithm, SplitDebuginfo,
+        Strip, SwitchWithOptPath, SymbolManglingVersion,
     };
     use crate::lint;
     use crate::utils::NativeLibraryKind;
@@ -2066,12 +2213,15 @@ crate mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(OptLevel);
     impl_dep_tracking_hash_via_hash!(LtoCli);
     impl_dep_tracking_hash_via_hash!(DebugInfo);
+    impl_dep_tracking_hash_via_hash!(SplitDebuginfo);
+    impl_dep_tracking_hash_via_hash!(Strip);
     impl_dep_tracking_hash_via_hash!(UnstableFeatures);
     impl_dep_tracking_hash_via_hash!(OutputTypes);
     impl_dep_tracking_hash_via_hash!(NativeLibraryKind);
     impl_dep_tracking_hash_via_hash!(Sanitizer);
     impl_dep_tracking_hash_via_hash!(Option<Sanitizer>);
     impl_dep_tracking_hash_via_hash!(CFGuard);
+    impl_dep_tracking_hash_via_hash!(CguPartitioningStrategy);
     impl_dep_tracking_hash_via_hash!(TargetTriple);
     impl_dep_tracking_hash_via_hash!(Edition);
     impl_dep_tracking_hash_via_hash!(LinkerPluginLto);
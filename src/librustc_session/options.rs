@@ -143,6 +143,15 @@ top_level_options!(
         json_artifact_notifications: bool [TRACKED],
 
         pretty: Option<PpMode> [UNTRACKED],
+
+        // Warnings about the command line that are reported once the session
+        // exists, because its emitter writes a single SARIF log.
+        early_warnings: Vec<String> [UNTRACKED],
+
+        // Set by drivers that want the borrow checker facts of every body. The
+        // `mir_borrowck` provider collects them then, so its results are not
+        // cached on disk. Not a command line option.
+        collect_borrowck_facts: bool [UNTRACKED],
     }
 );
 
@@ -252,6 +261,7 @@ macro_rules! options {
         pub const parse_sanitizer_list: &str = "comma separated list of sanitizers";
         pub const parse_sanitizer_memory_track_origins: &str = "0, 1, or 2";
         pub const parse_cfguard: &str = "either `disabled`, `nochecks`, or `checks`";
+        pub const parse_cgu_partitioning_strategy: &str = "either `default` or `stable-hash`";
         pub const parse_linker_flavor: &str = ::rustc_target::spec::LinkerFlavor::one_of();
         pub const parse_optimization_fuel: &str = "crate=integer";
         pub const parse_unpretty: &str = "`string` or `string=string`";
@@ -265,12 +275,16 @@ macro_rules! options {
         pub const parse_merge_functions: &str = "one of: `disabled`, `trampolines`, or `aliases`";
         pub const parse_symbol_mangling_version: &str = "either `legacy` or `v0` (RFC 2603)";
         pub const parse_src_file_hash: &str = "either `md5` or `sha1`";
+        pub const parse_split_debuginfo: &str = "one of: `off`, `packed`, or `unpacked`";
+        pub const parse_strip: &str = "one of: `none`, `debuginfo`, or `symbols`";
+        pub const parse_self_profile_summary: &str = "either `text` or `json`";
     }
 
     #[allow(dead_code)]
     mod $mod_set {
         use super::{$struct_name, Passes, Sanitizer, LtoCli, LinkerPluginLto, SwitchWithOptPath,
-            SymbolManglingVersion, CFGuard, SourceFileHashAlgorithm};
+            SymbolManglingVersion, CFGuard, CguPartitioningStrategy, SourceFileHashAlgorithm,
+            SplitDebuginfo, Strip, SelfProfileSummary};
         use rustc_target::spec::{LinkerFlavor, MergeFunctions, PanicStrategy, RelroLevel};
         use std::path::PathBuf;
         use std::str::FromStr;
@@ -499,6 +513,18 @@ macro_rules! options {
             true
         }
 
+        fn parse_cgu_partitioning_strategy(
+            slot: &mut CguPartitioningStrategy,
+            v: Option<&str>,
+        ) -> bool {
+            *slot = match v {
+                Some("default") => CguPartitioningStrategy::Default,
+                Some("stable-hash") => CguPartitioningStrategy::StableHash,
+                _ => return false,
+            };
+            true
+        }
+
         fn parse_linker_flavor(slote: &mut Option<LinkerFlavor>, v: Option<&str>) -> bool {
             match v.and_then(LinkerFlavor::from_str) {
                 Some(lf) => *slote = Some(lf),
@@ -617,6 +643,38 @@ macro_rules! options {
             }
             true
         }
+
+        fn parse_split_debuginfo(slot: &mut SplitDebuginfo, v: Option<&str>) -> bool {
+            *slot = match v {
+                Some("off") => SplitDebuginfo::Off,
+                Some("packed") => SplitDebuginfo::Packed,
+                Some("unpacked") => SplitDebuginfo::Unpacked,
+                _ => return false,
+            };
+            true
+        }
+
+        fn parse_strip(slot: &mut Strip, v: Option<&str>) -> bool {
+            *slot = match v {
+                Some("none") => Strip::None,
+                Some("debuginfo") => Strip::Debuginfo,
+                Some("symbols") => Strip::Symbols,
+                _ => return false,
+            };
+            true
+        }
+
+        fn parse_self_profile_summary(
+            slot: &mut Option<SelfProfileSummary>,
+            v: Option<&str>,
+        ) -> bool {
+            *slot = match v {
+                Some("text") => Some(SelfProfileSummary::Text),
+                Some("json") => Some(SelfProfileSummary::Json),
+                _ => return false,
+            };
+            true
+        }
     }
 ) }
 
@@ -707,6 +765,9 @@ options! {CodegenOptions, CodegenSetter, basic_codegen_options,
         "save all temporary output files during compilation (default: no)"),
     soft_float: bool = (false, parse_bool, [TRACKED],
         "use soft float ABI (*eabihf targets only) (default: no)"),
+    split_debuginfo: SplitDebuginfo = (SplitDebuginfo::Off, parse_split_debuginfo, [TRACKED],
+        "emit debuginfo into separate `.dwo` files, optionally packaged into a `.dwp` file \
+        (`off`, `packed`, or `unpacked`; default: `off`)"),
     target_cpu: Option<String> = (None, parse_opt_string, [TRACKED],
         "select target processor (`rustc --print target-cpus` for details)"),
     target_feature: String = (String::new(), parse_string, [TRACKED],
@@ -746,6 +807,14 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "select which borrowck is used (`mir` or `migrate`) (default: `migrate`)"),
     borrowck_stats: bool = (false, parse_bool, [UNTRACKED],
         "gather borrowck statistics (default: no)"),
+    cgu_partitioning_strategy: CguPartitioningStrategy = (CguPartitioningStrategy::Default,
+        parse_cgu_partitioning_strategy, [TRACKED],
+        "the strategy used to partition mono items into codegen units: `default`, or \
+        `stable-hash` for codegen unit boundaries that stay stable across edits \
+        (default: `default`)"),
+    cgu_reuse_summary: bool = (false, parse_bool, [UNTRACKED],
+        "write a JSON summary of how codegen units were reused by incremental compilation \
+        to `<crate>.cgu-reuse.json` (default: no)"),
     codegen_backend: Option<String> = (None, parse_opt_string, [TRACKED],
         "the backend to use"),
     control_flow_guard: CFGuard = (CFGuard::Disabled, parse_cfguard, [UNTRACKED],
@@ -788,6 +857,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "in addition to `.mir` files, create graphviz `.dot` files (default: no)"),
     embed_bitcode: bool = (false, parse_bool, [TRACKED],
         "embed LLVM bitcode in object files (default: no)"),
+    emit_future_incompat_report: bool = (false, parse_bool, [UNTRACKED],
+        "emit a JSON report of all future-incompatible lints that fired, including the ones \
+        silenced by `--cap-lints` (requires `--error-format=json`) (default: no)"),
     emit_stack_sizes: bool = (false, parse_bool, [UNTRACKED],
         "emit a section containing stack size metadata (default: no)"),
     fewer_names: bool = (false, parse_bool, [TRACKED],
@@ -799,17 +871,29 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "force all crates to be `rustc_private` unstable (default: no)"),
     fuel: Option<(String, u64)> = (None, parse_optimization_fuel, [TRACKED],
         "set the optimization fuel quota for a crate"),
+    group_macro_diagnostics: bool = (false, parse_bool, [UNTRACKED],
+        "report diagnostics with the same code and message from different invocations \
+        of a macro once, listing the other invocations (default: no)"),
     hir_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some statistics about AST and HIR (default: no)"),
     human_readable_cgu_names: bool = (false, parse_bool, [TRACKED],
         "generate human-readable, predictable names for codegen units (default: no)"),
     identify_regions: bool = (false, parse_bool, [UNTRACKED],
         "display unnamed regions as `'<id>`, using a non-ident unique id (default: no)"),
+    incremental_explain: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "print why incremental compilation executes the queries whose dep nodes \
+        contain this string, e.g. `typeck_tables_of` or `main`"),
     incremental_ignore_spans: bool = (false, parse_bool, [UNTRACKED],
         "ignore spans during ICH computation -- used for testing (default: no)"),
     incremental_info: bool = (false, parse_bool, [UNTRACKED],
         "print high-level information about incremental reuse (or the lack thereof) \
         (default: no)"),
+    incremental_max_age: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+        "delete the incremental compilation data of crates that have not been \
+        compiled for this many days"),
+    incremental_max_size: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+        "delete the incremental compilation data of the least recently compiled \
+        crates until the incremental compilation directory is at most this many MiB"),
     incremental_verify_ich: bool = (false, parse_bool, [UNTRACKED],
         "verify incr. comp. hashes of green query instances (default: no)"),
     inline_in_all_cgus: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -882,6 +966,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         (default: PLT is disabled if full relro is enabled)"),
     polonius: bool = (false, parse_bool, [UNTRACKED],
         "enable polonius-based borrow-checker (default: no)"),
+    polymorphize: bool = (false, parse_bool, [TRACKED],
+        "share code between instances of generic items that differ only in unused generic \
+        parameters (default: no)"),
     pre_link_arg: (/* redirected to pre_link_args */) = ((), parse_string_push, [UNTRACKED],
         "a single extra argument to prepend the linker invocation (can be used several times)"),
     pre_link_args: Vec<String> = (Vec::new(), parse_list, [UNTRACKED],
@@ -935,6 +1022,10 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         for example: `-Z self-profile-events=default,query-keys`
         all options: none, all, default, generic-activity, query-provider, query-cache-hit
                      query-blocked, incr-cache-load, query-keys, function-args, args, llvm"),
+    self_profile_summary: Option<SelfProfileSummary> = (None, parse_self_profile_summary,
+        [UNTRACKED],
+        "print how long type checking, borrow checking, const evaluation, trait selection, \
+        MIR optimization, codegen and LLVM took for each item, as `text` or `json`"),
     share_generics: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "make the current crate share its generic instantiations"),
     show_span: Option<String> = (None, parse_opt_string, [TRACKED],
@@ -944,6 +1035,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "exclude spans when debug-printing compiler state (default: no)"),
     src_hash_algorithm: Option<SourceFileHashAlgorithm> = (None, parse_src_file_hash, [TRACKED],
         "hash algorithm of source files in debug info (`md5`, or `sha1`)"),
+    strip: Strip = (Strip::None, parse_strip, [UNTRACKED],
+        "tell the linker which information to strip from the output; stripping debuginfo \
+        also skips generating it (`none`, `debuginfo`, or `symbols`; default: `none`)"),
     strip_debuginfo_if_disabled: bool = (false, parse_bool, [TRACKED],
         "tell the linker to strip debuginfo when building without debuginfo enabled \
         (default: no)"),
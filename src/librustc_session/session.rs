@@ -1,7 +1,8 @@
 use crate::cgu_reuse_tracker::CguReuseTracker;
 use crate::code_stats::CodeStats;
 pub use crate::code_stats::{DataTypeKind, FieldInfo, SizeKind, VariantInfo};
-use crate::config::{self, OutputType, PrintRequest, Sanitizer, SwitchWithOptPath};
+use crate::config::{self, DebugInfo, OutputType, PrintRequest, Sanitizer};
+use crate::config::{SplitDebuginfo, SwitchWithOptPath};
 use crate::filesearch;
 use crate::lint;
 use crate::parse::ParseSess;
@@ -18,7 +19,10 @@ use rustc_data_structures::sync::{
 use rustc_errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitterWriter;
 use rustc_errors::emitter::{Emitter, EmitterWriter, HumanReadableErrorType};
 use rustc_errors::json::JsonEmitter;
-use rustc_errors::{Applicability, DiagnosticBuilder, DiagnosticId, ErrorReported};
+use rustc_errors::sarif::SarifEmitter;
+use rustc_errors::{
+    Applicability, DiagnosticBuilder, DiagnosticId, ErrorReported, OrderedDiagnostics,
+};
 use rustc_span::edition::Edition;
 use rustc_span::source_map::{self, FileLoader, MultiSpan, RealFileLoader, SourceMap, Span};
 use rustc_span::SourceFileHashAlgorithm;
@@ -330,6 +334,14 @@ impl Session {
         &self.parse_sess.span_diagnostic
     }
 
+    /// Emits the report of all future-incompatible lints that fired so far, if
+    /// `-Z emit-future-incompat-report` is specified.
+    pub fn emit_future_incompat_report(&self) {
+        if self.opts.debugging_opts.emit_future_incompat_report {
+            self.diagnostic().emit_future_incompat_report();
+        }
+    }
+
     /// Analogous to calling methods on the given `DiagnosticBuilder`, but
     /// deduplicates on lint ID, span (if any), and message for this `Session`
     fn diag_once<'a, 'b>(
@@ -758,6 +770,17 @@ impl Session {
         self.opts.debugging_opts.threads
     }
 
+    /// When the front end runs on multiple threads, holds back the diagnostics
+    /// reported until the returned guard is dropped, and then emits them in an
+    /// order that does not depend on how the threads were scheduled.
+    pub fn order_diagnostics_across_threads(&self) -> Option<OrderedDiagnostics<'_>> {
+        if cfg!(parallel_compiler) && self.threads() > 1 {
+            Some(self.diagnostic().order_diagnostics())
+        } else {
+            None
+        }
+    }
+
     /// Returns the number of codegen units that should be used for this
     /// compilation
     pub fn codegen_units(&self) -> usize {
@@ -864,6 +887,16 @@ impl Session {
         // then try to skip it where possible.
         dbg_opts.plt.unwrap_or(needs_plt || !full_relro)
     }
+
+    /// Returns how debuginfo should be split out of the object files. This is
+    /// always `SplitDebuginfo::Off` if no debuginfo is generated at all.
+    pub fn split_debuginfo(&self) -> SplitDebuginfo {
+        if self.opts.debuginfo == DebugInfo::None {
+            SplitDebuginfo::Off
+        } else {
+            self.opts.cg.split_debuginfo
+        }
+    }
 }
 
 pub fn build_session(
@@ -944,6 +977,12 @@ fn default_emitter(
             )
             .ui_testing(sopts.debugging_opts.ui_testing),
         ),
+        (config::ErrorOutputType::Sarif, None) => {
+            Box::new(SarifEmitter::stderr(Some(registry), source_map.clone()))
+        }
+        (config::ErrorOutputType::Sarif, Some(dst)) => {
+            Box::new(SarifEmitter::new(dst, Some(registry), source_map.clone()))
+        }
     }
 }
 
@@ -1003,6 +1042,9 @@ pub fn build_session_with_source_map(
         emitter,
         sopts.debugging_opts.diagnostic_handler_flags(can_emit_warnings),
     );
+    for msg in &sopts.early_warnings {
+        span_diagnostic.warn(msg);
+    }
 
     let self_profiler = if let SwitchWithOptPath::Enabled(ref d) = sopts.debugging_opts.self_profile
     {
@@ -1017,7 +1059,7 @@ pub fn build_session_with_source_map(
         match profiler {
             Ok(profiler) => Some(Arc::new(profiler)),
             Err(e) => {
-                early_warn(sopts.error_format, &format!("failed to create profiler: {}", e));
+                span_diagnostic.warn(&format!("failed to create profiler: {}", e));
                 None
             }
         }
@@ -1058,11 +1100,12 @@ pub fn build_session_with_source_map(
     });
     let working_dir = file_path_mapping.map_prefix(working_dir);
 
-    let cgu_reuse_tracker = if sopts.debugging_opts.query_dep_graph {
-        CguReuseTracker::new()
-    } else {
-        CguReuseTracker::new_disabled()
-    };
+    let cgu_reuse_tracker =
+        if sopts.debugging_opts.query_dep_graph || sopts.debugging_opts.cgu_reuse_summary {
+            CguReuseTracker::new()
+        } else {
+            CguReuseTracker::new_disabled()
+        };
 
     let prof = SelfProfilerRef::new(
         self_profiler,
@@ -1221,6 +1264,19 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
             ));
         }
     }
+
+    // Split debuginfo relies on split DWARF, which is only available for some
+    // object formats, and is still experimental.
+    if sess.opts.cg.split_debuginfo != SplitDebuginfo::Off {
+        if !sess.target.target.options.supports_split_dwarf {
+            sess.err(&format!(
+                "`-C split-debuginfo` is not supported for the `{}` target",
+                sess.opts.target_triple
+            ));
+        } else if !sess.unstable_options() {
+            sess.err("`-C split-debuginfo` is unstable and requires `-Z unstable-options`");
+        }
+    }
 }
 
 /// Holds data on the current incremental compilation session, if there is one.
@@ -1250,6 +1306,7 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
         config::ErrorOutputType::Json { pretty, json_rendered } => {
             Box::new(JsonEmitter::basic(pretty, json_rendered, false))
         }
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
     };
     let handler = rustc_errors::Handler::with_emitter(true, None, emitter);
     handler.struct_fatal(msg).emit();
@@ -1265,6 +1322,7 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
         config::ErrorOutputType::Json { pretty, json_rendered } => {
             Box::new(JsonEmitter::basic(pretty, json_rendered, false))
         }
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
     };
     let handler = rustc_errors::Handler::with_emitter(true, None, emitter);
     handler.struct_warn(msg).emit();
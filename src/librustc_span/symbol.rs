@@ -427,6 +427,7 @@ symbols! {
         lint_reasons,
         literal,
         llvm_asm,
+        loaded_from_disk,
         local_inner_macros,
         log_syntax,
         loop_break_value,
@@ -658,6 +659,7 @@ symbols! {
         rustc_peek_maybe_init,
         rustc_peek_maybe_uninit,
         rustc_peek_indirectly_mutable,
+        rustc_polymorphize_error,
         rustc_private,
         rustc_proc_macro_decls,
         rustc_promotable,
@@ -116,7 +116,9 @@ fn get_symbol_hash<'tcx>(
 
         // also include any type parameters (for generic items)
         assert!(!substs.has_erasable_regions());
-        assert!(!substs.needs_subst());
+        assert!(!substs.needs_subst() || tcx.sess.opts.debugging_opts.polymorphize);
+        // Polymorphized instances keep only their unused parameters as `ty::Param`.
+        assert!(!instance.has_used_param_types_or_consts(tcx));
         substs.hash_stable(&mut hcx, &mut hasher);
 
         if let Some(instantiating_crate) = instantiating_crate {
@@ -800,6 +800,11 @@ pub struct TargetOptions {
     /// Whether or not RelaxElfRelocation flag will be passed to the linker
     pub relax_elf_relocations: bool,
 
+    /// Whether the object format and toolchain of this target support split
+    /// DWARF, i.e. emitting debuginfo into `.dwo` files which are referenced by
+    /// skeleton units in the object files and can be packaged into a `.dwp`.
+    pub supports_split_dwarf: bool,
+
     /// Additional arguments to pass to LLVM, similar to the `-C llvm-args` codegen option.
     pub llvm_args: Vec<String>,
 }
@@ -889,6 +894,7 @@ impl Default for TargetOptions {
             target_mcount: "mcount".to_string(),
             llvm_abiname: "".to_string(),
             relax_elf_relocations: false,
+            supports_split_dwarf: false,
             llvm_args: vec![],
         }
     }
@@ -1203,6 +1209,7 @@ impl Target {
         key!(target_mcount);
         key!(llvm_abiname);
         key!(relax_elf_relocations, bool);
+        key!(supports_split_dwarf, bool);
         key!(llvm_args, list);
 
         if let Some(array) = obj.find("abi-blacklist").and_then(Json::as_array) {
@@ -1430,6 +1437,7 @@ impl ToJson for Target {
         target_option_val!(target_mcount);
         target_option_val!(llvm_abiname);
         target_option_val!(relax_elf_relocations);
+        target_option_val!(supports_split_dwarf);
         target_option_val!(llvm_args);
 
         if default.abi_blacklist != self.options.abi_blacklist {
@@ -3,3 +3,4 @@
 extern crate test;
 
 mod hash;
+mod sync;
@@ -0,0 +1,107 @@
+#![cfg(test)]
+
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+use std::sync::{Arc, Barrier, Condvar, Mutex, RwLock};
+use std::thread;
+use test::Bencher;
+
+const THREADS: usize = 4;
+const ITERS: usize = 1000;
+
+/// Runs `f` on `THREADS` threads at once, all started at the same time to
+/// maximize contention.
+fn contended<T: Send + Sync + 'static>(shared: Arc<T>, f: fn(&T)) {
+    let barrier = Arc::new(Barrier::new(THREADS));
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let shared = shared.clone();
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..ITERS {
+                    f(&shared);
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+}
+
+#[bench]
+fn mutex_new_drop(b: &mut Bencher) {
+    b.iter(|| Mutex::new(0u32));
+}
+
+#[bench]
+fn mutex_uncontended(b: &mut Bencher) {
+    let m = Mutex::new(0u32);
+    b.iter(|| *m.lock().unwrap() += 1);
+}
+
+#[bench]
+fn mutex_contended(b: &mut Bencher) {
+    let m = Arc::new(Mutex::new(0u32));
+    b.iter(|| contended(m.clone(), |m| *m.lock().unwrap() += 1));
+}
+
+#[bench]
+fn rwlock_read_contended(b: &mut Bencher) {
+    let l = Arc::new(RwLock::new(0u32));
+    b.iter(|| {
+        contended(l.clone(), |l| {
+            test::black_box(*l.read().unwrap());
+        })
+    });
+}
+
+#[bench]
+fn rwlock_write_contended(b: &mut Bencher) {
+    let l = Arc::new(RwLock::new(0u32));
+    b.iter(|| contended(l.clone(), |l| *l.write().unwrap() += 1));
+}
+
+#[bench]
+fn rwlock_mostly_read_contended(b: &mut Bencher) {
+    // One in eight accesses is a write.
+    let l = Arc::new((RwLock::new(0u32), AtomicUsize::new(0)));
+    b.iter(|| {
+        contended(l.clone(), |(l, n)| {
+            if n.fetch_add(1, Relaxed) % 8 == 0 {
+                *l.write().unwrap() += 1;
+            } else {
+                test::black_box(*l.read().unwrap());
+            }
+        })
+    });
+}
+
+#[bench]
+fn condvar_ping_pong(b: &mut Bencher) {
+    let pair = Arc::new((Mutex::new(false), Condvar::new()));
+    b.iter(|| {
+        let pair2 = pair.clone();
+        let t = thread::spawn(move || {
+            let (lock, cvar) = &*pair2;
+            for _ in 0..ITERS {
+                let mut ready = lock.lock().unwrap();
+                while !*ready {
+                    ready = cvar.wait(ready).unwrap();
+                }
+                *ready = false;
+                cvar.notify_one();
+            }
+        });
+        let (lock, cvar) = &*pair;
+        for _ in 0..ITERS {
+            let mut ready = lock.lock().unwrap();
+            while *ready {
+                ready = cvar.wait(ready).unwrap();
+            }
+            *ready = true;
+            cvar.notify_one();
+        }
+        t.join().unwrap();
+    });
+}
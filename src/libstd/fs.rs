@@ -9,13 +9,15 @@
 
 #![stable(feature = "rust1", since = "1.0.0")]
 
-use crate::ffi::OsString;
+use crate::cmp;
+use crate::ffi::{OsStr, OsString};
 use crate::fmt;
-use crate::io::{self, Initializer, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write};
+use crate::io::{self, Initializer, IoSlice, IoSliceMut, Read, ReadBuf, Seek, SeekFrom, Write};
 use crate::path::{Path, PathBuf};
 use crate::sys::fs as fs_imp;
 use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
 use crate::time::SystemTime;
+use crate::vec;
 
 /// A reference to an open file on the filesystem.
 ///
@@ -624,6 +626,201 @@ impl File {
     pub fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
         self.inner.set_permissions(perm.0)
     }
+
+    /// Acquires an exclusive advisory lock on the file, blocking until it can
+    /// be acquired.
+    ///
+    /// While the lock is held, no other file handle can acquire a shared or
+    /// an exclusive lock on the same file. The lock is released when
+    /// [`unlock`] is called or when this handle (and all handles created from
+    /// it with [`try_clone`]) is closed.
+    ///
+    /// The locks are *advisory*: they don't prevent reading or writing the
+    /// file without holding a lock, they only exclude other callers of these
+    /// locking methods.
+    ///
+    /// If this file handle already holds a lock, the exact behavior is
+    /// unspecified and platform dependent, including the possibility that it
+    /// will deadlock. Call [`unlock`] before acquiring another lock.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_EX` flag, and the `LockFileEx` function on Windows with
+    /// the `LOCKFILE_EXCLUSIVE_LOCK` flag. Unix platforms without `flock` use
+    /// `fcntl` with `F_SETLKW` instead, which locks the file for the whole
+    /// process rather than only for this handle, and requires the file to be
+    /// opened for writing. Note that, this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.txt")?;
+    ///     f.lock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`unlock`]: #method.unlock
+    /// [`try_clone`]: #method.try_clone
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn lock(&self) -> io::Result<()> {
+        self.inner.lock()
+    }
+
+    /// Acquires a shared advisory lock on the file, blocking until it can be
+    /// acquired.
+    ///
+    /// Any number of file handles can hold a shared lock on the same file at
+    /// the same time, but no handle can acquire an exclusive lock while a
+    /// shared lock is held. See [`lock`] for how and when the lock is
+    /// released.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_SH` flag, and the `LockFileEx` function on Windows.
+    /// Unix platforms without `flock` use `fcntl` with `F_SETLKW` instead,
+    /// which requires the file to be opened for reading. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::open("foo.txt")?;
+    ///     f.lock_shared()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`lock`]: #method.lock
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.inner.lock_shared()
+    }
+
+    /// Tries to acquire an exclusive advisory lock on the file, without
+    /// blocking.
+    ///
+    /// This behaves like [`lock`], except that it returns an error of kind
+    /// [`ErrorKind::Locked`] immediately if another handle already holds a
+    /// lock on the file.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_EX` and `LOCK_NB` flags, and the `LockFileEx` function
+    /// on Windows with the `LOCKFILE_EXCLUSIVE_LOCK` and
+    /// `LOCKFILE_FAIL_IMMEDIATELY` flags. Unix platforms without `flock` use
+    /// `fcntl` with `F_SETLK` instead. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    /// use std::io::ErrorKind;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.txt")?;
+    ///     match f.try_lock() {
+    ///         Ok(()) => println!("got the lock"),
+    ///         Err(ref e) if e.kind() == ErrorKind::Locked => println!("file is locked"),
+    ///         Err(e) => return Err(e),
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`lock`]: #method.lock
+    /// [`ErrorKind::Locked`]: ../io/enum.ErrorKind.html#variant.Locked
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn try_lock(&self) -> io::Result<()> {
+        self.inner.try_lock()
+    }
+
+    /// Tries to acquire a shared advisory lock on the file, without blocking.
+    ///
+    /// This behaves like [`lock_shared`], except that it returns an error of
+    /// kind [`ErrorKind::Locked`] immediately if another handle already holds
+    /// an exclusive lock on the file.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_SH` and `LOCK_NB` flags, and the `LockFileEx` function
+    /// on Windows with the `LOCKFILE_FAIL_IMMEDIATELY` flag. Unix platforms
+    /// without `flock` use `fcntl` with `F_SETLK` instead. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::open("foo.txt")?;
+    ///     f.try_lock_shared()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`lock_shared`]: #method.lock_shared
+    /// [`ErrorKind::Locked`]: ../io/enum.ErrorKind.html#variant.Locked
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        self.inner.try_lock_shared()
+    }
+
+    /// Releases any lock held by this file handle.
+    ///
+    /// Unlocking a file that isn't locked is not an error.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_UN` flag, and the `UnlockFile` function on Windows.
+    /// Unix platforms without `flock` use `fcntl` with `F_UNLCK` instead.
+    /// Note that, this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.txt")?;
+    ///     f.lock()?;
+    ///     // ... update the file ...
+    ///     f.unlock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.inner.unlock()
+    }
 }
 
 impl AsInner<fs_imp::File> for File {
@@ -655,6 +852,10 @@ impl Read for File {
         self.inner.read(buf)
     }
 
+    fn read_buf(&mut self, buf: &mut ReadBuf<'_>) -> io::Result<()> {
+        self.inner.read_buf(buf)
+    }
+
     fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
         self.inner.read_vectored(bufs)
     }
@@ -690,6 +891,10 @@ impl Read for &File {
         self.inner.read(buf)
     }
 
+    fn read_buf(&mut self, buf: &mut ReadBuf<'_>) -> io::Result<()> {
+        self.inner.read_buf(buf)
+    }
+
     fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
         self.inner.read_vectored(bufs)
     }
@@ -1989,11 +2194,17 @@ pub fn remove_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
 ///
 /// # Platform-specific behavior
 ///
-/// This function currently corresponds to `opendir`, `lstat`, `rm` and `rmdir` functions on Unix
-/// and the `FindFirstFile`, `GetFileAttributesEx`, `DeleteFile`, and `RemoveDirectory` functions
-/// on Windows.
+/// This function currently corresponds to `openat`, `fdopendir`, `unlinkat` and `lstat`
+/// functions on Unix (except for macOS, iOS and Redox, where it corresponds to `opendir`,
+/// `lstat`, `rm` and `rmdir`) and the `FindFirstFile`, `GetFileAttributesEx`, `DeleteFile`,
+/// and `RemoveDirectory` functions on Windows.
 /// Note that, this [may change in the future][changes].
 ///
+/// On Unix platforms using `openat`, the directories are traversed through file descriptors
+/// opened with `O_NOFOLLOW`, so a directory in the tree that is concurrently replaced with a
+/// symbolic link can't make this function delete anything outside of the tree. The other
+/// platforms don't protect against such races yet.
+///
 /// [changes]: ../io/index.html#platform-specific-behavior
 ///
 /// # Errors
@@ -2094,6 +2305,468 @@ pub fn read_dir<P: AsRef<Path>>(path: P) -> io::Result<ReadDir> {
     fs_imp::readdir(path.as_ref()).map(ReadDir)
 }
 
+/// Returns an iterator that recursively walks a directory tree.
+///
+/// The iterator first yields an entry for `path` itself, and then, if it is a
+/// directory, an entry for every file and directory below it. The walk is
+/// depth-first: the contents of a directory are yielded right after the
+/// directory itself, before the rest of its siblings. The order of the
+/// entries within one directory is platform and filesystem dependent unless
+/// [`sort_by`] is used.
+///
+/// The returned [`WalkDir`] can be configured before iterating, for example to
+/// follow symbolic links or to limit the depth of the walk, and it allows
+/// skipping the contents of a directory while iterating with
+/// [`skip_current_dir`].
+///
+/// [`WalkDir`]: struct.WalkDir.html
+/// [`sort_by`]: struct.WalkDir.html#method.sort_by
+/// [`skip_current_dir`]: struct.WalkDir.html#method.skip_current_dir
+/// [`max_open`]: struct.WalkDir.html#method.max_open
+///
+/// # Platform-specific behavior
+///
+/// The walk keeps at most one directory open for each level of the tree it
+/// is currently in, and no more than [`max_open`] directories in total. When
+/// entering a directory would exceed that, the remaining entries of the
+/// outermost open directory are read into memory and it is closed. On Linux
+/// and Android, subdirectories of an open directory are opened with `openat`
+/// relative to it, instead of resolving their full path again. Note that,
+/// this [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// The iterator yields an [`io::Error`] for every entry that can't be read,
+/// such as a directory that can't be opened, or a symbolic link that is
+/// followed but points to nothing. Walking continues after an error.
+///
+/// When following symbolic links, a link pointing to one of the directories
+/// it is in would make the walk go on forever. Such a link is detected by
+/// comparing the identity of the directory it points to (the device and inode
+/// number on Unix) with the directories above it, and is yielded as an error
+/// instead of being walked.
+///
+/// [`io::Error`]: ../io/struct.Error.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_walk_dir)]
+/// use std::fs;
+///
+/// fn main() -> std::io::Result<()> {
+///     // Print every `.rs` file below `src`, skipping `target` directories.
+///     let mut walker = fs::walk_dir("src").sort_by(|a, b| a.file_name().cmp(b.file_name()));
+///     while let Some(entry) = walker.next() {
+///         let entry = entry?;
+///         if entry.file_type().is_dir() && entry.file_name() == "target" {
+///             walker.skip_current_dir();
+///         } else if entry.path().extension().map_or(false, |ext| ext == "rs") {
+///             println!("{}", entry.path().display());
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub fn walk_dir<P: AsRef<Path>>(path: P) -> WalkDir {
+    WalkDir {
+        root: Some(path.as_ref().to_path_buf()),
+        follow_links: false,
+        max_depth: usize::max_value(),
+        max_open: 10,
+        sorter: None,
+        stack: Vec::new(),
+    }
+}
+
+/// A recursive iterator over a directory tree.
+///
+/// This struct is created by the [`walk_dir`] function. See its documentation
+/// for more.
+///
+/// [`walk_dir`]: fn.walk_dir.html
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub struct WalkDir {
+    // The path to start at, until its entry has been yielded.
+    root: Option<PathBuf>,
+    follow_links: bool,
+    max_depth: usize,
+    max_open: usize,
+    sorter: Option<Box<dyn FnMut(&WalkDirEntry, &WalkDirEntry) -> cmp::Ordering + Send + Sync>>,
+    // The directories that are being walked, from the root down to the most
+    // recently entered one.
+    stack: Vec<WalkDirList>,
+}
+
+struct WalkDirList {
+    entries: WalkDirEntries,
+    // The depth of the entries in this directory.
+    depth: usize,
+    // Whether the directory is still open, either for reading or because the
+    // entries read from it refer to it.
+    open: bool,
+    // The identity and path of this directory, only recorded when following
+    // symbolic links, which is the only way to end up in a loop.
+    ancestor: Option<(DirId, PathBuf)>,
+}
+
+enum WalkDirEntries {
+    Open(ReadDir),
+    // The entries were read up front, to sort them or to close the directory.
+    Read(vec::IntoIter<io::Result<WalkDirEntry>>),
+    // The directory couldn't be opened.
+    Error(Option<io::Error>),
+}
+
+/// An entry yielded by the [`WalkDir`] iterator.
+///
+/// Unlike a [`DirEntry`], this can also represent the root of the walk, and
+/// keeps track of its depth in the tree and of whether a symbolic link was
+/// followed to reach it.
+///
+/// [`WalkDir`]: struct.WalkDir.html
+/// [`DirEntry`]: struct.DirEntry.html
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+pub struct WalkDirEntry {
+    path: PathBuf,
+    // `None` for the root of the walk, which isn't read from a directory, and
+    // for entries of a directory that was closed early.
+    entry: Option<DirEntry>,
+    file_type: FileType,
+    follow_link: bool,
+    depth: usize,
+}
+
+#[derive(PartialEq, Eq)]
+enum DirId {
+    File(u64, u64),
+    // Used on platforms that can't identify files.
+    Path(PathBuf),
+}
+
+impl DirId {
+    fn of(path: &Path) -> io::Result<DirId> {
+        match metadata(path)?.0.file_id() {
+            Some((dev, ino)) => Ok(DirId::File(dev, ino)),
+            None => canonicalize(path).map(DirId::Path),
+        }
+    }
+}
+
+impl WalkDir {
+    /// Sets whether symbolic links are followed.
+    ///
+    /// When enabled, symbolic links are yielded as the file or directory they
+    /// point to, and links to directories are walked into. Links that point
+    /// back to a directory above them are reported as errors.
+    ///
+    /// The root passed to [`walk_dir`] is always followed if it is a
+    /// symbolic link.
+    ///
+    /// This is disabled by default.
+    ///
+    /// [`walk_dir`]: fn.walk_dir.html
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn follow_links(mut self, follow: bool) -> WalkDir {
+        self.follow_links = follow;
+        self
+    }
+
+    /// Sets the maximum depth of the entries yielded.
+    ///
+    /// The root has depth 0, the entries directly inside of it depth 1, and
+    /// so on. Directories at the maximum depth are yielded, but not opened.
+    ///
+    /// There is no limit by default.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn max_depth(mut self, depth: usize) -> WalkDir {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Sets the maximum number of directories that are kept open at once.
+    ///
+    /// Once a walk is this many directories deep, entering another directory
+    /// first reads the remaining entries of the outermost open one into
+    /// memory and closes it. A lower limit uses fewer file descriptors, at
+    /// the cost of memory and of opening directories by their full path.
+    ///
+    /// The limit is 10 by default. At least one directory has to be open to
+    /// walk it, so a limit of 0 is treated as 1.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn max_open(mut self, max_open: usize) -> WalkDir {
+        self.max_open = cmp::max(max_open, 1);
+        self
+    }
+
+    /// Sets a function to sort the entries of every directory with.
+    ///
+    /// This requires reading all entries of a directory before yielding the
+    /// first one of them. Entries that couldn't be read are yielded after the
+    /// others.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(fs_walk_dir)]
+    /// use std::fs;
+    ///
+    /// for entry in fs::walk_dir("foo").sort_by(|a, b| a.file_name().cmp(b.file_name())) {
+    ///     println!("{}", entry.unwrap().path().display());
+    /// }
+    /// ```
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn sort_by<F>(mut self, cmp: F) -> WalkDir
+    where
+        F: FnMut(&WalkDirEntry, &WalkDirEntry) -> cmp::Ordering + Send + Sync + 'static,
+    {
+        self.sorter = Some(Box::new(cmp));
+        self
+    }
+
+    /// Skips the rest of the directory that is currently being walked.
+    ///
+    /// If the most recently yielded entry is a directory that is walked into,
+    /// its contents are skipped. Otherwise, the remaining entries of the
+    /// directory containing it are skipped, including when it is a directory
+    /// that isn't walked into, for example because it is at the maximum depth.
+    /// Either way, the walk continues with the next entry of the parent of
+    /// the skipped directory.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn skip_current_dir(&mut self) {
+        self.stack.pop();
+    }
+
+    // Enters `entry` if it is a directory that should be walked into, and
+    // yields it unless it turns out to be a symbolic link loop.
+    fn push(&mut self, entry: WalkDirEntry) -> io::Result<WalkDirEntry> {
+        if !entry.file_type.is_dir() || entry.depth >= self.max_depth {
+            return Ok(entry);
+        }
+
+        let ancestor = if self.follow_links {
+            let id = DirId::of(&entry.path)?;
+            for list in &self.stack {
+                if let Some((ref ancestor_id, ref ancestor_path)) = list.ancestor {
+                    if *ancestor_id == id {
+                        return Err(io::Error::new(
+                            io::ErrorKind::Other,
+                            format!(
+                                "filesystem loop: {} points to its ancestor {}",
+                                entry.path.display(),
+                                ancestor_path.display()
+                            ),
+                        ));
+                    }
+                }
+            }
+            Some((id, entry.path.clone()))
+        } else {
+            None
+        };
+
+        let depth = entry.depth + 1;
+        let dir = match entry.entry {
+            Some(ref dir_entry) => {
+                fs_imp::readdir_child(&dir_entry.0, entry.follow_link).map(ReadDir)
+            }
+            None => read_dir(&entry.path),
+        };
+        let follow_links = self.follow_links;
+        let entries = match (dir, &mut self.sorter) {
+            (Ok(dir), Some(sorter)) => {
+                let mut entries: Vec<_> = dir
+                    .map(|e| e.and_then(|e| WalkDirEntry::new(e, depth, follow_links)))
+                    .collect();
+                entries.sort_by(|a, b| match (a, b) {
+                    (Ok(a), Ok(b)) => sorter(a, b),
+                    (Ok(_), Err(_)) => cmp::Ordering::Less,
+                    (Err(_), Ok(_)) => cmp::Ordering::Greater,
+                    (Err(_), Err(_)) => cmp::Ordering::Equal,
+                });
+                WalkDirEntries::Read(entries.into_iter())
+            }
+            (Ok(dir), None) => WalkDirEntries::Open(dir),
+            (Err(e), _) => WalkDirEntries::Error(Some(e)),
+        };
+        let open = !matches!(entries, WalkDirEntries::Error(_));
+        self.stack.push(WalkDirList { entries, depth, open, ancestor });
+
+        if self.stack.iter().filter(|list| list.open).count() > self.max_open {
+            if let Some(oldest) = self.stack.iter_mut().find(|list| list.open) {
+                oldest.close(follow_links);
+            }
+        }
+        Ok(entry)
+    }
+}
+
+impl WalkDirList {
+    // Reads the remaining entries of this directory and closes it. Its
+    // subdirectories are then opened by their full path.
+    fn close(&mut self, follow_links: bool) {
+        let depth = self.depth;
+        let entries: Vec<_> = match self.entries {
+            WalkDirEntries::Open(ref mut dir) => dir
+                .map(|e| e.and_then(|e| WalkDirEntry::new(e, depth, follow_links)))
+                .map(|e| e.map(WalkDirEntry::detach))
+                .collect(),
+            WalkDirEntries::Read(ref mut entries) => {
+                entries.map(|e| e.map(WalkDirEntry::detach)).collect()
+            }
+            WalkDirEntries::Error(_) => return,
+        };
+        self.entries = WalkDirEntries::Read(entries.into_iter());
+        self.open = false;
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl Iterator for WalkDir {
+    type Item = io::Result<WalkDirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<WalkDirEntry>> {
+        if let Some(root) = self.root.take() {
+            return Some(WalkDirEntry::root(root).and_then(|entry| self.push(entry)));
+        }
+        loop {
+            let list = self.stack.last_mut()?;
+            let next = match list.entries {
+                WalkDirEntries::Open(ref mut dir) => dir
+                    .next()
+                    .map(|e| e.and_then(|e| WalkDirEntry::new(e, list.depth, self.follow_links))),
+                WalkDirEntries::Read(ref mut entries) => entries.next(),
+                WalkDirEntries::Error(ref mut err) => err.take().map(Err),
+            };
+            match next {
+                Some(Ok(entry)) => return Some(self.push(entry)),
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    // Done with this directory, which also closes it.
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl fmt::Debug for WalkDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WalkDir")
+            .field("root", &self.root)
+            .field("follow_links", &self.follow_links)
+            .field("max_depth", &self.max_depth)
+            .field("max_open", &self.max_open)
+            .field("depth", &self.stack.len())
+            .finish()
+    }
+}
+
+impl WalkDirEntry {
+    fn root(path: PathBuf) -> io::Result<WalkDirEntry> {
+        let file_type = symlink_metadata(&path)?.file_type();
+        let (file_type, follow_link) = if file_type.is_symlink() {
+            (metadata(&path)?.file_type(), true)
+        } else {
+            (file_type, false)
+        };
+        Ok(WalkDirEntry { path, entry: None, file_type, follow_link, depth: 0 })
+    }
+
+    fn new(entry: DirEntry, depth: usize, follow_links: bool) -> io::Result<WalkDirEntry> {
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        let (file_type, follow_link) = if follow_links && file_type.is_symlink() {
+            (metadata(&path)?.file_type(), true)
+        } else {
+            (file_type, false)
+        };
+        Ok(WalkDirEntry { path, entry: Some(entry), file_type, follow_link, depth })
+    }
+
+    // Drops the `DirEntry`, which keeps the directory it was read from open.
+    fn detach(self) -> WalkDirEntry {
+        WalkDirEntry { entry: None, ..self }
+    }
+
+    /// Returns the full path to the file that this entry represents.
+    ///
+    /// This is the path passed to [`walk_dir`], joined with the names of the
+    /// directories leading to this entry and its own name.
+    ///
+    /// [`walk_dir`]: fn.walk_dir.html
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Consumes this entry, returning its full path.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn into_path(self) -> PathBuf {
+        self.path
+    }
+
+    /// Returns the name of the file that this entry represents.
+    ///
+    /// For the root of the walk, this is the last component of its path, or
+    /// the whole path if it doesn't have one (such as for `..`).
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn file_name(&self) -> &OsStr {
+        self.path.file_name().unwrap_or_else(|| self.path.as_os_str())
+    }
+
+    /// Returns the depth of this entry below the root of the walk.
+    ///
+    /// The root has depth 0.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the file type of this entry.
+    ///
+    /// If this entry is a symbolic link that was followed, this is the file
+    /// type of the file it points to.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// Returns `true` if the path of this entry is a symbolic link, whether
+    /// or not it was followed.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn path_is_symlink(&self) -> bool {
+        self.follow_link || self.file_type.is_symlink()
+    }
+
+    /// Returns the metadata for the file that this entry represents.
+    ///
+    /// If this entry is a symbolic link that was followed, this is the
+    /// metadata of the file it points to, and otherwise that of the entry
+    /// itself.
+    #[unstable(feature = "fs_walk_dir", issue = "none")]
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        if self.follow_link {
+            return metadata(&self.path);
+        }
+        match self.entry {
+            Some(ref entry) => entry.metadata(),
+            None => symlink_metadata(&self.path),
+        }
+    }
+}
+
+#[unstable(feature = "fs_walk_dir", issue = "none")]
+impl fmt::Debug for WalkDirEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WalkDirEntry").field(&self.path).finish()
+    }
+}
+
 /// Changes the permissions found on a file or a directory.
 ///
 /// # Platform-specific behavior
@@ -2781,6 +3454,166 @@ mod tests {
         check!(fs::create_dir_all(Path::new("")));
     }
 
+    fn walk_dir_sorted(walker: fs::WalkDir) -> Vec<(String, usize)> {
+        walker
+            .sort_by(|a, b| a.file_name().cmp(b.file_name()))
+            .map(|entry| {
+                let entry = check!(entry);
+                (entry.file_name().to_str().unwrap().to_string(), entry.depth())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn walk_dir_basic() {
+        let tmpdir = tmpdir();
+        let root = tmpdir.join("root");
+        check!(fs::create_dir_all(root.join("b").join("c")));
+        check!(File::create(root.join("a.txt")));
+        check!(File::create(root.join("b").join("d.txt")));
+        check!(File::create(root.join("b").join("c").join("e.txt")));
+
+        let entries = walk_dir_sorted(fs::walk_dir(&root));
+        let expected = [("root", 0), ("a.txt", 1), ("b", 1), ("c", 2), ("e.txt", 3), ("d.txt", 2)];
+        let expected: Vec<_> = expected.iter().map(|&(n, d)| (n.to_string(), d)).collect();
+        assert_eq!(entries, expected);
+
+        let entries = walk_dir_sorted(fs::walk_dir(&root).max_depth(1));
+        let expected = [("root", 0), ("a.txt", 1), ("b", 1)];
+        let expected: Vec<_> = expected.iter().map(|&(n, d)| (n.to_string(), d)).collect();
+        assert_eq!(entries, expected);
+
+        let mut paths = vec![];
+        for entry in fs::walk_dir(&root) {
+            let entry = check!(entry);
+            assert!(entry.path().starts_with(&root));
+            assert_eq!(check!(entry.metadata()).is_dir(), entry.file_type().is_dir());
+            paths.push(entry.into_path());
+        }
+        paths.sort();
+        assert_eq!(paths.len(), 6);
+        assert_eq!(paths[0], root);
+
+        // A file as the root is yielded on its own.
+        let entries = walk_dir_sorted(fs::walk_dir(root.join("a.txt")));
+        assert_eq!(entries, vec![("a.txt".to_string(), 0)]);
+
+        assert!(fs::walk_dir(tmpdir.join("nonexistent")).next().unwrap().is_err());
+    }
+
+    #[test]
+    fn walk_dir_skip_current_dir() {
+        let tmpdir = tmpdir();
+        let root = tmpdir.join("root");
+        check!(fs::create_dir_all(root.join("a").join("skipped")));
+        check!(fs::create_dir_all(root.join("b")));
+        check!(File::create(root.join("a").join("1.txt")));
+        check!(File::create(root.join("a").join("2.txt")));
+        check!(File::create(root.join("b").join("3.txt")));
+
+        // Skipping a directory right after it was yielded skips its contents.
+        let mut walker = fs::walk_dir(&root).sort_by(|a, b| a.file_name().cmp(b.file_name()));
+        let mut names = vec![];
+        while let Some(entry) = walker.next() {
+            let entry = check!(entry);
+            if entry.file_name() == "a" {
+                walker.skip_current_dir();
+            }
+            names.push(entry.file_name().to_str().unwrap().to_string());
+        }
+        assert_eq!(names, ["root", "a", "b", "3.txt"]);
+
+        // Skipping after a file skips the rest of the directory containing it.
+        let mut walker = fs::walk_dir(&root).sort_by(|a, b| a.file_name().cmp(b.file_name()));
+        let mut names = vec![];
+        while let Some(entry) = walker.next() {
+            let entry = check!(entry);
+            if entry.file_name() == "1.txt" {
+                walker.skip_current_dir();
+            }
+            names.push(entry.file_name().to_str().unwrap().to_string());
+        }
+        assert_eq!(names, ["root", "a", "1.txt", "b", "3.txt"]);
+
+        // Skipping after a directory at the maximum depth skips its siblings.
+        let mut walker =
+            fs::walk_dir(&root).max_depth(1).sort_by(|a, b| a.file_name().cmp(b.file_name()));
+        let mut names = vec![];
+        while let Some(entry) = walker.next() {
+            let entry = check!(entry);
+            if entry.depth() == 1 {
+                walker.skip_current_dir();
+            }
+            names.push(entry.file_name().to_str().unwrap().to_string());
+        }
+        assert_eq!(names, ["root", "a"]);
+    }
+
+    #[test]
+    fn walk_dir_max_open() {
+        let tmpdir = tmpdir();
+        let root = tmpdir.join("root");
+        let deep = root.join("a").join("b").join("c").join("d");
+        check!(fs::create_dir_all(&deep));
+        for dir in &[root.join("a"), root.join("a").join("b"), deep.clone()] {
+            check!(File::create(dir.join("x.txt")));
+            check!(File::create(dir.join("y.txt")));
+        }
+
+        let expected = walk_dir_sorted(fs::walk_dir(&root));
+        assert_eq!(expected.len(), 11);
+        for max_open in 0..4 {
+            assert_eq!(walk_dir_sorted(fs::walk_dir(&root).max_open(max_open)), expected);
+        }
+
+        // Unsorted directories that were closed early keep all of their entries.
+        let mut names: Vec<_> = fs::walk_dir(&root)
+            .max_open(1)
+            .map(|entry| check!(entry).file_name().to_str().unwrap().to_string())
+            .collect();
+        names.sort();
+        let mut expected: Vec<_> = expected.into_iter().map(|(name, _)| name).collect();
+        expected.sort();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn walk_dir_symlinks() {
+        let tmpdir = tmpdir();
+        if !got_symlink_permission(&tmpdir) {
+            return;
+        };
+        let root = tmpdir.join("root");
+        let target = tmpdir.join("target");
+        check!(fs::create_dir_all(root.join("sub")));
+        check!(fs::create_dir_all(&target));
+        check!(File::create(target.join("file.txt")));
+        check!(symlink_dir(&target, root.join("link")));
+        check!(symlink_dir(&root, root.join("sub").join("loop")));
+
+        // Links aren't followed by default.
+        let entries = walk_dir_sorted(fs::walk_dir(&root));
+        let expected = [("root", 0), ("link", 1), ("sub", 1), ("loop", 2)];
+        let expected: Vec<_> = expected.iter().map(|&(n, d)| (n.to_string(), d)).collect();
+        assert_eq!(entries, expected);
+
+        // When following links, the loop is reported instead of walked.
+        let mut names = vec![];
+        let mut errors = 0;
+        for entry in fs::walk_dir(&root).follow_links(true) {
+            match entry {
+                Ok(entry) => {
+                    assert_eq!(entry.path_is_symlink(), entry.file_name() == "link");
+                    names.push(entry.file_name().to_str().unwrap().to_string());
+                }
+                Err(_) => errors += 1,
+            }
+        }
+        names.sort();
+        assert_eq!(names, ["file.txt", "link", "root", "sub"]);
+        assert_eq!(errors, 1);
+    }
+
     #[test]
     fn recursive_rmdir() {
         let tmpdir = tmpdir();
@@ -2816,6 +3649,58 @@ mod tests {
         assert!(canary.exists());
     }
 
+    #[test]
+    // Only these platforms remove the tree through directory file
+    // descriptors; elsewhere `remove_dir_all` still follows paths.
+    #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "redox"))))]
+    fn recursive_rmdir_toctou() {
+        use crate::sync::atomic::{AtomicBool, Ordering};
+        use crate::sync::Arc;
+        use crate::time::{Duration, Instant};
+
+        // Simulates an attacker racing a (privileged) `remove_dir_all` of a
+        // directory they control. They keep replacing the directory, and a
+        // directory inside of it, with symlinks to a directory they want to
+        // get emptied. Nothing behind those symlinks may ever be deleted.
+        let tmpdir = tmpdir();
+        let victim = tmpdir.join("victim_del");
+        let victim_sub = victim.join("sub");
+        let attack_dest = tmpdir.join("attack_dest");
+        let attack_file = attack_dest.join("attack_file");
+        check!(fs::create_dir(&attack_dest));
+        check!(File::create(&attack_file));
+
+        let done = Arc::new(AtomicBool::new(false));
+        let remover = {
+            let victim = victim.clone();
+            let done = done.clone();
+            thread::spawn(move || {
+                while !done.load(Ordering::Relaxed) {
+                    let _ = fs::remove_dir_all(&victim);
+                }
+            })
+        };
+
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(1) {
+            if !attack_file.exists() {
+                break;
+            }
+            let _ = fs::remove_file(&victim);
+            let _ = fs::create_dir(&victim);
+            let _ = fs::create_dir(&victim_sub);
+            let _ = fs::remove_dir(&victim_sub);
+            let _ = symlink_dir(&attack_dest, &victim_sub);
+            // Only succeeds if the remover emptied the directory in between.
+            let _ = fs::remove_dir(&victim);
+            let _ = symlink_dir(&attack_dest, &victim);
+        }
+
+        done.store(true, Ordering::Relaxed);
+        remover.join().unwrap();
+        assert!(attack_file.exists(), "remove_dir_all deleted a file outside of the tree");
+    }
+
     #[test]
     // only Windows makes a distinction between file and directory symlinks.
     #[cfg(windows)]
@@ -3380,6 +4265,64 @@ mod tests {
         check!(f1.write_all(b"!"));
     }
 
+    // Platforms that fall back to `fcntl` locks only exclude other processes,
+    // so two handles in the same process don't contend there.
+    #[test]
+    #[cfg(any(
+        windows,
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    fn file_lock_multiple_handles() {
+        let tmpdir = tmpdir();
+        let filename = &tmpdir.join("file_lock_multiple_handles.txt");
+        let f1 = check!(File::create(filename));
+        let f2 = check!(OpenOptions::new().write(true).open(filename));
+
+        // Shared locks don't exclude each other.
+        check!(f1.lock_shared());
+        check!(f2.lock_shared());
+        check!(f1.unlock());
+        check!(f2.unlock());
+
+        // An exclusive lock excludes both kinds of locks on other handles.
+        check!(f1.lock());
+        assert_eq!(f2.try_lock().unwrap_err().kind(), ErrorKind::Locked);
+        assert_eq!(f2.try_lock_shared().unwrap_err().kind(), ErrorKind::Locked);
+        check!(f1.unlock());
+        check!(f2.try_lock());
+        check!(f2.unlock());
+
+        // A shared lock excludes exclusive locks on other handles.
+        check!(f1.lock_shared());
+        assert_eq!(f2.try_lock().unwrap_err().kind(), ErrorKind::Locked);
+        check!(f2.try_lock_shared());
+        check!(f2.unlock());
+        check!(f1.unlock());
+
+        // Closing the handle releases its lock.
+        check!(f1.lock());
+        drop(f1);
+        check!(f2.try_lock());
+        check!(f2.unlock());
+    }
+
+    #[test]
+    fn file_unlock_unlocked() {
+        let tmpdir = tmpdir();
+        let f = check!(File::create(&tmpdir.join("file_unlock_unlocked.txt")));
+        check!(f.unlock());
+        check!(f.try_lock());
+        check!(f.unlock());
+        check!(f.unlock());
+    }
+
     #[test]
     #[cfg(not(windows))]
     fn unlink_readonly() {
@@ -6,7 +6,7 @@ use crate::cmp;
 use crate::error;
 use crate::fmt;
 use crate::io::{
-    self, Error, ErrorKind, Initializer, IoSlice, IoSliceMut, SeekFrom, DEFAULT_BUF_SIZE,
+    self, Error, ErrorKind, Initializer, IoSlice, IoSliceMut, ReadBuf, SeekFrom, DEFAULT_BUF_SIZE,
 };
 use crate::memchr;
 
@@ -278,6 +278,24 @@ impl<R: Read> Read for BufReader<R> {
         Ok(nread)
     }
 
+    fn read_buf(&mut self, buf: &mut ReadBuf<'_>) -> io::Result<()> {
+        // If we don't have any buffered data and we're doing a massive read
+        // (larger than our internal buffer), bypass our internal buffer
+        // entirely.
+        if self.pos == self.cap && buf.remaining() >= self.buf.len() {
+            self.discard_buffer();
+            return self.inner.read_buf(buf);
+        }
+
+        let prev = buf.filled_len();
+
+        let mut rem = self.fill_buf()?;
+        rem.read_buf(buf)?;
+
+        self.consume(buf.filled_len() - prev); // slice impl of read_buf known to never unfill buf
+        Ok(())
+    }
+
     fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
         let total_len = bufs.iter().map(|b| b.len()).sum::<usize>();
         if self.pos == self.cap && total_len >= self.buf.len() {
@@ -1123,7 +1141,8 @@ where
 #[cfg(test)]
 mod tests {
     use crate::io::prelude::*;
-    use crate::io::{self, BufReader, BufWriter, IoSlice, LineWriter, SeekFrom};
+    use crate::io::{self, BufReader, BufWriter, IoSlice, LineWriter, ReadBuf, SeekFrom};
+    use crate::mem::MaybeUninit;
     use crate::sync::atomic::{AtomicUsize, Ordering};
     use crate::thread;
 
@@ -1175,6 +1194,48 @@ mod tests {
         assert_eq!(reader.read(&mut buf).unwrap(), 0);
     }
 
+    #[test]
+    fn test_buffered_reader_read_buf() {
+        let inner: &[u8] = &[5, 6, 7, 0, 1, 2, 3, 4];
+        let mut reader = BufReader::with_capacity(3, inner);
+
+        let mut buf = [MaybeUninit::uninit(); 3];
+        let mut buf = ReadBuf::uninit(&mut buf);
+
+        reader.read_buf(&mut buf).unwrap();
+
+        assert_eq!(buf.filled(), [5, 6, 7]);
+        assert_eq!(reader.buffer(), []);
+
+        let mut buf = [MaybeUninit::uninit(); 2];
+        let mut buf = ReadBuf::uninit(&mut buf);
+
+        reader.read_buf(&mut buf).unwrap();
+
+        assert_eq!(buf.filled(), [0, 1]);
+        assert_eq!(reader.buffer(), [2]);
+
+        let mut buf = [MaybeUninit::uninit(); 1];
+        let mut buf = ReadBuf::uninit(&mut buf);
+
+        reader.read_buf(&mut buf).unwrap();
+
+        assert_eq!(buf.filled(), [2]);
+        assert_eq!(reader.buffer(), []);
+
+        let mut buf = [MaybeUninit::uninit(); 3];
+        let mut buf = ReadBuf::uninit(&mut buf);
+
+        reader.read_buf(&mut buf).unwrap();
+
+        assert_eq!(buf.filled(), [3, 4]);
+        assert_eq!(reader.buffer(), []);
+
+        reader.read_buf(&mut buf).unwrap();
+
+        assert_eq!(buf.filled_len(), 2);
+    }
+
     #[test]
     fn test_buffered_reader_seek() {
         let inner: &[u8] = &[5, 6, 7, 0, 1, 2, 3, 4];
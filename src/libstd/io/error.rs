@@ -171,6 +171,17 @@ pub enum ErrorKind {
     /// read.
     #[stable(feature = "read_exact", since = "1.6.0")]
     UnexpectedEof,
+
+    /// A lock on the resource is held by someone else, and the operation was
+    /// requested not to wait for it to be released.
+    ///
+    /// For example, [`File::try_lock`] returns this error if another file
+    /// handle, often one in a different process, holds a lock on the same
+    /// file.
+    ///
+    /// [`File::try_lock`]: ../../std/fs/struct.File.html#method.try_lock
+    #[unstable(feature = "file_lock", issue = "none")]
+    Locked,
 }
 
 impl ErrorKind {
@@ -194,6 +205,7 @@ impl ErrorKind {
             ErrorKind::Interrupted => "operation interrupted",
             ErrorKind::Other => "other os error",
             ErrorKind::UnexpectedEof => "unexpected end of file",
+            ErrorKind::Locked => "resource locked",
         }
     }
 }
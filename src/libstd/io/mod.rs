@@ -261,7 +261,7 @@
 
 use crate::cmp;
 use crate::fmt;
-use crate::mem;
+use crate::mem::{self, MaybeUninit};
 use crate::memchr;
 use crate::ops::{Deref, DerefMut};
 use crate::ptr;
@@ -277,6 +277,8 @@ pub use self::buffered::{BufReader, BufWriter, LineWriter};
 pub use self::cursor::Cursor;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::error::{Error, ErrorKind, Result};
+#[unstable(feature = "read_buf", issue = "none")]
+pub use self::readbuf::ReadBuf;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::stdio::{stderr, stdin, stdout, Stderr, Stdin, Stdout};
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -289,12 +291,15 @@ pub use self::stdio::{set_panic, set_print};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::util::{copy, empty, repeat, sink, Empty, Repeat, Sink};
 
+pub(crate) use self::util::generic_copy;
+
 mod buffered;
 mod cursor;
 mod error;
 mod impls;
 mod lazy;
 pub mod prelude;
+mod readbuf;
 mod stdio;
 mod util;
 
@@ -356,8 +361,10 @@ where
 // time is 4,500 times (!) slower than a default reservation size of 32 if the
 // reader has a very small amount of data to return.
 //
-// Because we're extending the buffer with uninitialized data for trusted
-// readers, we need to make sure to truncate that if any of this panics.
+// The spare capacity of the vector is handed to the reader as a `ReadBuf`, so
+// memory is zeroed at most once (and not at all for readers implementing
+// `read_buf` or returning a nop `Initializer`), and the length only ever grows
+// over bytes that were filled.
 fn read_to_end<R: Read + ?Sized>(r: &mut R, buf: &mut Vec<u8>) -> Result<usize> {
     read_to_end_with_reservation(r, buf, |_| 32)
 }
@@ -372,41 +379,46 @@ where
     F: FnMut(&R) -> usize,
 {
     let start_len = buf.len();
-    let mut g = Guard { len: buf.len(), buf };
-    let ret;
+    // Bytes past `buf.len()` that were initialized, but not filled, by the
+    // previous call to `read_buf`, so that they are not zeroed again.
+    let mut initialized = 0;
     loop {
-        if g.len == g.buf.len() {
-            unsafe {
-                // FIXME(danielhenrymantilla): #42788
-                //
-                //   - This creates a (mut) reference to a slice of
-                //     _uninitialized_ integers, which is **undefined behavior**
-                //
-                //   - Only the standard library gets to soundly "ignore" this,
-                //     based on its privileged knowledge of unstable rustc
-                //     internals;
-                g.buf.reserve(reservation_size(r));
-                let capacity = g.buf.capacity();
-                g.buf.set_len(capacity);
-                r.initializer().initialize(&mut g.buf[g.len..]);
-            }
+        if buf.len() == buf.capacity() {
+            buf.reserve(reservation_size(r));
         }
 
-        match r.read(&mut g.buf[g.len..]) {
-            Ok(0) => {
-                ret = Ok(g.len - start_len);
-                break;
-            }
-            Ok(n) => g.len += n,
-            Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
-            Err(e) => {
-                ret = Err(e);
-                break;
-            }
+        let len = buf.len();
+        let spare = buf.capacity() - len;
+        // SAFETY: the spare capacity lies within the allocation and is only
+        // ever handed out as `MaybeUninit<u8>`.
+        let mut read_buf = ReadBuf::uninit(unsafe {
+            slice::from_raw_parts_mut(buf.as_mut_ptr().add(len) as *mut MaybeUninit<u8>, spare)
+        });
+
+        // SAFETY: these bytes were initialized but not filled in the previous loop
+        unsafe {
+            read_buf.assume_init(initialized);
+        }
+
+        match r.read_buf(&mut read_buf) {
+            Ok(()) => {}
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+
+        if read_buf.filled_len() == 0 {
+            return Ok(buf.len() - start_len);
         }
-    }
 
-    ret
+        // store how much was initialized but not filled
+        initialized = read_buf.initialized_len() - read_buf.filled_len();
+        let new_len = read_buf.filled_len() + len;
+
+        // SAFETY: ReadBuf's invariants mean this much memory is initialized
+        unsafe {
+            buf.set_len(new_len);
+        }
+    }
 }
 
 pub(crate) fn default_read_vectored<F>(read: F, bufs: &mut [IoSliceMut<'_>]) -> Result<usize>
@@ -417,6 +429,15 @@ where
     read(buf)
 }
 
+pub(crate) fn default_read_buf<F>(read: F, buf: &mut ReadBuf<'_>) -> Result<()>
+where
+    F: FnOnce(&mut [u8]) -> Result<usize>,
+{
+    let n = read(buf.initialize_unfilled())?;
+    buf.add_filled(n);
+    Ok(())
+}
+
 pub(crate) fn default_write_vectored<F>(write: F, bufs: &[IoSlice<'_>]) -> Result<usize>
 where
     F: FnOnce(&[u8]) -> Result<usize>,
@@ -608,6 +629,41 @@ pub trait Read {
         Initializer::zeroing()
     }
 
+    /// Pull some bytes from this source into the specified buffer.
+    ///
+    /// This is equivalent to the [`read`] method, except that it is passed a
+    /// [`ReadBuf`] rather than `[u8]` to allow use with uninitialized buffers.
+    /// The new data will be appended to any existing contents of `buf`.
+    ///
+    /// Reaching the end of the stream is signalled by returning `Ok(())`
+    /// without adding anything to the filled region of `buf`.
+    ///
+    /// The default implementation delegates to `read`, zeroing the unfilled
+    /// part of `buf` first if it has not been initialized yet, unless
+    /// [`initializer`] returns a nop initializer.
+    ///
+    /// [`read`]: #tymethod.read
+    /// [`ReadBuf`]: struct.ReadBuf.html
+    /// [`initializer`]: #method.initializer
+    #[unstable(feature = "read_buf", issue = "none")]
+    fn read_buf(&mut self, buf: &mut ReadBuf<'_>) -> Result<()> {
+        // SAFETY: a nop initializer promises that `read` neither reads from
+        // the buffer nor de-initializes it, and `read` returns the number of
+        // bytes it initialized at the start of it.
+        unsafe {
+            if self.initializer().should_initialize() {
+                default_read_buf(|b| self.read(b), buf)
+            } else {
+                let unfilled = &mut *(buf.unfilled_mut() as *mut [MaybeUninit<u8>] as *mut [u8]);
+                let n = self.read(unfilled)?;
+                // `add_filled` panics if `read` claims more bytes than fit.
+                buf.assume_init(cmp::min(n, buf.remaining()));
+                buf.add_filled(n);
+                Ok(())
+            }
+        }
+    }
+
     /// Read all bytes until EOF in this source, placing them into `buf`.
     ///
     /// All bytes read from this source will be appended to the specified buffer
@@ -2163,6 +2219,22 @@ impl<T: Read, U: Read> Read for Chain<T, U> {
         self.second.read_vectored(bufs)
     }
 
+    fn read_buf(&mut self, buf: &mut ReadBuf<'_>) -> Result<()> {
+        if buf.remaining() == 0 {
+            return Ok(());
+        }
+
+        if !self.done_first {
+            let prev_filled = buf.filled_len();
+            self.first.read_buf(buf)?;
+            if buf.filled_len() != prev_filled {
+                return Ok(());
+            }
+            self.done_first = true;
+        }
+        self.second.read_buf(buf)
+    }
+
     unsafe fn initializer(&self) -> Initializer {
         let initializer = self.first.initializer();
         if initializer.should_initialize() { initializer } else { self.second.initializer() }
@@ -2490,7 +2562,8 @@ mod tests {
     use super::{repeat, Cursor, SeekFrom};
     use crate::cmp::{self, min};
     use crate::io::prelude::*;
-    use crate::io::{self, IoSlice, IoSliceMut};
+    use crate::io::{self, IoSlice, IoSliceMut, ReadBuf};
+    use crate::mem::MaybeUninit;
     use crate::ops::Deref;
 
     #[test]
@@ -2698,6 +2771,52 @@ mod tests {
         assert_eq!("AB", s);
     }
 
+    #[test]
+    fn chain_read_buf() {
+        let mut chain = (&b"AB"[..]).chain(&b"CDE"[..]);
+
+        let mut buf = [MaybeUninit::uninit(); 4];
+        let mut buf = ReadBuf::uninit(&mut buf);
+
+        chain.read_buf(&mut buf).unwrap();
+        assert_eq!(buf.filled(), b"AB");
+        chain.read_buf(&mut buf).unwrap();
+        assert_eq!(buf.filled(), b"ABCD");
+        assert_eq!(buf.initialized_len(), 4);
+
+        buf.clear();
+        chain.read_buf(&mut buf).unwrap();
+        assert_eq!(buf.filled(), b"E");
+        chain.read_buf(&mut buf).unwrap();
+        assert_eq!(buf.filled(), b"E");
+    }
+
+    #[test]
+    fn read_to_end_keeps_spare_initialized_bytes() {
+        // A reader that zeroes the whole unfilled buffer but only reports one
+        // byte as read, so `read_to_end` has to carry the initialized region
+        // over between calls.
+        struct OneByte(u8);
+
+        impl Read for OneByte {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                if self.0 == 0 || buf.is_empty() {
+                    return Ok(0);
+                }
+                for b in buf.iter_mut() {
+                    *b = 0;
+                }
+                buf[0] = self.0;
+                self.0 -= 1;
+                Ok(1)
+            }
+        }
+
+        let mut v = Vec::with_capacity(8);
+        assert_eq!(OneByte(20).read_to_end(&mut v).unwrap(), 20);
+        assert_eq!(v, (1..=20).rev().collect::<Vec<u8>>());
+    }
+
     #[bench]
     #[cfg_attr(target_os = "emscripten", ignore)]
     fn bench_read_to_end(b: &mut test::Bencher) {
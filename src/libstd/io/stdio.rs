@@ -366,6 +366,15 @@ impl Read for Stdin {
     }
 }
 
+impl StdinLock<'_> {
+    /// Gives access to the buffer in front of the raw stdin handle, so that
+    /// `io::copy` can drain it before handing the descriptor to the kernel.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub(crate) fn as_mut_buf(&mut self) -> &mut BufReader<impl Read> {
+        &mut *self.inner
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl Read for StdinLock<'_> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
@@ -39,8 +39,43 @@ use crate::mem::MaybeUninit;
 ///     Ok(())
 /// }
 /// ```
+///
+/// # Platform-specific behavior
+///
+/// On Linux and Android, when both ends are backed by file descriptors
+/// (files, sockets, pipes and child process handles, optionally wrapped in
+/// [`BufReader`], [`BufWriter`] or [`Take`]), this function uses the
+/// `copy_file_range`, `sendfile` or `splice` syscalls to move the data
+/// without copying it through userspace. Any data still held in the
+/// buffers of such wrappers is written out first, and the generic copy
+/// loop is used whenever the kernel rejects these syscalls.
+///
+/// Note that platform-specific behavior [may change in the future][changes].
+///
+/// [`BufReader`]: struct.BufReader.html
+/// [`BufWriter`]: struct.BufWriter.html
+/// [`Take`]: struct.Take.html
+/// [changes]: ../io/index.html#platform-specific-behavior
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn copy<R: ?Sized, W: ?Sized>(reader: &mut R, writer: &mut W) -> io::Result<u64>
+where
+    R: Read,
+    W: Write,
+{
+    cfg_if::cfg_if! {
+        if #[cfg(any(target_os = "linux", target_os = "android"))] {
+            crate::sys::kernel_copy::copy_spec(reader, writer)
+        } else {
+            generic_copy(reader, writer)
+        }
+    }
+}
+
+/// The userspace read-write loop used by [`copy`] when no platform-specific
+/// fast path applies.
+///
+/// [`copy`]: fn.copy.html
+pub(crate) fn generic_copy<R: ?Sized, W: ?Sized>(reader: &mut R, writer: &mut W) -> io::Result<u64>
 where
     R: Read,
     W: Write,
@@ -218,14 +218,12 @@
 // std may use features in a platform-specific way
 #![allow(unused_features)]
 #![cfg_attr(test, feature(print_internals, set_stdio, update_panic_count))]
+#![cfg_attr(all(test, any(target_os = "linux", target_os = "android")), feature(const_locks))]
 #![cfg_attr(
     all(target_vendor = "fortanix", target_env = "sgx"),
     feature(slice_index_methods, coerce_unsized, sgx_platform, ptr_wrapping_offset_from)
 )]
-#![cfg_attr(
-    all(test, target_vendor = "fortanix", target_env = "sgx"),
-    feature(fixed_size_array, maybe_uninit_extra)
-)]
+#![cfg_attr(all(test, target_vendor = "fortanix", target_env = "sgx"), feature(fixed_size_array))]
 // std is implemented with unstable features, many of which are internal
 // compiler details that will never be stable
 // NB: the following list is sorted to minimize merge conflicts.
@@ -278,12 +276,15 @@
 #![feature(linkage)]
 #![feature(llvm_asm)]
 #![feature(log_syntax)]
+#![feature(maybe_uninit_extra)]
 #![feature(maybe_uninit_ref)]
 #![feature(maybe_uninit_slice)]
+#![feature(maybe_uninit_slice_assume_init)]
 #![feature(needs_panic_runtime)]
 #![cfg_attr(not(bootstrap), feature(negative_impls))]
 #![feature(never_type)]
 #![feature(nll)]
+#![feature(once_cell)]
 #![feature(optin_builtin_traits)]
 #![feature(or_patterns)]
 #![feature(panic_info_message)]
@@ -457,6 +458,8 @@ pub mod error;
 pub mod ffi;
 pub mod fs;
 pub mod io;
+#[unstable(feature = "once_cell", issue = "none")]
+pub mod lazy;
 pub mod net;
 pub mod num;
 pub mod os;
@@ -7,6 +7,8 @@ pub struct Condvar {
     identifier: usize,
 }
 
+pub type MovableCondvar = Box<Condvar>;
+
 impl Condvar {
     pub const fn new() -> Condvar {
         Condvar { identifier: 0 }
@@ -2,7 +2,7 @@ use crate::ffi::{CStr, CString, OsString};
 use crate::fmt;
 use crate::hash::{Hash, Hasher};
 use crate::io::{self, Error, ErrorKind};
-use crate::io::{IoSlice, IoSliceMut, SeekFrom};
+use crate::io::{IoSlice, IoSliceMut, ReadBuf, SeekFrom};
 use crate::path::{Path, PathBuf};
 use crate::sys::cvt;
 use crate::sys::hermit::abi;
@@ -13,6 +13,7 @@ use crate::sys::{unsupported, Void};
 use crate::sys_common::os_str_bytes::OsStrExt;
 
 pub use crate::sys_common::fs::copy;
+pub use crate::sys_common::fs::readdir_child;
 //pub use crate::sys_common::fs::remove_dir_all;
 
 fn cstr(path: &Path) -> io::Result<CString> {
@@ -61,6 +62,10 @@ impl FileAttr {
         match self.0 {}
     }
 
+    pub fn file_id(&self) -> Option<(u64, u64)> {
+        match self.0 {}
+    }
+
     pub fn modified(&self) -> io::Result<SystemTime> {
         match self.0 {}
     }
@@ -297,6 +302,10 @@ impl File {
         self.0.read(buf)
     }
 
+    pub fn read_buf(&self, buf: &mut ReadBuf<'_>) -> io::Result<()> {
+        crate::io::default_read_buf(|buf| self.read(buf), buf)
+    }
+
     pub fn read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
         crate::io::default_read_vectored(|buf| self.read(buf), bufs)
     }
@@ -325,6 +334,26 @@ impl File {
         Err(Error::from_raw_os_error(22))
     }
 
+    pub fn lock(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn try_lock(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        unsupported()
+    }
+
     pub fn diverge(&self) -> ! {
         loop {}
     }
@@ -2,8 +2,11 @@
 
 #![stable(feature = "rust1", since = "1.0.0")]
 
+use crate::fmt;
 use crate::fs;
 use crate::io;
+use crate::marker::PhantomData;
+use crate::mem::{self, ManuallyDrop};
 use crate::os::raw;
 use crate::sys;
 use crate::sys_common::{AsInner, FromInner, IntoInner};
@@ -62,6 +65,218 @@ pub trait IntoRawFd {
     fn into_raw_fd(self) -> RawFd;
 }
 
+/// A borrowed file descriptor.
+///
+/// This has a lifetime parameter to tie it to the lifetime of something that
+/// owns the file descriptor, such as an [`OwnedFd`].
+///
+/// This uses `repr(transparent)` and has the representation of a host file
+/// descriptor, so it can be used in FFI in places where a file descriptor is
+/// passed as an argument, it is not captured or consumed, and it never has
+/// the value `-1`.
+///
+/// [`OwnedFd`]: struct.OwnedFd.html
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+#[rustc_layout_scalar_valid_range_start(0)]
+// Every platform supported by libstd has a 32-bit `c_int`, so this is `-2` in
+// two's complement, leaving `-1` as the niche.
+#[rustc_layout_scalar_valid_range_end(0xFF_FF_FF_FE)]
+#[unstable(feature = "io_safety", issue = "none")]
+pub struct BorrowedFd<'fd> {
+    fd: RawFd,
+    _phantom: PhantomData<&'fd OwnedFd>,
+}
+
+/// An owned file descriptor.
+///
+/// This closes the file descriptor on drop.
+///
+/// This uses `repr(transparent)` and has the representation of a host file
+/// descriptor, so it can be used in FFI in places where a file descriptor is
+/// passed as a consumed argument or returned as an owned value, and it never
+/// has the value `-1`. In particular, `Option<OwnedFd>` has the same
+/// representation as a `RawFd`, with `None` represented by `-1`.
+#[repr(transparent)]
+#[rustc_layout_scalar_valid_range_start(0)]
+// See the comment on `BorrowedFd` above.
+#[rustc_layout_scalar_valid_range_end(0xFF_FF_FF_FE)]
+#[unstable(feature = "io_safety", issue = "none")]
+pub struct OwnedFd {
+    fd: RawFd,
+}
+
+impl BorrowedFd<'_> {
+    /// Returns a `BorrowedFd` holding the given raw file descriptor.
+    ///
+    /// # Safety
+    ///
+    /// The resource pointed to by `fd` must remain open for the duration of
+    /// the returned `BorrowedFd`, and it must not have the value `-1`.
+    #[inline]
+    #[unstable(feature = "io_safety", issue = "none")]
+    pub unsafe fn borrow_raw_fd(fd: RawFd) -> Self {
+        assert_ne!(fd, u32::max_value() as RawFd);
+        // We just asserted that the value is in the valid range and isn't
+        // `-1` (the all-ones bit pattern).
+        Self { fd, _phantom: PhantomData }
+    }
+}
+
+impl OwnedFd {
+    /// Creates a new `OwnedFd` instance that shares the same underlying file
+    /// description as the existing `OwnedFd` instance.
+    ///
+    /// The new file descriptor has the close-on-exec flag set.
+    #[unstable(feature = "io_safety", issue = "none")]
+    pub fn try_clone(&self) -> io::Result<Self> {
+        // Borrow the descriptor as a `FileDesc` without taking ownership of
+        // it, so that duplicating it goes through the same `F_DUPFD_CLOEXEC`
+        // fallback logic as everything else in std.
+        let fd = ManuallyDrop::new(sys::fd::FileDesc::new(self.fd));
+        let new = fd.duplicate()?;
+        Ok(unsafe { Self::from_raw_fd(new.into_raw()) })
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsRawFd for BorrowedFd<'_> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsRawFd for OwnedFd {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl IntoRawFd for OwnedFd {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.fd;
+        mem::forget(self);
+        fd
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl FromRawFd for OwnedFd {
+    /// Constructs a new instance of `Self` from the given raw file descriptor.
+    ///
+    /// # Safety
+    ///
+    /// The resource pointed to by `fd` must be open and suitable for assuming
+    /// ownership. The resource must not require any cleanup other than `close`.
+    #[inline]
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        assert_ne!(fd, u32::max_value() as RawFd);
+        // We just asserted that the value is in the valid range and isn't
+        // `-1` (the all-ones bit pattern).
+        Self { fd }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl Drop for OwnedFd {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            // Note that errors are ignored when closing a file descriptor. The
+            // reason for this is that if an error occurs we don't actually know
+            // if the file descriptor was closed or not, and if we retried (for
+            // something like EINTR), we might close another valid file
+            // descriptor opened after we closed ours.
+            let _ = libc::close(self.fd);
+        }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl fmt::Debug for BorrowedFd<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BorrowedFd").field("fd", &self.fd).finish()
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl fmt::Debug for OwnedFd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedFd").field("fd", &self.fd).finish()
+    }
+}
+
+/// A trait to borrow the file descriptor from an underlying object.
+///
+/// This is only available on unix platforms and must be imported in order to
+/// call the method. Unlike [`AsRawFd`], the returned [`BorrowedFd`] carries a
+/// lifetime, so the borrow checker makes sure the file descriptor isn't used
+/// after the object owning it has been dropped.
+///
+/// [`AsRawFd`]: trait.AsRawFd.html
+/// [`BorrowedFd`]: struct.BorrowedFd.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(io_safety)]
+/// use std::fs::File;
+/// # use std::io;
+/// use std::os::unix::io::{AsFd, BorrowedFd};
+///
+/// let f = File::open("foo.txt")?;
+///
+/// // Borrow the file descriptor to pass it to a function that can only use
+/// // it for as long as `f` is alive.
+/// let borrowed_fd: BorrowedFd<'_> = f.as_fd();
+/// # Ok::<(), io::Error>(())
+/// ```
+#[unstable(feature = "io_safety", issue = "none")]
+pub trait AsFd {
+    /// Borrows the file descriptor.
+    #[unstable(feature = "io_safety", issue = "none")]
+    fn as_fd(&self) -> BorrowedFd<'_>;
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl<T: AsFd> AsFd for &T {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        T::as_fd(self)
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl<T: AsFd> AsFd for &mut T {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        T::as_fd(self)
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for BorrowedFd<'_> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        *self
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for OwnedFd {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        // `OwnedFd` and `BorrowedFd` have the same validity invariants, and
+        // the `BorrowedFd` is bounded by the lifetime of `&self`.
+        unsafe { BorrowedFd::borrow_raw_fd(self.as_raw_fd()) }
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl AsRawFd for fs::File {
     fn as_raw_fd(&self) -> RawFd {
@@ -81,6 +296,30 @@ impl IntoRawFd for fs::File {
     }
 }
 
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for fs::File {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(self.as_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<fs::File> for OwnedFd {
+    #[inline]
+    fn from(file: fs::File) -> OwnedFd {
+        unsafe { OwnedFd::from_raw_fd(file.into_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<OwnedFd> for fs::File {
+    #[inline]
+    fn from(owned_fd: OwnedFd) -> Self {
+        unsafe { Self::from_raw_fd(owned_fd.into_raw_fd()) }
+    }
+}
+
 #[stable(feature = "asraw_stdio", since = "1.21.0")]
 impl AsRawFd for io::Stdin {
     fn as_raw_fd(&self) -> RawFd {
@@ -122,3 +361,93 @@ impl<'a> AsRawFd for io::StderrLock<'a> {
         libc::STDERR_FILENO
     }
 }
+
+// The standard streams are never closed by std, so their file descriptors can
+// be borrowed for any lifetime.
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for io::Stdin {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(libc::STDIN_FILENO) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for io::Stdout {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(libc::STDOUT_FILENO) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for io::Stderr {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(libc::STDERR_FILENO) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl<'a> AsFd for io::StdinLock<'a> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(libc::STDIN_FILENO) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl<'a> AsFd for io::StdoutLock<'a> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(libc::STDOUT_FILENO) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl<'a> AsFd for io::StderrLock<'a> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(libc::STDERR_FILENO) }
+    }
+}
+
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod tests {
+    use super::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
+    use crate::fs::File;
+    use crate::mem::size_of;
+
+    #[test]
+    fn niche_optimizations() {
+        assert_eq!(size_of::<Option<OwnedFd>>(), size_of::<RawFd>());
+        assert_eq!(size_of::<Option<BorrowedFd<'static>>>(), size_of::<RawFd>());
+        unsafe {
+            assert_eq!(OwnedFd::from_raw_fd(RawFd::max_value()).into_raw_fd(), RawFd::max_value());
+            assert_eq!(OwnedFd::from_raw_fd(0).into_raw_fd(), 0);
+            assert_eq!(
+                BorrowedFd::borrow_raw_fd(RawFd::max_value()).as_raw_fd(),
+                RawFd::max_value()
+            );
+            assert_eq!(BorrowedFd::borrow_raw_fd(0).as_raw_fd(), 0);
+        }
+    }
+
+    #[test]
+    fn owned_fd_round_trip() {
+        let tmpdir = crate::sys_common::io::test::tmpdir();
+        let file = File::create(tmpdir.join("owned_fd_round_trip")).unwrap();
+        let raw = file.as_raw_fd();
+        assert_eq!(file.as_fd().as_raw_fd(), raw);
+
+        let owned = OwnedFd::from(file);
+        assert_eq!(owned.as_raw_fd(), raw);
+
+        let cloned = owned.try_clone().unwrap();
+        assert_ne!(cloned.as_raw_fd(), raw);
+
+        let file = File::from(owned);
+        assert_eq!(file.as_raw_fd(), raw);
+    }
+}
@@ -55,6 +55,9 @@ pub mod prelude {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub use super::fs::{FileTypeExt, MetadataExt, OpenOptionsExt, PermissionsExt};
     #[doc(no_inline)]
+    #[unstable(feature = "io_safety", issue = "none")]
+    pub use super::io::{AsFd, BorrowedFd, OwnedFd};
+    #[doc(no_inline)]
     #[stable(feature = "rust1", since = "1.0.0")]
     pub use super::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
     #[doc(no_inline)]
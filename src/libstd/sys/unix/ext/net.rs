@@ -22,13 +22,21 @@ use crate::io::{self, Initializer, IoSlice, IoSliceMut};
 use crate::mem;
 use crate::net::{self, Shutdown};
 use crate::os::unix::ffi::OsStrExt;
-use crate::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use crate::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
 use crate::path::Path;
 use crate::sys::net::Socket;
 use crate::sys::{self, cvt};
 use crate::sys_common::{self, AsInner, FromInner, IntoInner};
 use crate::time::Duration;
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod ancillary;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[unstable(feature = "unix_socket_ancillary_data", issue = "none")]
+pub use self::ancillary::{
+    AncillaryData, AncillaryError, Messages, ScmCredentials, ScmRights, SocketAncillary, SocketCred,
+};
+
 #[cfg(any(
     target_os = "linux",
     target_os = "android",
@@ -601,6 +609,143 @@ impl UnixStream {
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
         self.0.shutdown(how)
     }
+
+    /// Receives data and ancillary data from socket.
+    ///
+    /// On success, returns the number of bytes read.
+    ///
+    /// If the ancillary buffer is too small for all control messages, the
+    /// ones that did not fit are discarded by the kernel and
+    /// [`SocketAncillary::truncated`] returns `true`.
+    ///
+    /// [`SocketAncillary::truncated`]: struct.SocketAncillary.html#method.truncated
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::{UnixStream, SocketAncillary, AncillaryData};
+    /// use std::io::IoSliceMut;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let socket = UnixStream::connect("/tmp/sock")?;
+    ///     let mut buf1 = [1; 8];
+    ///     let mut buf2 = [2; 16];
+    ///     let mut buf3 = [3; 8];
+    ///     let bufs = &mut [
+    ///         IoSliceMut::new(&mut buf1),
+    ///         IoSliceMut::new(&mut buf2),
+    ///         IoSliceMut::new(&mut buf3),
+    ///     ][..];
+    ///     let mut ancillary_buffer = [0; 128];
+    ///     let mut ancillary = SocketAncillary::new(&mut ancillary_buffer[..]);
+    ///     let size = socket.recv_vectored_with_ancillary(bufs, &mut ancillary)?;
+    ///     println!("received {}", size);
+    ///     for ancillary_result in ancillary.messages() {
+    ///         if let AncillaryData::ScmRights(scm_rights) = ancillary_result.unwrap() {
+    ///             for fd in scm_rights {
+    ///                 println!("receive file descriptor: {}", fd);
+    ///             }
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "none")]
+    pub fn recv_vectored_with_ancillary(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        ancillary: &mut SocketAncillary<'_>,
+    ) -> io::Result<usize> {
+        let (count, _, _) = ancillary::recv_vectored_with_ancillary_from(&self.0, bufs, ancillary)?;
+
+        Ok(count)
+    }
+
+    /// Sends data and ancillary data on the socket.
+    ///
+    /// On success, returns the number of bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::{UnixStream, SocketAncillary};
+    /// use std::io::IoSlice;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let socket = UnixStream::connect("/tmp/sock")?;
+    ///     let buf1 = [1; 8];
+    ///     let buf2 = [2; 16];
+    ///     let buf3 = [3; 8];
+    ///     let bufs = &[
+    ///         IoSlice::new(&buf1),
+    ///         IoSlice::new(&buf2),
+    ///         IoSlice::new(&buf3),
+    ///     ][..];
+    ///     let fds = [0, 1, 2];
+    ///     let mut ancillary_buffer = [0; 128];
+    ///     let mut ancillary = SocketAncillary::new(&mut ancillary_buffer[..]);
+    ///     ancillary.add_fds(&fds[..]);
+    ///     socket.send_vectored_with_ancillary(bufs, &mut ancillary)
+    ///         .expect("send_vectored_with_ancillary function failed");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "none")]
+    pub fn send_vectored_with_ancillary(
+        &self,
+        bufs: &[IoSlice<'_>],
+        ancillary: &mut SocketAncillary<'_>,
+    ) -> io::Result<usize> {
+        ancillary::send_vectored_with_ancillary_to(&self.0, None, bufs, ancillary)
+    }
+
+    /// Moves the socket to pass unix credentials as control message in [`SocketAncillary`].
+    ///
+    /// Set the socket option `SO_PASSCRED`.
+    ///
+    /// [`SocketAncillary`]: struct.SocketAncillary.html
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::UnixStream;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let socket = UnixStream::connect("/tmp/sock")?;
+    ///     socket.set_passcred(true).expect("set_passcred function failed");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "none")]
+    pub fn set_passcred(&self, passcred: bool) -> io::Result<()> {
+        sys_common::net::setsockopt(
+            &self.0,
+            libc::SOL_SOCKET,
+            libc::SO_PASSCRED,
+            passcred as libc::c_int,
+        )
+    }
+
+    /// Get the current value of the socket for passing unix credentials in [`SocketAncillary`].
+    /// This value can be change by [`set_passcred`].
+    ///
+    /// Get the socket option `SO_PASSCRED`.
+    ///
+    /// [`SocketAncillary`]: struct.SocketAncillary.html
+    /// [`set_passcred`]: #method.set_passcred
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "none")]
+    pub fn passcred(&self) -> io::Result<bool> {
+        let passcred: libc::c_int =
+            sys_common::net::getsockopt(&self.0, libc::SOL_SOCKET, libc::SO_PASSCRED)?;
+        Ok(passcred != 0)
+    }
 }
 
 #[stable(feature = "unix_socket", since = "1.10.0")]
@@ -686,6 +831,30 @@ impl IntoRawFd for UnixStream {
     }
 }
 
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for UnixStream {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(self.as_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<UnixStream> for OwnedFd {
+    #[inline]
+    fn from(value: UnixStream) -> OwnedFd {
+        unsafe { OwnedFd::from_raw_fd(value.into_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<OwnedFd> for UnixStream {
+    #[inline]
+    fn from(owned_fd: OwnedFd) -> Self {
+        unsafe { Self::from_raw_fd(owned_fd.into_raw_fd()) }
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl AsRawFd for net::TcpStream {
     fn as_raw_fd(&self) -> RawFd {
@@ -750,6 +919,78 @@ impl IntoRawFd for net::UdpSocket {
     }
 }
 
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for net::TcpStream {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(self.as_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<net::TcpStream> for OwnedFd {
+    #[inline]
+    fn from(value: net::TcpStream) -> OwnedFd {
+        unsafe { OwnedFd::from_raw_fd(value.into_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<OwnedFd> for net::TcpStream {
+    #[inline]
+    fn from(owned_fd: OwnedFd) -> Self {
+        unsafe { Self::from_raw_fd(owned_fd.into_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for net::TcpListener {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(self.as_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<net::TcpListener> for OwnedFd {
+    #[inline]
+    fn from(value: net::TcpListener) -> OwnedFd {
+        unsafe { OwnedFd::from_raw_fd(value.into_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<OwnedFd> for net::TcpListener {
+    #[inline]
+    fn from(owned_fd: OwnedFd) -> Self {
+        unsafe { Self::from_raw_fd(owned_fd.into_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for net::UdpSocket {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(self.as_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<net::UdpSocket> for OwnedFd {
+    #[inline]
+    fn from(value: net::UdpSocket) -> OwnedFd {
+        unsafe { OwnedFd::from_raw_fd(value.into_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<OwnedFd> for net::UdpSocket {
+    #[inline]
+    fn from(owned_fd: OwnedFd) -> Self {
+        unsafe { Self::from_raw_fd(owned_fd.into_raw_fd()) }
+    }
+}
+
 /// A structure representing a Unix domain socket server.
 ///
 /// # Examples
@@ -1011,6 +1252,30 @@ impl IntoRawFd for UnixListener {
     }
 }
 
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for UnixListener {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(self.as_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<UnixListener> for OwnedFd {
+    #[inline]
+    fn from(value: UnixListener) -> OwnedFd {
+        unsafe { OwnedFd::from_raw_fd(value.into_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<OwnedFd> for UnixListener {
+    #[inline]
+    fn from(owned_fd: OwnedFd) -> Self {
+        unsafe { Self::from_raw_fd(owned_fd.into_raw_fd()) }
+    }
+}
+
 #[stable(feature = "unix_socket", since = "1.10.0")]
 impl<'a> IntoIterator for &'a UnixListener {
     type Item = io::Result<UnixStream>;
@@ -1603,6 +1868,238 @@ impl UnixDatagram {
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
         self.0.shutdown(how)
     }
+
+    /// Receives data and ancillary data from socket.
+    ///
+    /// On success, returns the number of bytes read, if the data was truncated and the address
+    /// from whence the msg came.
+    ///
+    /// If the ancillary buffer is too small for all control messages, the
+    /// ones that did not fit are discarded by the kernel and
+    /// [`SocketAncillary::truncated`] returns `true`.
+    ///
+    /// [`SocketAncillary::truncated`]: struct.SocketAncillary.html#method.truncated
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::{UnixDatagram, SocketAncillary, AncillaryData};
+    /// use std::io::IoSliceMut;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let sock = UnixDatagram::unbound()?;
+    ///     let mut buf1 = [1; 8];
+    ///     let mut buf2 = [2; 16];
+    ///     let mut buf3 = [3; 8];
+    ///     let bufs = &mut [
+    ///         IoSliceMut::new(&mut buf1),
+    ///         IoSliceMut::new(&mut buf2),
+    ///         IoSliceMut::new(&mut buf3),
+    ///     ][..];
+    ///     let mut ancillary_buffer = [0; 128];
+    ///     let mut ancillary = SocketAncillary::new(&mut ancillary_buffer[..]);
+    ///     let (size, _truncated, sender) =
+    ///         sock.recv_vectored_with_ancillary_from(bufs, &mut ancillary)?;
+    ///     println!("received {}", size);
+    ///     for ancillary_result in ancillary.messages() {
+    ///         if let AncillaryData::ScmRights(scm_rights) = ancillary_result.unwrap() {
+    ///             for fd in scm_rights {
+    ///                 println!("receive file descriptor: {}", fd);
+    ///             }
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "none")]
+    pub fn recv_vectored_with_ancillary_from(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        ancillary: &mut SocketAncillary<'_>,
+    ) -> io::Result<(usize, bool, SocketAddr)> {
+        let (count, truncated, addr) =
+            ancillary::recv_vectored_with_ancillary_from(&self.0, bufs, ancillary)?;
+        let addr = addr?;
+
+        Ok((count, truncated, addr))
+    }
+
+    /// Receives data and ancillary data from socket.
+    ///
+    /// On success, returns the number of bytes read and if the data was truncated.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::{UnixDatagram, SocketAncillary, AncillaryData};
+    /// use std::io::IoSliceMut;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let sock = UnixDatagram::unbound()?;
+    ///     let mut buf1 = [1; 8];
+    ///     let mut buf2 = [2; 16];
+    ///     let mut buf3 = [3; 8];
+    ///     let bufs = &mut [
+    ///         IoSliceMut::new(&mut buf1),
+    ///         IoSliceMut::new(&mut buf2),
+    ///         IoSliceMut::new(&mut buf3),
+    ///     ][..];
+    ///     let mut ancillary_buffer = [0; 128];
+    ///     let mut ancillary = SocketAncillary::new(&mut ancillary_buffer[..]);
+    ///     let (size, _truncated) = sock.recv_vectored_with_ancillary(bufs, &mut ancillary)?;
+    ///     println!("received {}", size);
+    ///     for ancillary_result in ancillary.messages() {
+    ///         if let AncillaryData::ScmRights(scm_rights) = ancillary_result.unwrap() {
+    ///             for fd in scm_rights {
+    ///                 println!("receive file descriptor: {}", fd);
+    ///             }
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "none")]
+    pub fn recv_vectored_with_ancillary(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        ancillary: &mut SocketAncillary<'_>,
+    ) -> io::Result<(usize, bool)> {
+        let (count, truncated, addr) =
+            ancillary::recv_vectored_with_ancillary_from(&self.0, bufs, ancillary)?;
+        addr?;
+
+        Ok((count, truncated))
+    }
+
+    /// Sends data and ancillary data on the socket to the specified address.
+    ///
+    /// On success, returns the number of bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::{UnixDatagram, SocketAncillary};
+    /// use std::io::IoSlice;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let sock = UnixDatagram::unbound()?;
+    ///     let buf1 = [1; 8];
+    ///     let buf2 = [2; 16];
+    ///     let buf3 = [3; 8];
+    ///     let bufs = &[
+    ///         IoSlice::new(&buf1),
+    ///         IoSlice::new(&buf2),
+    ///         IoSlice::new(&buf3),
+    ///     ][..];
+    ///     let fds = [0, 1, 2];
+    ///     let mut ancillary_buffer = [0; 128];
+    ///     let mut ancillary = SocketAncillary::new(&mut ancillary_buffer[..]);
+    ///     ancillary.add_fds(&fds[..]);
+    ///     sock.send_vectored_with_ancillary_to(bufs, &mut ancillary, "/some/sock")
+    ///         .expect("send_vectored_with_ancillary_to function failed");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "none")]
+    pub fn send_vectored_with_ancillary_to<P: AsRef<Path>>(
+        &self,
+        bufs: &[IoSlice<'_>],
+        ancillary: &mut SocketAncillary<'_>,
+        path: P,
+    ) -> io::Result<usize> {
+        ancillary::send_vectored_with_ancillary_to(&self.0, Some(path.as_ref()), bufs, ancillary)
+    }
+
+    /// Sends data and ancillary data on the socket.
+    ///
+    /// On success, returns the number of bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::{UnixDatagram, SocketAncillary};
+    /// use std::io::IoSlice;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let sock = UnixDatagram::unbound()?;
+    ///     sock.connect("/some/sock")?;
+    ///     let buf1 = [1; 8];
+    ///     let buf2 = [2; 16];
+    ///     let buf3 = [3; 8];
+    ///     let bufs = &[
+    ///         IoSlice::new(&buf1),
+    ///         IoSlice::new(&buf2),
+    ///         IoSlice::new(&buf3),
+    ///     ][..];
+    ///     let fds = [0, 1, 2];
+    ///     let mut ancillary_buffer = [0; 128];
+    ///     let mut ancillary = SocketAncillary::new(&mut ancillary_buffer[..]);
+    ///     ancillary.add_fds(&fds[..]);
+    ///     sock.send_vectored_with_ancillary(bufs, &mut ancillary)
+    ///         .expect("send_vectored_with_ancillary function failed");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "none")]
+    pub fn send_vectored_with_ancillary(
+        &self,
+        bufs: &[IoSlice<'_>],
+        ancillary: &mut SocketAncillary<'_>,
+    ) -> io::Result<usize> {
+        ancillary::send_vectored_with_ancillary_to(&self.0, None, bufs, ancillary)
+    }
+
+    /// Moves the socket to pass unix credentials as control message in [`SocketAncillary`].
+    ///
+    /// Set the socket option `SO_PASSCRED`.
+    ///
+    /// [`SocketAncillary`]: struct.SocketAncillary.html
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::UnixDatagram;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let socket = UnixDatagram::unbound()?;
+    ///     socket.set_passcred(true).expect("set_passcred function failed");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "none")]
+    pub fn set_passcred(&self, passcred: bool) -> io::Result<()> {
+        sys_common::net::setsockopt(
+            &self.0,
+            libc::SOL_SOCKET,
+            libc::SO_PASSCRED,
+            passcred as libc::c_int,
+        )
+    }
+
+    /// Get the current value of the socket for passing unix credentials in [`SocketAncillary`].
+    /// This value can be change by [`set_passcred`].
+    ///
+    /// Get the socket option `SO_PASSCRED`.
+    ///
+    /// [`SocketAncillary`]: struct.SocketAncillary.html
+    /// [`set_passcred`]: #method.set_passcred
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "none")]
+    pub fn passcred(&self) -> io::Result<bool> {
+        let passcred: libc::c_int =
+            sys_common::net::getsockopt(&self.0, libc::SOL_SOCKET, libc::SO_PASSCRED)?;
+        Ok(passcred != 0)
+    }
 }
 
 #[stable(feature = "unix_socket", since = "1.10.0")]
@@ -1626,6 +2123,30 @@ impl IntoRawFd for UnixDatagram {
     }
 }
 
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for UnixDatagram {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(self.as_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<UnixDatagram> for OwnedFd {
+    #[inline]
+    fn from(value: UnixDatagram) -> OwnedFd {
+        unsafe { OwnedFd::from_raw_fd(value.into_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<OwnedFd> for UnixDatagram {
+    #[inline]
+    fn from(owned_fd: OwnedFd) -> Self {
+        unsafe { Self::from_raw_fd(owned_fd.into_raw_fd()) }
+    }
+}
+
 #[cfg(all(test, not(target_os = "emscripten")))]
 mod test {
     use crate::io::prelude::*;
@@ -2003,4 +2524,176 @@ mod test {
     fn abstract_namespace_not_allowed() {
         assert!(UnixStream::connect("\0asdf").is_err());
     }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[test]
+    fn test_send_vectored_fds_unix_stream() {
+        let dir = tmpdir();
+        let path = dir.path().join("file");
+        or_panic!(crate::fs::write(&path, b"hello"));
+        let file = or_panic!(crate::fs::File::open(&path));
+
+        let (s1, s2) = or_panic!(UnixStream::pair());
+
+        let buf1 = [1; 8];
+        let bufs_send = &[IoSlice::new(&buf1[..])][..];
+
+        let mut ancillary1_buffer = [0; 128];
+        let mut ancillary1 = SocketAncillary::new(&mut ancillary1_buffer[..]);
+        assert!(ancillary1.add_fds(&[file.as_raw_fd()][..]));
+
+        let usize = or_panic!(s1.send_vectored_with_ancillary(&bufs_send, &mut ancillary1));
+        assert_eq!(usize, 8);
+
+        let mut buf2 = [0; 8];
+        let mut bufs_recv = &mut [IoSliceMut::new(&mut buf2[..])][..];
+
+        let mut ancillary2_buffer = [0; 128];
+        let mut ancillary2 = SocketAncillary::new(&mut ancillary2_buffer[..]);
+
+        let usize = or_panic!(s2.recv_vectored_with_ancillary(&mut bufs_recv, &mut ancillary2));
+        assert_eq!(usize, 8);
+        assert_eq!(buf1, buf2);
+        assert!(!ancillary2.truncated());
+
+        let mut ancillary_data_vec = ancillary2.messages().collect::<Vec<_>>();
+        assert_eq!(ancillary_data_vec.len(), 1);
+        if let AncillaryData::ScmRights(scm_rights) = ancillary_data_vec.pop().unwrap().unwrap() {
+            let fd_vec = scm_rights.collect::<Vec<_>>();
+            assert_eq!(fd_vec.len(), 1);
+            let mut received = unsafe { crate::fs::File::from_raw_fd(fd_vec[0]) };
+            let mut contents = String::new();
+            or_panic!(received.read_to_string(&mut contents));
+            assert_eq!(contents, "hello");
+        } else {
+            unreachable!("must be ScmRights");
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[test]
+    fn test_send_vectored_fds_misaligned_ancillary_buffer() {
+        #[repr(align(8))]
+        struct Aligned([u8; 129]);
+
+        let (s1, s2) = or_panic!(UnixStream::pair());
+
+        let buf1 = [1; 8];
+        let bufs_send = &[IoSlice::new(&buf1[..])][..];
+
+        let mut ancillary1_buffer = Aligned([0; 129]);
+        let mut ancillary1 = SocketAncillary::new(&mut ancillary1_buffer.0[1..]);
+        assert!(ancillary1.capacity() < 128);
+        assert!(ancillary1.add_fds(&[s1.as_raw_fd()][..]));
+
+        let usize = or_panic!(s1.send_vectored_with_ancillary(&bufs_send, &mut ancillary1));
+        assert_eq!(usize, 8);
+
+        let mut buf2 = [0; 8];
+        let mut bufs_recv = &mut [IoSliceMut::new(&mut buf2[..])][..];
+
+        let mut ancillary2_buffer = Aligned([0; 129]);
+        let mut ancillary2 = SocketAncillary::new(&mut ancillary2_buffer.0[1..]);
+
+        let usize = or_panic!(s2.recv_vectored_with_ancillary(&mut bufs_recv, &mut ancillary2));
+        assert_eq!(usize, 8);
+        assert!(!ancillary2.truncated());
+
+        let mut ancillary_data_vec = ancillary2.messages().collect::<Vec<_>>();
+        assert_eq!(ancillary_data_vec.len(), 1);
+        if let AncillaryData::ScmRights(scm_rights) = ancillary_data_vec.pop().unwrap().unwrap() {
+            let fd_vec = scm_rights.collect::<Vec<_>>();
+            assert_eq!(fd_vec.len(), 1);
+            drop(unsafe { OwnedFd::from_raw_fd(fd_vec[0]) });
+        } else {
+            unreachable!("must be ScmRights");
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[test]
+    fn test_send_vectored_with_ancillary_to_unix_datagram() {
+        let dir = tmpdir();
+        let path1 = dir.path().join("sock1");
+        let path2 = dir.path().join("sock2");
+
+        let bsock1 = or_panic!(UnixDatagram::bind(&path1));
+        let bsock2 = or_panic!(UnixDatagram::bind(&path2));
+
+        or_panic!(bsock2.set_passcred(true));
+        assert!(or_panic!(bsock2.passcred()));
+
+        let buf1 = [1; 8];
+        let bufs_send = &[IoSlice::new(&buf1[..])][..];
+
+        let mut ancillary1_buffer = [0; 128];
+        let mut ancillary1 = SocketAncillary::new(&mut ancillary1_buffer[..]);
+        let mut cred1 = SocketCred::new();
+        cred1.set_pid(unsafe { libc::getpid() });
+        cred1.set_uid(unsafe { libc::getuid() });
+        cred1.set_gid(unsafe { libc::getgid() });
+        assert!(ancillary1.add_creds(&[cred1.clone()][..]));
+
+        let usize =
+            or_panic!(bsock1.send_vectored_with_ancillary_to(&bufs_send, &mut ancillary1, &path2));
+        assert_eq!(usize, 8);
+
+        let mut buf2 = [0; 8];
+        let mut bufs_recv = &mut [IoSliceMut::new(&mut buf2[..])][..];
+
+        let mut ancillary2_buffer = [0; 128];
+        let mut ancillary2 = SocketAncillary::new(&mut ancillary2_buffer[..]);
+
+        let (usize, truncated, addr) =
+            or_panic!(bsock2.recv_vectored_with_ancillary_from(&mut bufs_recv, &mut ancillary2));
+        assert_eq!(ancillary2.truncated(), false);
+        assert_eq!(usize, 8);
+        assert_eq!(truncated, false);
+        assert_eq!(addr.as_pathname().unwrap(), &path1);
+        assert_eq!(buf1, buf2);
+
+        let mut ancillary_data_vec = ancillary2.messages().collect::<Vec<_>>();
+        assert_eq!(ancillary_data_vec.len(), 1);
+        if let AncillaryData::ScmCredentials(scm_credentials) =
+            ancillary_data_vec.pop().unwrap().unwrap()
+        {
+            let cred_vec = scm_credentials.collect::<Vec<_>>();
+            assert_eq!(cred_vec.len(), 1);
+            assert_eq!(cred1.get_pid(), cred_vec[0].get_pid());
+            assert_eq!(cred1.get_uid(), cred_vec[0].get_uid());
+            assert_eq!(cred1.get_gid(), cred_vec[0].get_gid());
+        } else {
+            unreachable!("must be ScmCredentials");
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[test]
+    fn test_recv_vectored_with_ancillary_truncated() {
+        let (s1, s2) = or_panic!(UnixDatagram::pair());
+
+        let buf1 = [1; 8];
+        let bufs_send = &[IoSlice::new(&buf1[..])][..];
+
+        let mut ancillary1_buffer = [0; 128];
+        let mut ancillary1 = SocketAncillary::new(&mut ancillary1_buffer[..]);
+        assert!(ancillary1.add_fds(&[s1.as_raw_fd()][..]));
+
+        let usize = or_panic!(s1.send_vectored_with_ancillary(&bufs_send, &mut ancillary1));
+        assert_eq!(usize, 8);
+
+        let mut buf2 = [0; 4];
+        let mut bufs_recv = &mut [IoSliceMut::new(&mut buf2[..])][..];
+
+        // Too small to hold even one control message header.
+        let mut ancillary2_buffer = [0; 4];
+        let mut ancillary2 = SocketAncillary::new(&mut ancillary2_buffer[..]);
+
+        let (usize, truncated) =
+            or_panic!(s2.recv_vectored_with_ancillary(&mut bufs_recv, &mut ancillary2));
+        assert_eq!(usize, 4);
+        assert!(truncated);
+        assert!(ancillary2.truncated());
+        assert_eq!(ancillary2.messages().count(), 0);
+    }
 }
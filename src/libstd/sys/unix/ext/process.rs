@@ -4,7 +4,7 @@
 
 use crate::ffi::OsStr;
 use crate::io;
-use crate::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use crate::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
 use crate::process;
 use crate::sys;
 use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
@@ -227,6 +227,62 @@ impl IntoRawFd for process::ChildStderr {
     }
 }
 
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<OwnedFd> for process::Stdio {
+    #[inline]
+    fn from(owned_fd: OwnedFd) -> Self {
+        unsafe { Self::from_raw_fd(owned_fd.into_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for process::ChildStdin {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(self.as_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<process::ChildStdin> for OwnedFd {
+    #[inline]
+    fn from(value: process::ChildStdin) -> OwnedFd {
+        unsafe { OwnedFd::from_raw_fd(value.into_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for process::ChildStdout {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(self.as_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<process::ChildStdout> for OwnedFd {
+    #[inline]
+    fn from(value: process::ChildStdout) -> OwnedFd {
+        unsafe { OwnedFd::from_raw_fd(value.into_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl AsFd for process::ChildStderr {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw_fd(self.as_raw_fd()) }
+    }
+}
+
+#[unstable(feature = "io_safety", issue = "none")]
+impl From<process::ChildStderr> for OwnedFd {
+    #[inline]
+    fn from(value: process::ChildStderr) -> OwnedFd {
+        unsafe { OwnedFd::from_raw_fd(value.into_raw_fd()) }
+    }
+}
+
 /// Returns the OS-assigned process identifier associated with this process's parent.
 #[stable(feature = "unix_ppid", since = "1.27.0")]
 pub fn parent_id() -> u32 {
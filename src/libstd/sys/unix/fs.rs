@@ -2,7 +2,7 @@ use crate::os::unix::prelude::*;
 
 use crate::ffi::{CStr, CString, OsStr, OsString};
 use crate::fmt;
-use crate::io::{self, Error, ErrorKind, IoSlice, IoSliceMut, SeekFrom};
+use crate::io::{self, Error, ErrorKind, IoSlice, IoSliceMut, ReadBuf, SeekFrom};
 use crate::mem;
 use crate::path::{Path, PathBuf};
 use crate::ptr;
@@ -48,7 +48,10 @@ use libc::{
     dirent64, fstat64, ftruncate64, lseek64, lstat64, off64_t, open64, readdir64_r, stat64,
 };
 
-pub use crate::sys_common::fs::remove_dir_all;
+pub use remove_dir_impl::remove_dir_all;
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub use crate::sys_common::fs::readdir_child;
 
 pub struct File(FileDesc);
 
@@ -207,7 +210,7 @@ pub struct DirEntry {
         target_os = "fuchsia",
         target_os = "redox"
     ))]
-    name: Box<[u8]>,
+    name: CString,
 }
 
 #[derive(Clone, Debug)]
@@ -264,6 +267,10 @@ impl FileAttr {
     pub fn file_type(&self) -> FileType {
         FileType { mode: self.stat.st_mode as mode_t }
     }
+
+    pub fn file_id(&self) -> Option<(u64, u64)> {
+        Some((self.stat.st_dev as u64, self.stat.st_ino as u64))
+    }
 }
 
 #[cfg(target_os = "netbsd")]
@@ -416,8 +423,6 @@ impl Iterator for ReadDir {
         target_os = "illumos"
     ))]
     fn next(&mut self) -> Option<io::Result<DirEntry>> {
-        use crate::slice;
-
         unsafe {
             loop {
                 // Although readdir_r(3) would be a correct function to use here because
@@ -435,14 +440,9 @@ impl Iterator for ReadDir {
                     };
                 }
 
-                let name = (*entry_ptr).d_name.as_ptr();
-                let namelen = libc::strlen(name) as usize;
-
                 let ret = DirEntry {
                     entry: *entry_ptr,
-                    name: slice::from_raw_parts(name as *const u8, namelen as usize)
-                        .to_owned()
-                        .into_boxed_slice(),
+                    name: CStr::from_ptr((*entry_ptr).d_name.as_ptr()).to_owned(),
                     dir: self.clone(),
                 };
                 if ret.name_bytes() != b"." && ret.name_bytes() != b".." {
@@ -610,7 +610,23 @@ impl DirEntry {
         target_os = "redox"
     ))]
     fn name_bytes(&self) -> &[u8] {
-        &*self.name
+        self.name.as_bytes()
+    }
+
+    #[cfg(not(any(
+        target_os = "solaris",
+        target_os = "illumos",
+        target_os = "fuchsia",
+        target_os = "redox",
+        target_os = "macos",
+        target_os = "ios"
+    )))]
+    fn name_cstr(&self) -> &CStr {
+        unsafe { CStr::from_ptr(self.entry.d_name.as_ptr()) }
+    }
+    #[cfg(any(target_os = "solaris", target_os = "illumos", target_os = "fuchsia"))]
+    fn name_cstr(&self) -> &CStr {
+        &self.name
     }
 }
 
@@ -824,6 +840,10 @@ impl File {
         self.0.read(buf)
     }
 
+    pub fn read_buf(&self, buf: &mut ReadBuf<'_>) -> io::Result<()> {
+        self.0.read_buf(buf)
+    }
+
     pub fn read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
         self.0.read_vectored(bufs)
     }
@@ -876,6 +896,107 @@ impl File {
         cvt_r(|| unsafe { libc::fchmod(self.0.raw(), perm.mode) })?;
         Ok(())
     }
+
+    pub fn lock(&self) -> io::Result<()> {
+        lock_file(self.0.raw(), LockKind::Exclusive, true)
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        lock_file(self.0.raw(), LockKind::Shared, true)
+    }
+
+    pub fn try_lock(&self) -> io::Result<()> {
+        try_lock_file(self.0.raw(), LockKind::Exclusive)
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        try_lock_file(self.0.raw(), LockKind::Shared)
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        lock_file(self.0.raw(), LockKind::Unlock, true)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum LockKind {
+    Shared,
+    Exclusive,
+    Unlock,
+}
+
+// `flock` locks belong to the open file description, so two separately opened
+// handles to the same file exclude each other even within one process, which
+// matches the behavior of `LockFileEx` on Windows.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "fuchsia",
+    target_os = "emscripten"
+))]
+fn lock_file(fd: c_int, kind: LockKind, block: bool) -> io::Result<()> {
+    let operation = match kind {
+        LockKind::Shared => libc::LOCK_SH,
+        LockKind::Exclusive => libc::LOCK_EX,
+        LockKind::Unlock => libc::LOCK_UN,
+    };
+    let operation = if block { operation } else { operation | libc::LOCK_NB };
+    cvt_r(|| unsafe { libc::flock(fd, operation) })?;
+    Ok(())
+}
+
+// Everywhere else, fall back to POSIX record locks covering the whole file.
+// These are owned by the process instead of the file description, so they
+// only exclude other processes, and they're all released as soon as any
+// descriptor for the file is closed by this process.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "fuchsia",
+    target_os = "emscripten"
+)))]
+fn lock_file(fd: c_int, kind: LockKind, block: bool) -> io::Result<()> {
+    let mut lock: libc::flock = unsafe { mem::zeroed() };
+    lock.l_type = match kind {
+        LockKind::Shared => libc::F_RDLCK,
+        LockKind::Exclusive => libc::F_WRLCK,
+        LockKind::Unlock => libc::F_UNLCK,
+    } as _;
+    // A start and length of 0 lock the whole file, however large it grows.
+    lock.l_whence = libc::SEEK_SET as _;
+    lock.l_start = 0;
+    lock.l_len = 0;
+    let cmd = if block { libc::F_SETLKW } else { libc::F_SETLK };
+    cvt_r(|| unsafe { libc::fcntl(fd, cmd, &lock) })?;
+    Ok(())
+}
+
+fn try_lock_file(fd: c_int, kind: LockKind) -> io::Result<()> {
+    match lock_file(fd, kind, false) {
+        // `flock` reports `EWOULDBLOCK` if the lock is held elsewhere, while
+        // `fcntl` may report either `EACCES` or `EAGAIN`. These all have a
+        // more general meaning too, so only map them to `Locked` here.
+        Err(ref e)
+            if e.raw_os_error().map_or(false, |errno| {
+                errno == libc::EWOULDBLOCK || errno == libc::EAGAIN || errno == libc::EACCES
+            }) =>
+        {
+            Err(io::Error::from(ErrorKind::Locked))
+        }
+        result => result,
+    }
 }
 
 impl DirBuilder {
@@ -984,6 +1105,27 @@ pub fn readdir(p: &Path) -> io::Result<ReadDir> {
     }
 }
 
+/// Opens the directory `entry` refers to relative to the (still open)
+/// directory it was read from, so that the full path doesn't have to be
+/// resolved again for every level of a deep tree.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn readdir_child(entry: &DirEntry, follow_links: bool) -> io::Result<ReadDir> {
+    let parent_fd = cvt(unsafe { dirfd(entry.dir.inner.dirp.0) })?;
+    let mut flags = libc::O_CLOEXEC | libc::O_RDONLY | libc::O_DIRECTORY;
+    if !follow_links {
+        flags |= libc::O_NOFOLLOW;
+    }
+    let fd = cvt_r(|| unsafe { libc::openat(parent_fd, entry.name_cstr().as_ptr(), flags) })?;
+    let ptr = unsafe { libc::fdopendir(fd) };
+    if ptr.is_null() {
+        let err = Error::last_os_error();
+        unsafe { libc::close(fd) };
+        return Err(err);
+    }
+    let inner = InnerReadDir { dirp: Dir(ptr), root: entry.path() };
+    Ok(ReadDir { inner: Arc::new(inner), end_of_stream: false })
+}
+
 pub fn unlink(p: &Path) -> io::Result<()> {
     let p = cstr(p)?;
     cvt(unsafe { libc::unlink(p.as_ptr()) })?;
@@ -1156,81 +1298,20 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
-    use crate::cmp;
-    use crate::sync::atomic::{AtomicBool, Ordering};
-
-    // Kernel prior to 4.5 don't have copy_file_range
-    // We store the availability in a global to avoid unnecessary syscalls
-    static HAS_COPY_FILE_RANGE: AtomicBool = AtomicBool::new(true);
-
-    unsafe fn copy_file_range(
-        fd_in: libc::c_int,
-        off_in: *mut libc::loff_t,
-        fd_out: libc::c_int,
-        off_out: *mut libc::loff_t,
-        len: libc::size_t,
-        flags: libc::c_uint,
-    ) -> libc::c_long {
-        libc::syscall(libc::SYS_copy_file_range, fd_in, off_in, fd_out, off_out, len, flags)
-    }
+    use super::kernel_copy::{copy_regular_files, CopyResult};
 
     let (mut reader, reader_metadata) = open_from(from)?;
-    let len = reader_metadata.len();
+    let max_len = u64::max_value();
     let (mut writer, _) = open_to_and_set_permissions(to, reader_metadata)?;
 
-    let has_copy_file_range = HAS_COPY_FILE_RANGE.load(Ordering::Relaxed);
-    let mut written = 0u64;
-    while written < len {
-        let copy_result = if has_copy_file_range {
-            let bytes_to_copy = cmp::min(len - written, usize::max_value() as u64) as usize;
-            let copy_result = unsafe {
-                // We actually don't have to adjust the offsets,
-                // because copy_file_range adjusts the file offset automatically
-                cvt(copy_file_range(
-                    reader.as_raw_fd(),
-                    ptr::null_mut(),
-                    writer.as_raw_fd(),
-                    ptr::null_mut(),
-                    bytes_to_copy,
-                    0,
-                ))
-            };
-            if let Err(ref copy_err) = copy_result {
-                match copy_err.raw_os_error() {
-                    Some(libc::ENOSYS) | Some(libc::EPERM) => {
-                        HAS_COPY_FILE_RANGE.store(false, Ordering::Relaxed);
-                    }
-                    _ => {}
-                }
-            }
-            copy_result
-        } else {
-            Err(io::Error::from_raw_os_error(libc::ENOSYS))
-        };
-        match copy_result {
-            Ok(ret) => written += ret as u64,
-            Err(err) => {
-                match err.raw_os_error() {
-                    Some(os_err)
-                        if os_err == libc::ENOSYS
-                            || os_err == libc::EXDEV
-                            || os_err == libc::EINVAL
-                            || os_err == libc::EPERM =>
-                    {
-                        // Try fallback io::copy if either:
-                        // - Kernel version is < 4.5 (ENOSYS)
-                        // - Files are mounted on different fs (EXDEV)
-                        // - copy_file_range is disallowed, for example by seccomp (EPERM)
-                        // - copy_file_range cannot be used with pipes or device nodes (EINVAL)
-                        assert_eq!(written, 0);
-                        return io::copy(&mut reader, &mut writer);
-                    }
-                    _ => return Err(err),
-                }
-            }
-        }
+    match copy_regular_files(reader.as_raw_fd(), writer.as_raw_fd(), max_len) {
+        CopyResult::Ended(bytes) => Ok(bytes),
+        CopyResult::Error(e, _) => Err(e),
+        CopyResult::Fallback(written) => match io::generic_copy(&mut reader, &mut writer) {
+            Ok(bytes) => Ok(bytes + written),
+            Err(e) => Err(e),
+        },
     }
-    Ok(written)
 }
 
 #[cfg(any(target_os = "macos", target_os = "ios"))]
@@ -1343,3 +1424,124 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     })?;
     Ok(bytes_copied as u64)
 }
+
+// Apple platforms only gained `openat`, `fdopendir` and `unlinkat` in macOS
+// 10.10 and iOS 8, which are newer than the oldest releases we support, and
+// Redox doesn't provide them at all. These keep using the path based
+// implementation.
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "redox"))]
+mod remove_dir_impl {
+    pub use crate::sys_common::fs::remove_dir_all;
+}
+
+// Everywhere else, the directory tree is walked through directory file
+// descriptors rather than paths. Every directory is opened relative to its
+// parent with `O_NOFOLLOW | O_DIRECTORY`, and every entry is removed relative
+// to the directory it was found in. So if a directory in the tree is replaced
+// by a symlink while we're working on it, we either fail to open the symlink,
+// or unlink the symlink itself, but never touch anything it points to.
+#[cfg(not(any(target_os = "macos", target_os = "ios", target_os = "redox")))]
+mod remove_dir_impl {
+    use super::{cstr, lstat, Dir, DirEntry, InnerReadDir, ReadDir};
+    use crate::ffi::CStr;
+    use crate::io;
+    use crate::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
+    use crate::path::{Path, PathBuf};
+    use crate::sync::Arc;
+    use crate::sys::{cvt, cvt_r};
+
+    fn openat_nofollow_dironly(parent_fd: Option<RawFd>, p: &CStr) -> io::Result<OwnedFd> {
+        let fd = cvt_r(|| unsafe {
+            libc::openat(
+                parent_fd.unwrap_or(libc::AT_FDCWD),
+                p.as_ptr(),
+                libc::O_CLOEXEC | libc::O_RDONLY | libc::O_NOFOLLOW | libc::O_DIRECTORY,
+            )
+        })?;
+        Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+    }
+
+    fn fdreaddir(dir_fd: OwnedFd) -> io::Result<(ReadDir, RawFd)> {
+        let ptr = unsafe { libc::fdopendir(dir_fd.as_raw_fd()) };
+        if ptr.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let dirp = Dir(ptr);
+        // The file descriptor is now owned by the `DIR` stream and is closed
+        // by `closedir`, so give up our ownership of it.
+        let fd = dir_fd.into_raw_fd();
+        // `root` is only used to build the full paths of the entries, which
+        // we never ask for.
+        let inner = InnerReadDir { dirp, root: PathBuf::new() };
+        Ok((ReadDir { inner: Arc::new(inner), end_of_stream: false }, fd))
+    }
+
+    #[cfg(any(target_os = "solaris", target_os = "illumos", target_os = "haiku"))]
+    fn is_dir(_ent: &DirEntry) -> Option<bool> {
+        None
+    }
+
+    #[cfg(not(any(target_os = "solaris", target_os = "illumos", target_os = "haiku")))]
+    fn is_dir(ent: &DirEntry) -> Option<bool> {
+        match ent.entry.d_type {
+            libc::DT_UNKNOWN => None,
+            libc::DT_DIR => Some(true),
+            _ => Some(false),
+        }
+    }
+
+    fn remove_dir_all_recursive(parent_fd: Option<RawFd>, path: &CStr) -> io::Result<()> {
+        // Try to open the entry as a directory, without following symlinks.
+        let fd = match openat_nofollow_dironly(parent_fd, path) {
+            // Not a directory (or a symlink, for which some older Linux
+            // kernels report `ELOOP` instead of `ENOTDIR`), so don't descend
+            // into it, but unlink it from its parent. The root itself is
+            // known to be a directory, so it failing to open as one means
+            // it was replaced in the meantime, which is an error.
+            Err(err) if matches!(err.raw_os_error(), Some(libc::ENOTDIR) | Some(libc::ELOOP)) => {
+                return match parent_fd {
+                    Some(parent_fd) => {
+                        cvt(unsafe { libc::unlinkat(parent_fd, path.as_ptr(), 0) })?;
+                        Ok(())
+                    }
+                    None => Err(err),
+                };
+            }
+            result => result?,
+        };
+
+        let (dir, fd) = fdreaddir(fd)?;
+        for child in dir {
+            let child = child?;
+            match is_dir(&child) {
+                Some(false) => {
+                    cvt(unsafe { libc::unlinkat(fd, child.name_cstr().as_ptr(), 0) })?;
+                }
+                // If we don't know the type of the entry, try to descend into
+                // it rather than calling `unlinkat` without `AT_REMOVEDIR`:
+                // POSIX allows that to unlink a directory for privileged
+                // processes, which orphans its contents on some filesystems.
+                Some(true) | None => remove_dir_all_recursive(Some(fd), child.name_cstr())?,
+            }
+        }
+
+        // Remove the directory itself now that it is empty.
+        cvt(unsafe {
+            libc::unlinkat(parent_fd.unwrap_or(libc::AT_FDCWD), path.as_ptr(), libc::AT_REMOVEDIR)
+        })?;
+        Ok(())
+    }
+
+    pub fn remove_dir_all(p: &Path) -> io::Result<()> {
+        // A symlink passed as the root is removed rather than followed, like
+        // `remove_file` would. Checking this up front isn't racy: if the root
+        // is swapped for a symlink afterwards, opening it with `O_NOFOLLOW`
+        // fails below.
+        let attr = lstat(p)?;
+        if attr.file_type().is_symlink() {
+            crate::fs::remove_file(p)
+        } else {
+            remove_dir_all_recursive(None, &cstr(p)?)
+        }
+    }
+}
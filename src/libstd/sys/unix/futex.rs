@@ -0,0 +1,88 @@
+#![cfg(any(target_os = "linux", target_os = "android"))]
+
+use crate::sync::atomic::{AtomicU32, Ordering::Relaxed};
+use crate::sys::cvt;
+use crate::time::{Duration, Instant};
+
+/// Waits for a `futex_wake` operation to wake us.
+///
+/// Returns directly if the futex doesn't hold the expected value.
+///
+/// Returns false on timeout, and true in all other cases.
+pub fn futex_wait(futex: &AtomicU32, expected: u32, timeout: Option<Duration>) -> bool {
+    // Calculate the deadline up front, so that being interrupted by a signal
+    // doesn't extend the total time we wait.
+    let deadline = timeout.and_then(|d| Instant::now().checked_add(d));
+
+    loop {
+        // No need to wait if the value already changed.
+        if futex.load(Relaxed) != expected {
+            return true;
+        }
+
+        // `FUTEX_WAIT` takes a relative timeout, so recompute the remaining
+        // time on every iteration. A deadline that overflowed `Instant` is
+        // treated as waiting forever.
+        let timespec = match deadline {
+            Some(deadline) => {
+                let remaining = match deadline.checked_duration_since(Instant::now()) {
+                    Some(remaining) => remaining,
+                    None => return false,
+                };
+                Some(libc::timespec {
+                    tv_sec: remaining.as_secs().min(libc::time_t::max_value() as u64)
+                        as libc::time_t,
+                    tv_nsec: remaining.subsec_nanos() as _,
+                })
+            }
+            None => None,
+        };
+
+        let r = unsafe {
+            libc::syscall(
+                libc::SYS_futex,
+                futex as *const AtomicU32,
+                libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG,
+                expected,
+                timespec.as_ref().map_or(crate::ptr::null(), |t| t as *const libc::timespec),
+            )
+        };
+
+        if r < 0 {
+            match super::os::errno() {
+                libc::ETIMEDOUT => return false,
+                libc::EINTR => continue,
+                _ => {}
+            }
+        }
+        return true;
+    }
+}
+
+/// Wakes up one thread that's blocked on `futex_wait` on this futex.
+///
+/// Returns true if this actually woke up such a thread,
+/// or false if no thread was waiting on this futex.
+pub fn futex_wake(futex: &AtomicU32) -> bool {
+    let woken = cvt(unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            futex as *const AtomicU32,
+            libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
+            1,
+        )
+    });
+    matches!(woken, Ok(n) if n > 0)
+}
+
+/// Wakes up all threads that are waiting on `futex_wait` on this futex.
+pub fn futex_wake_all(futex: &AtomicU32) {
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            futex as *const AtomicU32,
+            libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
+            i32::max_value(),
+        );
+    }
+}
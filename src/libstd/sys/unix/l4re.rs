@@ -8,7 +8,7 @@ pub mod net {
     #![allow(warnings)]
     use crate::convert::TryFrom;
     use crate::fmt;
-    use crate::io::{self, IoSlice, IoSliceMut};
+    use crate::io::{self, IoSlice, IoSliceMut, ReadBuf};
     use crate::net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr};
     use crate::sys::fd::FileDesc;
     use crate::sys_common::{AsInner, FromInner, IntoInner};
@@ -167,6 +167,10 @@ pub mod net {
             unimpl!();
         }
 
+        pub fn read_buf(&self, _: &mut ReadBuf<'_>) -> io::Result<()> {
+            unimpl!();
+        }
+
         pub fn read_vectored(&self, _: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
             unimpl!();
         }
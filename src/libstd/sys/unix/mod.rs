@@ -44,17 +44,18 @@ pub mod alloc;
 pub mod android;
 pub mod args;
 pub mod cmath;
-pub mod condvar;
 pub mod env;
 pub mod ext;
 pub mod fast_thread_local;
 pub mod fd;
 pub mod fs;
+pub mod futex;
 pub mod io;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub mod kernel_copy;
 #[cfg(target_os = "l4re")]
 mod l4re;
 pub mod memchr;
-pub mod mutex;
 #[cfg(not(target_os = "l4re"))]
 pub mod net;
 #[cfg(target_os = "l4re")]
@@ -64,7 +65,6 @@ pub mod path;
 pub mod pipe;
 pub mod process;
 pub mod rand;
-pub mod rwlock;
 pub mod stack_overflow;
 pub mod stdio;
 pub mod thread;
@@ -73,6 +73,21 @@ pub mod time;
 
 pub use crate::sys_common::os_str_bytes as os_str;
 
+cfg_if::cfg_if! {
+    if #[cfg(any(target_os = "linux", target_os = "android"))] {
+        #[path = "futex_condvar.rs"]
+        pub mod condvar;
+        #[path = "futex_mutex.rs"]
+        pub mod mutex;
+        #[path = "futex_rwlock.rs"]
+        pub mod rwlock;
+    } else {
+        pub mod condvar;
+        pub mod mutex;
+        pub mod rwlock;
+    }
+}
+
 #[cfg(not(test))]
 pub fn init() {
     // By default, some platforms will send a *signal* when an EPIPE error
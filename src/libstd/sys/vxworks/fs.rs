@@ -1,7 +1,7 @@
 // copies from linuxx
 use crate::ffi::{CStr, CString, OsStr, OsString};
 use crate::fmt;
-use crate::io::{self, Error, ErrorKind, IoSlice, IoSliceMut, SeekFrom};
+use crate::io::{self, Error, ErrorKind, IoSlice, IoSliceMut, ReadBuf, SeekFrom};
 use crate::mem;
 use crate::path::{Path, PathBuf};
 use crate::ptr;
@@ -14,6 +14,9 @@ use crate::sys::{cvt, cvt_r};
 use crate::sys_common::{AsInner, FromInner};
 use libc::{self, c_int, mode_t, off_t, stat64};
 use libc::{dirent, ftruncate, lseek, open, readdir_r as readdir64_r};
+
+pub use crate::sys_common::fs::readdir_child;
+
 pub struct File(FileDesc);
 
 #[derive(Clone)]
@@ -84,6 +87,10 @@ impl FileAttr {
         FileType { mode: self.stat.st_mode as mode_t }
     }
 
+    pub fn file_id(&self) -> Option<(u64, u64)> {
+        Some((self.stat.st_dev as u64, self.stat.st_ino as u64))
+    }
+
     pub fn modified(&self) -> io::Result<SystemTime> {
         Ok(SystemTime::from(libc::timespec {
             tv_sec: self.stat.st_mtime as libc::time_t,
@@ -347,6 +354,10 @@ impl File {
         self.0.read(buf)
     }
 
+    pub fn read_buf(&self, buf: &mut ReadBuf<'_>) -> io::Result<()> {
+        self.0.read_buf(buf)
+    }
+
     pub fn read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
         self.0.read_vectored(bufs)
     }
@@ -400,6 +411,26 @@ impl File {
         Ok(())
     }
 
+    pub fn lock(&self) -> io::Result<()> {
+        Err(io::Error::new(ErrorKind::Other, "file locking is not supported on VxWorks"))
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        Err(io::Error::new(ErrorKind::Other, "file locking is not supported on VxWorks"))
+    }
+
+    pub fn try_lock(&self) -> io::Result<()> {
+        Err(io::Error::new(ErrorKind::Other, "file locking is not supported on VxWorks"))
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        Err(io::Error::new(ErrorKind::Other, "file locking is not supported on VxWorks"))
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        Err(io::Error::new(ErrorKind::Other, "file locking is not supported on VxWorks"))
+    }
+
     pub fn diverge(&self) -> ! {
         panic!()
     }
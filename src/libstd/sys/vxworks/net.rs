@@ -1,7 +1,7 @@
 use crate::cmp;
 use crate::ffi::CStr;
 use crate::io;
-use crate::io::{IoSlice, IoSliceMut};
+use crate::io::{IoSlice, IoSliceMut, ReadBuf};
 use crate::mem;
 use crate::net::{Shutdown, SocketAddr};
 use crate::str;
@@ -155,6 +155,24 @@ impl Socket {
         self.recv_with_flags(buf, 0)
     }
 
+    pub fn read_buf(&self, buf: &mut ReadBuf<'_>) -> io::Result<()> {
+        let ret = cvt(unsafe {
+            libc::recv(
+                self.0.raw(),
+                buf.unfilled_mut().as_mut_ptr() as *mut c_void,
+                buf.remaining(),
+                0,
+            )
+        })?;
+
+        // SAFETY: `ret` bytes were written to the unfilled part of the buffer
+        unsafe {
+            buf.assume_init(ret as usize);
+        }
+        buf.add_filled(ret as usize);
+        Ok(())
+    }
+
     pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.recv_with_flags(buf, MSG_PEEK)
     }
@@ -1,6 +1,6 @@
 use crate::ffi::{CStr, CString, OsStr, OsString};
 use crate::fmt;
-use crate::io::{self, IoSlice, IoSliceMut, SeekFrom};
+use crate::io::{self, IoSlice, IoSliceMut, ReadBuf, SeekFrom};
 use crate::iter;
 use crate::mem::{self, ManuallyDrop};
 use crate::os::wasi::ffi::{OsStrExt, OsStringExt};
@@ -12,7 +12,7 @@ use crate::sys::time::SystemTime;
 use crate::sys::unsupported;
 use crate::sys_common::FromInner;
 
-pub use crate::sys_common::fs::remove_dir_all;
+pub use crate::sys_common::fs::{readdir_child, remove_dir_all};
 
 pub struct File {
     fd: WasiFd,
@@ -80,6 +80,10 @@ impl FileAttr {
         FileType { bits: self.meta.filetype }
     }
 
+    pub fn file_id(&self) -> Option<(u64, u64)> {
+        Some((self.meta.dev, self.meta.ino))
+    }
+
     pub fn modified(&self) -> io::Result<SystemTime> {
         Ok(SystemTime::from_wasi_timestamp(self.meta.mtim))
     }
@@ -395,6 +399,10 @@ impl File {
         self.read_vectored(&mut [IoSliceMut::new(buf)])
     }
 
+    pub fn read_buf(&self, buf: &mut ReadBuf<'_>) -> io::Result<()> {
+        crate::io::default_read_buf(|buf| self.read(buf), buf)
+    }
+
     pub fn read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
         self.fd.read(bufs)
     }
@@ -426,6 +434,27 @@ impl File {
         unsupported()
     }
 
+    // WASI has no file locking (yet).
+    pub fn lock(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn try_lock(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        unsupported()
+    }
+
     pub fn fd(&self) -> &WasiFd {
         &self.fd
     }
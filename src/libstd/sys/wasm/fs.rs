@@ -1,11 +1,13 @@
 use crate::ffi::OsString;
 use crate::fmt;
 use crate::hash::{Hash, Hasher};
-use crate::io::{self, IoSlice, IoSliceMut, SeekFrom};
+use crate::io::{self, IoSlice, IoSliceMut, ReadBuf, SeekFrom};
 use crate::path::{Path, PathBuf};
 use crate::sys::time::SystemTime;
 use crate::sys::{unsupported, Void};
 
+pub use crate::sys_common::fs::readdir_child;
+
 pub struct File(Void);
 
 pub struct FileAttr(Void);
@@ -37,6 +39,10 @@ impl FileAttr {
         match self.0 {}
     }
 
+    pub fn file_id(&self) -> Option<(u64, u64)> {
+        match self.0 {}
+    }
+
     pub fn modified(&self) -> io::Result<SystemTime> {
         match self.0 {}
     }
@@ -198,6 +204,10 @@ impl File {
         match self.0 {}
     }
 
+    pub fn read_buf(&self, _buf: &mut ReadBuf<'_>) -> io::Result<()> {
+        match self.0 {}
+    }
+
     pub fn read_vectored(&self, _bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
         match self.0 {}
     }
@@ -226,6 +236,26 @@ impl File {
         match self.0 {}
     }
 
+    pub fn lock(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn try_lock(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
     pub fn diverge(&self) -> ! {
         match self.0 {}
     }
@@ -2,7 +2,7 @@ use crate::os::windows::prelude::*;
 
 use crate::ffi::OsString;
 use crate::fmt;
-use crate::io::{self, Error, IoSlice, IoSliceMut, SeekFrom};
+use crate::io::{self, Error, IoSlice, IoSliceMut, ReadBuf, SeekFrom};
 use crate::mem;
 use crate::path::{Path, PathBuf};
 use crate::ptr;
@@ -15,6 +15,8 @@ use crate::sys_common::FromInner;
 
 use super::to_u16s;
 
+pub use crate::sys_common::fs::readdir_child;
+
 pub struct File {
     handle: Handle,
 }
@@ -405,6 +407,10 @@ impl File {
         self.handle.read(buf)
     }
 
+    pub fn read_buf(&self, buf: &mut ReadBuf<'_>) -> io::Result<()> {
+        self.handle.read_buf(buf)
+    }
+
     pub fn read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
         self.handle.read_vectored(bufs)
     }
@@ -539,6 +545,59 @@ impl File {
         })?;
         Ok(())
     }
+
+    pub fn lock(&self) -> io::Result<()> {
+        self.lock_file(c::LOCKFILE_EXCLUSIVE_LOCK)
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.lock_file(0)
+    }
+
+    pub fn try_lock(&self) -> io::Result<()> {
+        // Fails with `ERROR_LOCK_VIOLATION` if the file is locked, which
+        // `decode_error_kind` turns into `ErrorKind::Locked`.
+        self.lock_file(c::LOCKFILE_EXCLUSIVE_LOCK | c::LOCKFILE_FAIL_IMMEDIATELY)
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        self.lock_file(c::LOCKFILE_FAIL_IMMEDIATELY)
+    }
+
+    fn lock_file(&self, flags: c::DWORD) -> io::Result<()> {
+        // Lock the largest possible range starting at offset 0, which covers
+        // the whole file however large it grows.
+        let mut overlapped: c::OVERLAPPED = unsafe { mem::zeroed() };
+        cvt(unsafe {
+            c::LockFileEx(
+                self.handle.raw(),
+                flags,
+                0,
+                u32::max_value(),
+                u32::max_value(),
+                &mut overlapped,
+            )
+        })?;
+        Ok(())
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        // A handle can hold both a shared and an exclusive lock on the same
+        // range, in which case each of them needs its own unlock operation.
+        // Unlocking a range that isn't locked fails with `ERROR_NOT_LOCKED`,
+        // which isn't an error for our purposes.
+        for _ in 0..2 {
+            let result = cvt(unsafe {
+                c::UnlockFile(self.handle.raw(), 0, 0, u32::max_value(), u32::max_value())
+            });
+            match result {
+                Ok(_) => {}
+                Err(ref e) if e.raw_os_error() == Some(c::ERROR_NOT_LOCKED as i32) => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
 }
 
 impl FromInner<c::HANDLE> for File {
@@ -576,6 +635,15 @@ impl FileAttr {
         FileType::new(self.attributes, self.reparse_tag)
     }
 
+    // Only known if the attributes were queried through a handle, as `stat`
+    // and `lstat` do.
+    pub fn file_id(&self) -> Option<(u64, u64)> {
+        match (self.volume_serial_number, self.file_index) {
+            (Some(volume), Some(index)) => Some((volume as u64, index)),
+            _ => None,
+        }
+    }
+
     pub fn modified(&self) -> io::Result<SystemTime> {
         Ok(SystemTime::from(self.last_write_time))
     }
@@ -1,7 +1,7 @@
 #![unstable(issue = "none", feature = "windows_net")]
 
 use crate::cmp;
-use crate::io::{self, IoSlice, IoSliceMut, Read};
+use crate::io::{self, IoSlice, IoSliceMut, Read, ReadBuf};
 use crate::mem;
 use crate::net::{Shutdown, SocketAddr};
 use crate::ptr;
@@ -242,6 +242,25 @@ impl Socket {
         self.recv_with_flags(buf, 0)
     }
 
+    pub fn read_buf(&self, buf: &mut ReadBuf<'_>) -> io::Result<()> {
+        // Shut down sockets report EOF, see `recv_with_flags`.
+        let len = cmp::min(buf.remaining(), i32::max_value() as usize) as i32;
+        let ret = unsafe {
+            match c::recv(self.0, buf.unfilled_mut().as_mut_ptr() as *mut c_void, len, 0) {
+                -1 if c::WSAGetLastError() == c::WSAESHUTDOWN => 0,
+                -1 => return Err(last_error()),
+                n => n as usize,
+            }
+        };
+
+        // SAFETY: `ret` bytes were written to the unfilled part of the buffer
+        unsafe {
+            buf.assume_init(ret);
+        }
+        buf.add_filled(ret);
+        Ok(())
+    }
+
     pub fn read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
         // On unix when a socket is shut down all further reads return 0, so we
         // do the same on windows to map a shut down socket to returning EOF.
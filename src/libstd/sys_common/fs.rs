@@ -3,6 +3,7 @@
 use crate::fs;
 use crate::io::{self, Error, ErrorKind};
 use crate::path::Path;
+use crate::sys::fs as fs_imp;
 
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     if !from.is_file() {
@@ -37,3 +38,9 @@ fn remove_dir_all_recursive(path: &Path) -> io::Result<()> {
     }
     fs::remove_dir(path)
 }
+
+/// Opens the directory `entry` refers to, for platforms that can only open
+/// directories by path.
+pub fn readdir_child(entry: &fs_imp::DirEntry, _follow_links: bool) -> io::Result<fs_imp::ReadDir> {
+    fs_imp::readdir(&entry.path())
+}
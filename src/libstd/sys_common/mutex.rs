@@ -86,6 +86,66 @@ pub fn raw(mutex: &Mutex) -> &imp::Mutex {
     &mutex.0
 }
 
+/// An OS-based mutual exclusion lock that can be moved, for use by the safe
+/// wrapper at the top level of the crate.
+///
+/// Depending on the platform the OS mutex is either stored inline, or boxed to
+/// give it a stable address.
+pub struct MovableMutex(imp::MovableMutex);
+
+unsafe impl Sync for MovableMutex {}
+
+impl MovableMutex {
+    /// Creates a new mutex.
+    ///
+    /// Futex based mutexes are stored inline and need no initialization, so
+    /// they can be created in a `const` context.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub const fn new() -> Self {
+        Self(imp::Mutex::new())
+    }
+
+    /// Creates a new mutex.
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    pub fn new() -> Self {
+        let mut mutex = imp::MovableMutex::from(imp::Mutex::new());
+        unsafe { mutex.init() };
+        Self(mutex)
+    }
+
+    pub(super) fn raw(&self) -> &imp::Mutex {
+        &self.0
+    }
+
+    /// Locks the mutex blocking the current thread until it is available.
+    #[inline]
+    pub fn raw_lock(&self) {
+        unsafe { self.0.lock() }
+    }
+
+    /// Attempts to lock the mutex without blocking, returning whether it was
+    /// successfully acquired or not.
+    #[inline]
+    pub fn try_lock(&self) -> bool {
+        unsafe { self.0.try_lock() }
+    }
+
+    /// Unlocks the mutex.
+    ///
+    /// Behavior is undefined if the current thread does not actually hold the
+    /// mutex.
+    #[inline]
+    pub unsafe fn raw_unlock(&self) {
+        self.0.unlock()
+    }
+}
+
+impl Drop for MovableMutex {
+    fn drop(&mut self) {
+        unsafe { self.0.destroy() };
+    }
+}
+
 #[must_use]
 /// A simple RAII utility for the above Mutex without the poisoning semantics.
 pub struct MutexGuard<'a>(&'a imp::Mutex);
@@ -86,3 +86,82 @@ impl RWLock {
         self.0.destroy()
     }
 }
+
+/// A reader-writer lock that can be moved, for use by the safe wrapper at the
+/// top level of the crate.
+///
+/// Depending on the platform the OS lock is either stored inline, or boxed to
+/// give it a stable address.
+pub struct MovableRWLock(imp::MovableRWLock);
+
+impl MovableRWLock {
+    /// Creates a new reader-writer lock for use.
+    ///
+    /// Futex based locks are stored inline, so they can be created in a
+    /// `const` context.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub const fn new() -> Self {
+        Self(imp::RWLock::new())
+    }
+
+    /// Creates a new reader-writer lock for use.
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    pub fn new() -> Self {
+        Self(imp::MovableRWLock::from(imp::RWLock::new()))
+    }
+
+    /// Acquires shared access to the underlying lock, blocking the current
+    /// thread to do so.
+    #[inline]
+    pub fn read(&self) {
+        unsafe { self.0.read() }
+    }
+
+    /// Attempts to acquire shared access to this lock, returning whether it
+    /// succeeded or not.
+    ///
+    /// This function does not block the current thread.
+    #[inline]
+    pub fn try_read(&self) -> bool {
+        unsafe { self.0.try_read() }
+    }
+
+    /// Acquires write access to the underlying lock, blocking the current thread
+    /// to do so.
+    #[inline]
+    pub fn write(&self) {
+        unsafe { self.0.write() }
+    }
+
+    /// Attempts to acquire exclusive access to this lock, returning whether it
+    /// succeeded or not.
+    ///
+    /// This function does not block the current thread.
+    #[inline]
+    pub fn try_write(&self) -> bool {
+        unsafe { self.0.try_write() }
+    }
+
+    /// Unlocks previously acquired shared access to this lock.
+    ///
+    /// Behavior is undefined if the current thread does not have shared access.
+    #[inline]
+    pub unsafe fn read_unlock(&self) {
+        self.0.read_unlock()
+    }
+
+    /// Unlocks previously acquired exclusive access to this lock.
+    ///
+    /// Behavior is undefined if the current thread does not currently have
+    /// exclusive access.
+    #[inline]
+    pub unsafe fn write_unlock(&self) {
+        self.0.write_unlock()
+    }
+}
+
+impl Drop for MovableRWLock {
+    fn drop(&mut self) {
+        unsafe { self.0.destroy() };
+    }
+}
@@ -161,6 +161,7 @@ use crate::cell::UnsafeCell;
 use crate::ffi::{CStr, CString};
 use crate::fmt;
 use crate::io;
+use crate::marker::PhantomData;
 use crate::mem;
 use crate::num::NonZeroU64;
 use crate::panic;
@@ -186,6 +187,11 @@ mod local;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::local::{AccessError, LocalKey};
 
+mod scoped;
+
+#[unstable(feature = "scoped_threads", issue = "none")]
+pub use scoped::{scope, Scope, ScopedJoinHandle};
+
 // The types used by the thread_local! macro to access TLS keys. Note that there
 // are two types, the "OS" type and the "fast" type. The OS thread local key
 // type is accessed via platform-specific API calls and is slow, while the fast
@@ -454,6 +460,20 @@ impl Builder {
         F: FnOnce() -> T,
         F: Send + 'a,
         T: Send + 'a,
+    {
+        Ok(JoinHandle(self.spawn_unchecked_(f, None)?))
+    }
+
+    unsafe fn spawn_unchecked_<'a, 'scope, F, T>(
+        self,
+        f: F,
+        scope_data: Option<Arc<scoped::ScopeData>>,
+    ) -> io::Result<JoinInner<'scope, T>>
+    where
+        F: FnOnce() -> T,
+        F: Send + 'a,
+        T: Send + 'a,
+        'scope: 'a,
     {
         let Builder { name, stack_size } = self;
 
@@ -462,7 +482,11 @@ impl Builder {
         let my_thread = Thread::new(name);
         let their_thread = my_thread.clone();
 
-        let my_packet: Arc<UnsafeCell<Option<Result<T>>>> = Arc::new(UnsafeCell::new(None));
+        let my_packet: Arc<Packet<'scope, T>> = Arc::new(Packet {
+            scope: scope_data,
+            result: UnsafeCell::new(None),
+            _marker: PhantomData,
+        });
         let their_packet = my_packet.clone();
 
         let main = move || {
@@ -474,10 +498,18 @@ impl Builder {
             let try_result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
                 crate::sys_common::backtrace::__rust_begin_short_backtrace(f)
             }));
-            *their_packet.get() = Some(try_result);
+            // Store the result in the packet, then drop our reference to it so
+            // that a scope waiting on this thread only wakes up once nothing on
+            // this thread can observe `'scope` anymore.
+            *their_packet.result.get() = Some(try_result);
+            drop(their_packet);
         };
 
-        Ok(JoinHandle(JoinInner {
+        if let Some(scope_data) = &my_packet.scope {
+            scope_data.increment_num_running_threads();
+        }
+
+        Ok(JoinInner {
             // `imp::Thread::new` takes a closure with a `'static` lifetime, since it's passed
             // through FFI or otherwise used with low-level threading primitives that have no
             // notion of or way to enforce lifetimes.
@@ -496,8 +528,8 @@ impl Builder {
                 )),
             )?),
             thread: my_thread,
-            packet: Packet(my_packet),
-        }))
+            packet: my_packet,
+        })
     }
 }
 
@@ -1328,33 +1360,62 @@ impl fmt::Debug for Thread {
 pub type Result<T> = crate::result::Result<T, Box<dyn Any + Send + 'static>>;
 
 // This packet is used to communicate the return value between the child thread
-// and the parent thread. Memory is shared through the `Arc` within and there's
-// no need for a mutex here because synchronization happens with `join()` (the
-// parent thread never reads this packet until the child has exited).
+// and the parent thread. Memory is shared through the `Arc` and there's no need
+// for a mutex here because synchronization happens with `join()` (the parent
+// thread never reads this packet until the child has exited).
 //
-// This packet itself is then stored into a `JoinInner` which in turns is placed
-// in `JoinHandle` and `JoinGuard`. Due to the usage of `UnsafeCell` we need to
-// manually worry about impls like Send and Sync. The type `T` should
+// An `Arc` to the packet is stored into a `JoinInner` which in turns is placed
+// in `JoinHandle` or `ScopedJoinHandle`. Due to the usage of `UnsafeCell` we
+// need to manually worry about impls like Send and Sync. The type `T` should
 // already always be Send (otherwise the thread could not have been created) and
-// this type is inherently Sync because no methods take &self. Regardless,
-// however, we add inheriting impls for Send/Sync to this type to ensure it's
-// Send/Sync and that future modifications will still appropriately classify it.
-struct Packet<T>(Arc<UnsafeCell<Option<Result<T>>>>);
+// the packet is Sync because all access to the `UnsafeCell` is synchronized by
+// the `join()` boundary, and `ScopeData` is Sync.
+//
+// For scoped threads the packet also keeps the scope alive, and dropping it is
+// what tells the scope that the thread has finished.
+struct Packet<'scope, T> {
+    scope: Option<Arc<scoped::ScopeData>>,
+    result: UnsafeCell<Option<Result<T>>>,
+    _marker: PhantomData<Option<&'scope scoped::ScopeData>>,
+}
 
-unsafe impl<T: Send> Send for Packet<T> {}
-unsafe impl<T: Sync> Sync for Packet<T> {}
+unsafe impl<'scope, T: Sync> Sync for Packet<'scope, T> {}
+
+impl<'scope, T> Drop for Packet<'scope, T> {
+    fn drop(&mut self) {
+        // If this packet was for a thread that ran in a scope, the thread
+        // panicked, and nobody consumed the panic payload, we make sure
+        // the scope function will panic.
+        let unhandled_panic = matches!(self.result.get_mut(), Some(Err(_)));
+        // Drop the result without causing unwinding. This is only relevant
+        // for threads that aren't joined, as `join()` takes the result and
+        // leaves `None` behind. A panic here would escape the outermost
+        // `catch_unwind` of the thread, so abort instead.
+        if let Err(_) = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            *self.result.get_mut() = None;
+        })) {
+            crate::sys_common::util::abort(format_args!("thread result panicked on drop"));
+        }
+        // Book-keeping so the scope knows when it's done. This must happen
+        // after the result has been dropped, since dropping it might still
+        // use things it borrowed from 'scope.
+        if let Some(scope) = &self.scope {
+            scope.decrement_num_running_threads(unhandled_panic);
+        }
+    }
+}
 
-/// Inner representation for JoinHandle
-struct JoinInner<T> {
+/// Inner representation for JoinHandle and ScopedJoinHandle
+struct JoinInner<'scope, T> {
     native: Option<imp::Thread>,
     thread: Thread,
-    packet: Packet<T>,
+    packet: Arc<Packet<'scope, T>>,
 }
 
-impl<T> JoinInner<T> {
+impl<'scope, T> JoinInner<'scope, T> {
     fn join(&mut self) -> Result<T> {
         self.native.take().unwrap().join();
-        unsafe { (*self.packet.0.get()).take().unwrap() }
+        Arc::get_mut(&mut self.packet).unwrap().result.get_mut().take().unwrap()
     }
 }
 
@@ -1422,7 +1483,7 @@ impl<T> JoinInner<T> {
 /// [`thread::spawn`]: fn.spawn.html
 /// [`thread::Builder::spawn`]: struct.Builder.html#method.spawn
 #[stable(feature = "rust1", since = "1.0.0")]
-pub struct JoinHandle<T>(JoinInner<T>);
+pub struct JoinHandle<T>(JoinInner<'static, T>);
 
 #[stable(feature = "joinhandle_impl_send_sync", since = "1.29.0")]
 unsafe impl<T> Send for JoinHandle<T> {}
@@ -1523,6 +1584,7 @@ mod tests {
     use crate::any::Any;
     use crate::mem;
     use crate::result;
+    use crate::sync::atomic::{AtomicUsize, Ordering::SeqCst};
     use crate::sync::mpsc::{channel, Sender};
     use crate::thread::{self, ThreadId};
     use crate::time::Duration;
@@ -1783,6 +1845,78 @@ mod tests {
         assert!(thread::current().id() != spawned_id);
     }
 
+    #[test]
+    fn test_scoped_threads_borrow() {
+        let mut a = vec![1, 2, 3];
+        let mut x = 0;
+        thread::scope(|s| {
+            s.spawn(|| assert_eq!(a.len(), 3));
+            s.spawn(|| x += a[0] + a[2]);
+        });
+        a.push(4);
+        assert_eq!(x, a.len());
+    }
+
+    #[test]
+    fn test_scoped_threads_join() {
+        let v = [1, 2, 3, 4];
+        let sum = thread::scope(|s| {
+            let (left, right) = v.split_at(2);
+            let l = s.spawn(move || left.iter().sum::<i32>());
+            let r = Builder::new()
+                .name("right".to_string())
+                .spawn_scoped(s, move || {
+                    assert_eq!(thread::current().name(), Some("right"));
+                    right.iter().sum::<i32>()
+                })
+                .unwrap();
+            l.join().unwrap() + r.join().unwrap()
+        });
+        assert_eq!(sum, 10);
+    }
+
+    #[test]
+    fn test_scoped_threads_waits_for_unjoined() {
+        let (tx, rx) = channel();
+        thread::scope(|s| {
+            s.spawn(|| {
+                thread::sleep(Duration::from_millis(20));
+                tx.send(()).unwrap();
+            });
+        });
+        assert!(rx.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_scoped_threads_joined_panic_is_handled() {
+        thread::scope(|s| {
+            let t = s.spawn(|| panic!("oh no"));
+            assert!(t.join().is_err());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "a scoped thread panicked")]
+    fn test_scoped_threads_unjoined_panic_is_propagated() {
+        thread::scope(|s| {
+            s.spawn(|| panic!("oh no"));
+        });
+    }
+
+    #[test]
+    fn test_scoped_threads_nested() {
+        let counter = &AtomicUsize::new(0);
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(move || {
+                    s.spawn(move || counter.fetch_add(1, SeqCst));
+                    counter.fetch_add(1, SeqCst);
+                });
+            }
+        });
+        assert_eq!(counter.load(SeqCst), 8);
+    }
+
     // NOTE: the corresponding test for stderr is in ui/thread-stderr, due
     // to the test harness apparently interfering with stderr configuration.
 }
@@ -610,7 +610,7 @@ static TargetMachine::CodeGenFileType fromRust(LLVMRustFileType Type) {
 
 extern "C" LLVMRustResult
 LLVMRustWriteOutputFile(LLVMTargetMachineRef Target, LLVMPassManagerRef PMR,
-                        LLVMModuleRef M, const char *Path,
+                        LLVMModuleRef M, const char *Path, const char *DwoPath,
                         LLVMRustFileType RustFileType) {
   llvm::legacy::PassManager *PM = unwrap<llvm::legacy::PassManager>(PMR);
   auto FileType = fromRust(RustFileType);
@@ -626,12 +626,30 @@ LLVMRustWriteOutputFile(LLVMTargetMachineRef Target, LLVMPassManagerRef PMR,
   }
 
   buffer_ostream BOS(OS);
-  unwrap(Target)->addPassesToEmitFile(*PM, BOS, nullptr, FileType, false);
+  // Like `OS`, the split DWARF streams must outlive the pass manager, so they
+  // live at function scope. `DBOS` is declared last so that it is destroyed,
+  // and flushed into `DOS`, first.
+  std::unique_ptr<raw_fd_ostream> DOS;
+  std::unique_ptr<buffer_ostream> DBOS;
+  if (DwoPath) {
+    DOS.reset(new raw_fd_ostream(DwoPath, EC, sys::fs::F_None));
+    if (EC)
+      ErrorInfo = EC.message();
+    if (ErrorInfo != "") {
+      LLVMRustSetLastError(ErrorInfo.c_str());
+      return LLVMRustResult::Failure;
+    }
+    DBOS.reset(new buffer_ostream(*DOS));
+    // The skeleton units left in the object file refer to the split DWARF
+    // file by this name.
+    unwrap(Target)->Options.MCOptions.SplitDwarfFile = DwoPath;
+  }
+  unwrap(Target)->addPassesToEmitFile(*PM, BOS, DBOS.get(), FileType, false);
   PM->run(*unwrap(M));
 
   // Apparently `addPassesToEmitFile` adds a pointer to our on-the-stack output
-  // stream (OS), so the only real safe place to delete this is here? Don't we
-  // wish this was written in Rust?
+  // streams (OS and DBOS), so the only real safe place to delete this is here?
+  // Don't we wish this was written in Rust?
   LLVMDisposePassManager(PMR);
   return LLVMRustResult::Success;
 }
@@ -0,0 +1,23 @@
+// Tests that the warnings of a check build are emitted again by later check
+// builds that load the results of the queries which emitted them from the
+// on-disk cache.
+
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: --emit=metadata
+// build-pass (FIXME(62277): could be check-pass?)
+
+#![crate_type = "rlib"]
+#![warn(unused_variables, unused_mut, dead_code)]
+
+fn unused() {} //~ WARNING function is never used
+
+pub fn f() {
+    let x = 1; //~ WARNING unused variable
+    let mut y = 2; //~ WARNING variable does not need to be mutable
+    drop(y);
+    let c = || {
+        let mut z = 3; //~ WARNING variable does not need to be mutable
+        drop(z);
+    };
+    c();
+}
@@ -0,0 +1,31 @@
+// Tests that the borrow checking result of an unchanged body is loaded from the
+// on-disk cache when it is needed again, instead of borrow checking the body
+// again. Changing `b` makes `type_of(Foo)` run again, which needs the result of
+// borrow checking `a`, the other body defining `Foo`.
+
+// revisions: rpass1 rpass2
+// compile-flags: -Z query-dep-graph
+
+#![feature(rustc_attrs, type_alias_impl_trait)]
+
+type Foo = impl std::fmt::Debug;
+
+#[rustc_clean(cfg="rpass2", label="mir_borrowck", loaded_from_disk="mir_borrowck")]
+fn a() -> Foo {
+    1u32
+}
+
+#[cfg(rpass1)]
+fn b() -> Foo {
+    2u32
+}
+
+#[cfg(rpass2)]
+fn b() -> Foo {
+    let x = 2u32;
+    x
+}
+
+fn main() {
+    println!("{:?} {:?}", a(), b());
+}
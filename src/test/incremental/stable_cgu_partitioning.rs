@@ -0,0 +1,37 @@
+// This test checks that with `-Z cgu-partitioning-strategy=stable-hash`, generic
+// instantiations are placed in codegen units of their own, so that adding a new instantiation
+// doesn't invalidate the codegen unit containing the non-generic items.
+
+// revisions: cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Z cgu-partitioning-strategy=stable-hash -C codegen-units=1
+// build-pass
+
+#![feature(rustc_attrs)]
+#![crate_type = "rlib"]
+#![rustc_partition_reused(module = "stable_cgu_partitioning-stable.0", cfg = "cfail2")]
+#![rustc_partition_codegened(module = "stable_cgu_partitioning-stable.0-volatile", cfg = "cfail2")]
+
+pub mod stable {
+    pub fn answer() -> u32 {
+        42
+    }
+}
+
+pub fn size<T>() -> usize {
+    std::mem::size_of::<T>()
+}
+
+#[cfg(cfail1)]
+pub fn wrapper<T>() -> usize {
+    size::<T>()
+}
+
+// Adds a new instantiation, `size::<(T, u8)>`, without changing any non-generic item.
+#[cfg(cfail2)]
+pub fn wrapper<T>() -> usize {
+    size::<T>() + size::<(T, u8)>()
+}
+
+pub fn instantiate() -> usize {
+    wrapper::<u32>()
+}
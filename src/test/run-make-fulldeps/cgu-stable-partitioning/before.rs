@@ -0,0 +1,17 @@
+pub mod a {
+    pub fn a() -> u32 {
+        1
+    }
+}
+
+pub mod b {
+    pub fn b() -> u32 {
+        2
+    }
+}
+
+pub mod c {
+    pub fn c() -> u32 {
+        3
+    }
+}
@@ -0,0 +1,82 @@
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_mir;
+
+use rustc_driver::Compilation;
+use rustc_hir::def_id::LocalDefId;
+use rustc_interface::{interface, Queries};
+use rustc_middle::ty::TyCtxt;
+use rustc_mir::borrow_check::consumers::BodyWithBorrowckFacts;
+
+use std::path::PathBuf;
+
+#[derive(Default)]
+struct Calls {
+    after_typeck: bool,
+}
+
+impl rustc_driver::Callbacks for Calls {
+    fn wants_borrowck_facts(&self) -> bool {
+        true
+    }
+
+    fn after_typeck<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        _queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        self.after_typeck = true;
+        Compilation::Continue
+    }
+
+    fn after_borrowck<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        tcx: TyCtxt<'tcx>,
+        bodies: &[(LocalDefId, BodyWithBorrowckFacts<'tcx>)],
+    ) -> Compilation {
+        assert!(self.after_typeck, "`after_borrowck` was called before `after_typeck`");
+        for (def_id, body) in bodies {
+            let name = tcx.def_path_str(def_id.to_def_id());
+            if body.input_facts.borrow_region.is_empty() {
+                println!("{}: has no borrow facts", name);
+            } else {
+                println!("{}: has borrow facts", name);
+            }
+            if tcx.is_mir_available(def_id.to_def_id()) {
+                let optimized = tcx.optimized_mir(def_id.to_def_id());
+                assert_eq!(optimized.arg_count, body.body.arg_count);
+                println!("{}: has optimized MIR", name);
+            }
+        }
+        Compilation::Continue
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let mut sysroot = PathBuf::from(&args[2]);
+    sysroot.pop();
+    sysroot.pop();
+
+    let mut rustc_args = vec![
+        "rustc".to_string(),
+        args[1].clone(),
+        "--sysroot".to_string(),
+        sysroot.display().to_string(),
+    ];
+    rustc_args.extend(args[3..].iter().cloned());
+    let mut calls = Calls::default();
+    let result = rustc_driver::catch_fatal_errors(|| {
+        rustc_driver::run_compiler(&rustc_args, &mut calls, None, None)
+    });
+    assert!(calls.after_typeck);
+    if !matches!(result, Ok(Ok(()))) {
+        std::process::exit(1);
+    }
+}
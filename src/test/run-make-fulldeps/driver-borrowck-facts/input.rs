@@ -0,0 +1,22 @@
+#![crate_type = "lib"]
+
+pub fn borrow(v: &Vec<u32>) -> &u32 {
+    let first = &v[0];
+    first
+}
+
+pub fn no_borrow(x: u32) -> u32 {
+    x + 1
+}
+
+// Evaluated while type-checking `array`, so `optimized_mir` steals the MIR of
+// `len` before the crate is borrow checked.
+pub const fn len() -> usize {
+    let x = [1, 2];
+    let r = &x;
+    r.len()
+}
+
+pub fn array() -> [u8; len()] {
+    [0; len()]
+}
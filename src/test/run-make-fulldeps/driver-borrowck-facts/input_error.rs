@@ -0,0 +1,7 @@
+#![crate_type = "lib"]
+
+pub fn conflict(mut v: Vec<u32>) -> u32 {
+    let first = &v[0];
+    v.push(1);
+    *first
+}
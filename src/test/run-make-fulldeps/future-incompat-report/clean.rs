@@ -0,0 +1,5 @@
+#![crate_type = "lib"]
+
+pub fn sum() -> u32 {
+    [1, 2, 3].iter().sum()
+}
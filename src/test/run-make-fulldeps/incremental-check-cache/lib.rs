@@ -0,0 +1,14 @@
+pub struct Wrapper(u32);
+
+impl Default for Wrapper {
+    fn default() -> Self {
+        Wrapper(1)
+    }
+}
+
+pub fn f(w: Wrapper) -> u32 {
+    let x = 1;
+    let mut y = w.0;
+    drop(y);
+    2
+}
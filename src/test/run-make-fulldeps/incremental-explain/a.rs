@@ -0,0 +1,11 @@
+pub struct S {
+    x: u32,
+}
+
+pub fn get(s: &S) -> u64 {
+    s.x as u64
+}
+
+pub fn unrelated() -> u32 {
+    1
+}
@@ -0,0 +1,14 @@
+pub struct Wrapper(u64);
+
+#[inline(never)]
+fn fill(v: &mut Vec<Wrapper>) {
+    for i in 0..10 {
+        v.push(Wrapper(i));
+    }
+}
+
+fn main() {
+    let mut v = Vec::new();
+    fill(&mut v);
+    println!("{}", v.len());
+}
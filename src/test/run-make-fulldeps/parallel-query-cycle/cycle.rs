@@ -0,0 +1,21 @@
+// Type checking `a` needs to know whether the return type of `b` is `Send`,
+// which needs the type checking results of `b`, and the other way around.
+// When both bodies are checked on different threads, each thread waits for
+// the other one, and the cycle is only found by the deadlock handler.
+
+fn is_send<T: Send>(_: T) {}
+
+fn a() -> impl Sized {
+    is_send(b());
+    0u8
+}
+
+fn b() -> impl Sized {
+    is_send(a());
+    0u8
+}
+
+fn main() {
+    a();
+    b();
+}
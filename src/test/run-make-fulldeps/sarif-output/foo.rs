@@ -0,0 +1,4 @@
+fn main() {
+    let x = 1;
+    let y: u32 = "not a number";
+}
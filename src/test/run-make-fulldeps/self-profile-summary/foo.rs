@@ -0,0 +1,9 @@
+const TABLE: [u64; 4] = [1, 4, 9, 16];
+
+fn slow(v: &[u64]) -> u64 {
+    v.iter().map(|x| x * 2).sum()
+}
+
+fn main() {
+    println!("{}", slow(&TABLE));
+}
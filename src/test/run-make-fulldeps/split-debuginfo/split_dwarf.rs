@@ -0,0 +1,16 @@
+// A binary with enough debuginfo for split DWARF to leave skeleton units in the
+// object files.
+
+pub struct Point {
+    pub x: u32,
+    pub y: u32,
+}
+
+fn manhattan(p: &Point) -> u32 {
+    p.x + p.y
+}
+
+fn main() {
+    let p = Point { x: 1, y: 2 };
+    println!("{}", manhattan(&p));
+}
@@ -0,0 +1,8 @@
+pub struct Foo {
+    pub x: u32,
+}
+
+fn main() {
+    let foo = Foo { x: 1 };
+    println!("{}", foo.x);
+}
@@ -0,0 +1,18 @@
+// Errors from different invocations of the same macro are reported once,
+// pointing at the other invocations.
+
+// compile-flags: -Z group-macro-diagnostics
+
+macro_rules! define {
+    ($name:ident) => {
+        fn $name() -> u32 {
+            missing //~ ERROR cannot find value `missing` in this scope
+        }
+    };
+}
+
+define!(a);
+define!(b);
+define!(c);
+
+fn main() {}
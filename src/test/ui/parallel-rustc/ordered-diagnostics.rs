@@ -0,0 +1,28 @@
+// Checks that the errors reported while type checking bodies on several
+// threads are emitted in source order, however the threads are scheduled.
+
+// needs-parallel-compiler
+// compile-flags: -Z threads=4
+
+fn a() {
+    let _x: u32 = true; //~ ERROR mismatched types
+}
+
+fn b() {
+    let _x: u32 = false; //~ ERROR mismatched types
+}
+
+fn c() {
+    let _x: bool = 1u32; //~ ERROR mismatched types
+}
+
+fn d() {
+    let _x: bool = 0u32; //~ ERROR mismatched types
+}
+
+fn main() {
+    a();
+    b();
+    c();
+    d();
+}
@@ -0,0 +1,38 @@
+// build-fail
+// compile-flags: -Zpolymorphize
+#![feature(rustc_attrs)]
+
+// This test checks that the polymorphization analysis correctly detects unused type
+// parameters in closures, and that closures which don't use the parameters of their parent
+// don't make them used.
+
+// Neither the function nor its closure use the generic parameter.
+#[rustc_polymorphize_error]
+pub fn unused<T>() -> u32 {
+    //~^ ERROR item has unused generic parameters
+    let add_one = |x: u32| x + 1;
+    //~^ ERROR item has unused generic parameters
+    add_one(3)
+}
+
+// Closure uses the generic parameter of its parent.
+#[rustc_polymorphize_error]
+pub fn used_by_closure<T: Default>() -> T {
+    let make = || T::default();
+    make()
+}
+
+// Function uses its generic parameter, its closure doesn't.
+#[rustc_polymorphize_error]
+pub fn used_by_parent<T>() -> u32 {
+    let _: Option<T> = None;
+    let add_one = |x: u32| x + 1;
+    //~^ ERROR item has unused generic parameters
+    add_one(3)
+}
+
+fn main() {
+    unused::<u32>();
+    used_by_closure::<u32>();
+    used_by_parent::<u32>();
+}
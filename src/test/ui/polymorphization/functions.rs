@@ -0,0 +1,54 @@
+// build-fail
+// compile-flags: -Zpolymorphize
+#![feature(rustc_attrs)]
+
+// This test checks that the polymorphization analysis correctly detects unused type
+// parameters in functions.
+
+// Function doesn't have any generic parameters to be unused.
+#[rustc_polymorphize_error]
+pub fn no_parameters() {}
+
+// Function has an unused generic parameter.
+#[rustc_polymorphize_error]
+pub fn unused<T>() {
+    //~^ ERROR item has unused generic parameters
+}
+
+// Function uses generic parameter in value of a binding.
+#[rustc_polymorphize_error]
+pub fn used_binding_value<T: Default>() {
+    let _: T = Default::default();
+}
+
+// Function uses generic parameter in type of a binding.
+#[rustc_polymorphize_error]
+pub fn used_binding_type<T>() {
+    let _: Option<T> = None;
+}
+
+// Function uses generic parameter in argument.
+#[rustc_polymorphize_error]
+pub fn used_argument<T>(_: T) {}
+
+// Function uses generic parameter in substitutions to another function.
+#[rustc_polymorphize_error]
+pub fn used_substs<T>() {
+    unused::<T>()
+}
+
+// Function uses one of its two generic parameters.
+#[rustc_polymorphize_error]
+pub fn partially_used<A, B>(_: A) {
+    //~^ ERROR item has unused generic parameters
+}
+
+fn main() {
+    no_parameters();
+    unused::<u32>();
+    used_binding_value::<u32>();
+    used_binding_type::<u32>();
+    used_argument(3u32);
+    used_substs::<u32>();
+    partially_used::<u32, u64>(3u32);
+}
@@ -0,0 +1,41 @@
+// build-fail
+// compile-flags: -Zpolymorphize
+#![feature(rustc_attrs)]
+
+// This test checks that generic parameters which are only used through `PhantomData` are
+// considered unused, unless the `PhantomData` is passed on as a generic argument.
+
+use std::marker::PhantomData;
+
+// Function only uses its generic parameter in the type of a `PhantomData` binding.
+#[rustc_polymorphize_error]
+pub fn unused_phantom<T>() {
+    //~^ ERROR item has unused generic parameters
+    let _x: PhantomData<T> = PhantomData;
+}
+
+// Function only uses its generic parameter in a tuple of `PhantomData`.
+#[rustc_polymorphize_error]
+pub fn unused_phantom_tuple<T>() {
+    //~^ ERROR item has unused generic parameters
+    let _x: (PhantomData<T>, u32) = (PhantomData, 3);
+}
+
+// Function passes `PhantomData<T>` as a generic argument of another type.
+#[rustc_polymorphize_error]
+pub fn used_phantom_substs<T>() {
+    let _x: Vec<PhantomData<T>> = Vec::new();
+}
+
+// Function passes `PhantomData<T>` as a generic argument of another function.
+#[rustc_polymorphize_error]
+pub fn used_phantom_call<T>() {
+    std::mem::drop::<PhantomData<T>>(PhantomData);
+}
+
+fn main() {
+    unused_phantom::<u32>();
+    unused_phantom_tuple::<u32>();
+    used_phantom_substs::<u32>();
+    used_phantom_call::<u32>();
+}
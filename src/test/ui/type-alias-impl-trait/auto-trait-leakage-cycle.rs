@@ -0,0 +1,22 @@
+// Checks that a cycle caused by checking whether a type alias `impl Trait` implements an auto
+// trait is explained, and doesn't name the module containing the alias as defining it.
+
+#![feature(type_alias_impl_trait)]
+
+type A = impl Clone;
+type B = impl Clone;
+
+fn send<T: Send>(_: T) {}
+
+fn a() -> A {
+    //~^ ERROR cycle detected
+    send(b());
+    1u32
+}
+
+fn b() -> B {
+    send(a());
+    2u32
+}
+
+fn main() {}
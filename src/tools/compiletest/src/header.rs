@@ -43,6 +43,7 @@ impl EarlyProps {
         let mut props = EarlyProps::default();
         let rustc_has_profiler_support = env::var_os("RUSTC_PROFILER_SUPPORT").is_some();
         let rustc_has_sanitizer_support = env::var_os("RUSTC_SANITIZER_SUPPORT").is_some();
+        let rustc_is_parallel = env::var_os("RUSTC_PARALLEL_COMPILER").is_some();
 
         iter_header(testfile, None, rdr, &mut |ln| {
             // we should check if any only-<platform> exists and if it exists
@@ -78,6 +79,10 @@ impl EarlyProps {
                     props.ignore = true;
                 }
 
+                if !rustc_is_parallel && config.parse_needs_parallel_compiler(ln) {
+                    props.ignore = true;
+                }
+
                 if config.target == "wasm32-unknown-unknown" && config.parse_check_run_results(ln) {
                     props.ignore = true;
                 }
@@ -833,6 +838,10 @@ impl Config {
         self.parse_name_directive(line, "needs-sanitizer-support")
     }
 
+    fn parse_needs_parallel_compiler(&self, line: &str) -> bool {
+        self.parse_name_directive(line, "needs-parallel-compiler")
+    }
+
     /// Parses a name-value directive which contains config-specific information, e.g., `ignore-x86`
     /// or `normalize-stderr-32bit`.
     fn parse_cfg_name_directive(&self, line: &str, prefix: &str) -> ParsedNameDirective {